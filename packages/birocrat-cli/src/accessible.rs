@@ -0,0 +1,362 @@
+use crate::{error::Error, utils::option_items};
+use birocrat::SelectOption;
+use dialoguer::{console::Term, Password};
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+// These are the plain equivalents of the prompts in `utils`, which are used in accessible mode.
+// Rather than redrawing widgets in place, they only ever print whole lines and read whole lines,
+// which screen readers can follow, and they always say what the user chose. Options are numbered
+// and paged, so the user can type the number of the one they want.
+
+/// Prints the given prompt and reads a line of input, without its trailing newline.
+fn read_line(prompt: &str) -> Result<String, Error> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{prompt}: ").map_err(|err| Error::ReadInputFailed { source: err })?;
+    stderr
+        .flush()
+        .map_err(|err| Error::ReadInputFailed { source: err })?;
+
+    let mut line = String::new();
+    let num_read = io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|err| Error::ReadInputFailed { source: err })?;
+    // Otherwise, we'd ask forever
+    if num_read == 0 {
+        return Err(Error::ReadInputFailed {
+            source: io::ErrorKind::UnexpectedEof.into(),
+        });
+    }
+    let line = line.strip_suffix('\n').unwrap_or(&line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    Ok(line.to_string())
+}
+
+/// Reads a single-line input, using the default if the user doesn't enter anything.
+pub fn read_simple(prompt: &str, default: Option<String>) -> Result<String, Error> {
+    let input = match &default {
+        Some(default) => read_line(&format!("{prompt} (default: {default})"))?,
+        None => read_line(prompt)?,
+    };
+    let input = match default {
+        Some(default) if input.is_empty() => default,
+        _ => input,
+    };
+    eprintln!("Answer: {input}");
+
+    Ok(input)
+}
+
+/// Prints the given information and waits for the user to press Enter.
+pub fn acknowledge(text: &str) -> Result<(), Error> {
+    eprintln!("{text}");
+    read_line("Press Enter to continue")?;
+
+    Ok(())
+}
+
+/// Asks a yes-or-no question, asking again until the user answers it. If they don't enter
+/// anything, the answer will be no.
+pub fn confirm(prompt: &str) -> Result<bool, Error> {
+    let answer = loop {
+        match read_line(&format!("{prompt} (y/n, default: n)"))?
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "y" | "yes" => break true,
+            "" | "n" | "no" => break false,
+            _ => eprintln!("Please enter 'y' or 'n'."),
+        }
+    };
+    eprintln!("Answer: {}", if answer { "yes" } else { "no" });
+
+    Ok(answer)
+}
+
+/// Reads paths to existing files, asking again until each path the user gives exists. If
+/// `multiple` is set, the user can give as many paths as they like, finishing with an empty input.
+pub fn read_paths(prompt: &str, multiple: bool) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = Vec::new();
+    loop {
+        let can_finish = multiple && !paths.is_empty();
+        let input = if can_finish {
+            read_line(&format!("{prompt} (leave empty to finish)"))?
+        } else {
+            read_line(prompt)?
+        };
+        if input.is_empty() && can_finish {
+            break;
+        }
+        if !Path::new(&input).is_file() {
+            eprintln!("No file found at '{input}'.");
+            continue;
+        }
+        eprintln!("Answer: {input}");
+        paths.push(PathBuf::from(input));
+        if !multiple {
+            break;
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Reads a single-line secret input, without showing what the user types. Hiding input needs the
+/// terminal's help, so this still goes through `dialoguer`, but its password prompt is just a
+/// plain line.
+pub fn read_secret(prompt: &str) -> Result<String, Error> {
+    let input = Password::new()
+        .with_prompt(prompt)
+        .report(false)
+        .interact()?;
+    eprintln!("Answer received.");
+
+    Ok(input)
+}
+
+/// Reads a multi-line input, line by line, until the user enters a line with just a full stop. If
+/// they don't enter anything before that, the starter text will be used.
+pub fn read_multiple(prompt: &str, starter: &str) -> Result<String, Error> {
+    eprintln!("{prompt}");
+    eprintln!(
+        "Enter your answer over as many lines as you like, then a line with just a full stop to \
+        finish."
+    );
+    if !starter.is_empty() {
+        eprintln!("To keep the current answer, enter just a full stop. The current answer is:");
+        eprintln!("{starter}");
+    }
+
+    let mut lines = Vec::new();
+    loop {
+        let line = read_line("Line")?;
+        if line == "." {
+            break;
+        }
+        lines.push(line);
+    }
+    let input = if lines.is_empty() {
+        starter.to_string()
+    } else {
+        lines.join("\n").trim().to_string()
+    };
+    eprintln!("Answer: {input}");
+
+    Ok(input)
+}
+
+/// Lists the given options with numbers, a page at a time, and asks the user to choose one by
+/// number. If a default is given, it will be chosen if the user doesn't enter anything.
+pub fn select_one<'o>(
+    prompt: &str,
+    options: &'o [SelectOption],
+    default: Option<&str>,
+    page_size: Option<usize>,
+) -> Result<&'o SelectOption, Error> {
+    let default_idx = default.and_then(|default| options.iter().position(|o| o.value == default));
+    let mut pages = Pages::new(options, page_size);
+    // Start on the page with the default, so the user can see it
+    if let Some(idx) = default_idx {
+        pages.page = idx / pages.page_size;
+    }
+    let number_prompt = match default_idx {
+        Some(idx) => format!(
+            "Enter a number from 1 to {} (default: {})",
+            options.len(),
+            idx + 1
+        ),
+        None => format!("Enter a number from 1 to {}", options.len()),
+    };
+
+    eprintln!("{prompt}");
+    pages.print();
+    let selection = loop {
+        let input = read_line(&pages.with_hint(&number_prompt))?;
+        if pages.turn(&input) {
+            pages.print();
+            continue;
+        }
+        if let (true, Some(idx)) = (input.trim().is_empty(), default_idx) {
+            break idx;
+        }
+        match parse_option_number(&input, options.len()) {
+            Some(idx) => break idx,
+            None => eprintln!("Please enter a number from 1 to {}.", options.len()),
+        }
+    };
+    eprintln!("Chosen: {}", options[selection].label());
+
+    Ok(&options[selection])
+}
+
+/// Lists the given options with numbers, a page at a time, and asks the user to choose any number
+/// of them by number. If the user doesn't enter anything, the options with the given default values
+/// will be chosen.
+pub fn select_multiple<'o>(
+    prompt: &str,
+    options: &'o [SelectOption],
+    defaults: &[&str],
+    page_size: Option<usize>,
+) -> Result<Vec<&'o SelectOption>, Error> {
+    let mut pages = Pages::new(options, page_size);
+    let default_idxs = options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| defaults.contains(&option.value.as_str()))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let number_prompt = if default_idxs.is_empty() {
+        "Enter the numbers of your choices, separated by commas (or leave empty for none)"
+            .to_string()
+    } else {
+        format!(
+            "Enter the numbers of your choices, separated by commas (default: {})",
+            default_idxs
+                .iter()
+                .map(|idx| (idx + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+
+    eprintln!("{prompt}");
+    pages.print();
+    let selections = loop {
+        let input = read_line(&pages.with_hint(&number_prompt))?;
+        if pages.turn(&input) {
+            pages.print();
+            continue;
+        }
+        if input.trim().is_empty() {
+            break default_idxs;
+        }
+        let selections = input
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| parse_option_number(part, options.len()))
+            .collect::<Option<Vec<_>>>();
+        match selections {
+            Some(mut selections) => {
+                selections.sort_unstable();
+                selections.dedup();
+                break selections;
+            }
+            None => eprintln!("Please only enter numbers from 1 to {}.", options.len()),
+        }
+    };
+    let chosen = selections
+        .into_iter()
+        .map(|idx| &options[idx])
+        .collect::<Vec<_>>();
+    if chosen.is_empty() {
+        eprintln!("Chosen: nothing");
+    } else {
+        eprintln!(
+            "Chosen: {}",
+            chosen
+                .iter()
+                .map(|option| option.label())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(chosen)
+}
+
+/// The number of options to show at once if the user doesn't say otherwise.
+const DEFAULT_PAGE_SIZE: usize = 30;
+/// The width to lay options out in if we can't tell how wide the terminal is.
+const DEFAULT_WIDTH: usize = 80;
+
+/// A list of options for the user to choose from, split into pages so long lists don't scroll the
+/// prompt off the screen. The user can type the number of any option, whichever page it's on.
+struct Pages {
+    /// The text for each option, with its number.
+    items: Vec<String>,
+    /// How many options to show on each page.
+    page_size: usize,
+    /// The page currently being shown, counting from 0.
+    page: usize,
+}
+impl Pages {
+    fn new(options: &[SelectOption], page_size: Option<usize>) -> Self {
+        let items = option_items(options)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| format!("{}. {item}", idx + 1))
+            .collect();
+        Self {
+            items,
+            page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1),
+            page: 0,
+        }
+    }
+    fn num_pages(&self) -> usize {
+        self.items.len().div_ceil(self.page_size).max(1)
+    }
+    /// Adds instructions for turning the page to the given prompt, if there's more than one page.
+    fn with_hint(&self, prompt: &str) -> String {
+        if self.num_pages() > 1 {
+            format!("{prompt}, or 'n'/'p' for the next/previous page")
+        } else {
+            prompt.to_string()
+        }
+    }
+    /// Turns the page if the user asked to, returning whether or not they did.
+    fn turn(&mut self, input: &str) -> bool {
+        if self.num_pages() == 1 {
+            return false;
+        }
+        match input.trim() {
+            "n" => self.page = (self.page + 1) % self.num_pages(),
+            "p" => self.page = (self.page + self.num_pages() - 1) % self.num_pages(),
+            _ => return false,
+        }
+        true
+    }
+    /// Prints the current page of options, laid out in as many columns as will fit in the
+    /// terminal. Options run down each column before moving on to the next, so they can still be
+    /// read in order.
+    fn print(&self) {
+        let start = self.page * self.page_size;
+        let items = &self.items[start..(start + self.page_size).min(self.items.len())];
+        if self.num_pages() > 1 {
+            eprintln!("Page {} of {}:", self.page + 1, self.num_pages());
+        }
+
+        let term_width = Term::stderr()
+            .size_checked()
+            .map(|(_, cols)| cols as usize)
+            .unwrap_or(DEFAULT_WIDTH);
+        let col_width = items
+            .iter()
+            .map(|item| item.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 3;
+        let num_cols = (term_width / col_width).clamp(1, items.len().max(1));
+        let num_rows = items.len().div_ceil(num_cols);
+        for row in 0..num_rows {
+            let line = (row..items.len())
+                .step_by(num_rows)
+                .map(|idx| format!("{:<col_width$}", items[idx]))
+                .collect::<String>();
+            eprintln!("{}", line.trim_end());
+        }
+    }
+}
+
+/// Parses the number of an option the user entered (counting from 1) into its index, if it's valid.
+fn parse_option_number(input: &str, num_options: usize) -> Option<usize> {
+    match input.trim().parse::<usize>() {
+        Ok(number) if (1..=num_options).contains(&number) => Some(number - 1),
+        _ => None,
+    }
+}
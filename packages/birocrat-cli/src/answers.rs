@@ -0,0 +1,169 @@
+use crate::error::Error;
+use birocrat::{Answer, Form, Question};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Answers provided ahead of time in a file.
+#[derive(Default)]
+pub struct PresetAnswers {
+    /// The answers, keyed by question ID, with any `env:` or `file:` references already resolved.
+    pub answers: HashMap<String, Answer>,
+    /// The raw references that answers were resolved from, keyed by question ID. These are kept
+    /// so we can write them back out instead of the (probably secret) values they resolved to.
+    pub references: HashMap<String, String>,
+}
+
+/// Reads a set of answers from the given JSON file. This should be an object of question IDs to
+/// answers, where each answer is either a string (for text questions), an array of strings (for
+/// select questions, with `[option, details]` pairs for options that need details), or an object
+/// of these (for pages). Selections with follow-ups are objects with the selection as `answer` and
+/// the follow-up's answer as `followup`. Not every question needs to be answered.
+///
+/// String answers of the form `env:VAR` will be read from the environment variable `VAR`, and
+/// those of the form `file:path` will be read from the file at `path`, so secrets never need to be
+/// stored in answers files.
+pub fn read_answers(path: &Path) -> Result<PresetAnswers, Error> {
+    let contents = fs::read_to_string(path).map_err(|err| Error::ReadAnswersFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })?;
+    let answers: Value =
+        serde_json::from_str(&contents).map_err(|err| Error::ParseAnswersFailed {
+            source: err,
+            target: path.to_path_buf(),
+        })?;
+    parse_answers(answers)
+}
+
+/// Parses a set of answers from a JSON value, in the same format [`read_answers`] accepts.
+pub fn parse_answers(answers: Value) -> Result<PresetAnswers, Error> {
+    let answers = match answers {
+        Value::Object(map) => map,
+        _ => return Err(Error::AnswersNotObject),
+    };
+
+    let mut preset_answers = PresetAnswers::default();
+    for (id, answer) in answers {
+        let answer = match answer {
+            Value::String(text) => match resolve_reference(&id, &text)? {
+                Some(resolved) => {
+                    preset_answers.references.insert(id.clone(), text);
+                    Answer::Text(resolved)
+                }
+                None => Answer::Text(text),
+            },
+            answer => {
+                Answer::from_json(answer).ok_or(Error::InvalidAnswerValue { id: id.clone() })?
+            }
+        };
+        preset_answers.answers.insert(id, answer);
+    }
+
+    Ok(preset_answers)
+}
+
+/// Writes every answer given in the form so far to the given path as JSON, in the same format
+/// that [`read_answers`] accepts. Any answers that were resolved from references will have those
+/// references written instead, and answers to secret questions (including those tagged as
+/// secret) that weren't resolved from references will be left out entirely, as will computed steps (which the form works out again
+/// itself).
+pub fn write_answers(
+    form: &Form,
+    references: &HashMap<String, String>,
+    path: &Path,
+) -> Result<(), Error> {
+    let answers = form
+        .answers()
+        .filter_map(|(id, question, answer)| {
+            if let Some(reference) = references.get(id) {
+                Some((id.to_string(), Value::String(reference.clone())))
+            // Pages and selections with follow-ups can't be answered without all their answers,
+            // so we leave them out entirely if there's a secret in them
+            } else if question.is_secret() || matches!(question, Question::Computed { .. }) {
+                None
+            } else {
+                Some((id.to_string(), answer.to_json()))
+            }
+        })
+        .collect::<serde_json::Map<_, _>>();
+    // This is already a `Value`, so serializing it can't fail
+    let answers_str = serde_json::to_string_pretty(&Value::Object(answers)).unwrap();
+
+    fs::write(path, answers_str).map_err(|err| Error::WriteAnswersFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })
+}
+
+/// Gets the answers given in the form so far as JSON values keyed by question ID, leaving out any
+/// answers to secret questions (including those tagged as secret).
+pub fn answer_values(form: &Form) -> serde_json::Map<String, Value> {
+    form.answers()
+        .filter(|(_, question, _)| !question.is_secret())
+        .map(|(id, _, answer)| (id.to_string(), answer.to_json()))
+        .collect()
+}
+
+/// Resolves an `env:` or `file:` reference in a string answer for the question with the given ID.
+/// If the answer isn't a reference, this will return `None`.
+fn resolve_reference(id: &str, text: &str) -> Result<Option<String>, Error> {
+    if let Some(var) = text.strip_prefix("env:") {
+        let value = std::env::var(var).map_err(|_| Error::AnswerEnvVarMissing {
+            id: id.to_string(),
+            var: var.to_string(),
+        })?;
+        Ok(Some(value))
+    } else if let Some(file) = text.strip_prefix("file:") {
+        let value = fs::read_to_string(file).map_err(|err| Error::ReadAnswerFileFailed {
+            source: err,
+            id: id.to_string(),
+            target: file.into(),
+        })?;
+        // Files almost always end with a newline that isn't part of the secret
+        let value = value.strip_suffix('\n').unwrap_or(&value);
+        let value = value.strip_suffix('\r').unwrap_or(value);
+        Ok(Some(value.to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::Lua;
+    use serde_json::json;
+
+    static TAGGED_SCRIPT: &str = r#"
+        function Main(state, answer, params)
+            if state == nil then
+                return { "question", { id = "name", type = "simple", text = "Name?" }, {} }
+            elseif state.name == nil then
+                return {
+                    "question",
+                    { id = "diagnosis", type = "simple", text = "Diagnosis?", tags = { "secret" } },
+                    { name = answer.text },
+                }
+            else
+                return { "done", {}, nil }
+            end
+        end
+    "#;
+
+    #[test]
+    fn should_leave_out_tagged_secrets() {
+        let vm = Lua::new();
+        let mut form = Form::new(TAGGED_SCRIPT, json!({}), &vm).unwrap();
+        form.progress_with_answer(0, Answer::Text("Alex".to_string()))
+            .unwrap();
+        form.progress_with_answer(1, Answer::Text("Flu".to_string()))
+            .unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("birocrat-answers-{}.json", std::process::id()));
+        write_answers(&form, &HashMap::new(), &path).unwrap();
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written, json!({ "name": "Alex" }));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    answers::{self, PresetAnswers},
+    cli::BatchArgs,
+    error::Error,
+    headless::run_headless,
+    template::render_path_template,
+    utils,
+};
+use birocrat::Answer;
+use fmterr::fmterr;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Runs a form once for every set of parameters in a JSON Lines file, answering its questions
+/// from an answers file, and writing each output to its own file in the output directory.
+pub fn batch(args: BatchArgs, forms_dir: Option<&Path>) -> Result<(), Error> {
+    let script = utils::read_script(&args.script, forms_dir)?;
+    let params_list =
+        fs::read_to_string(&args.params_list).map_err(|err| Error::ReadParamsListFailed {
+            source: err,
+            target: args.params_list.clone(),
+        })?;
+    let PresetAnswers { answers, .. } = match &args.answers {
+        Some(path) => answers::read_answers(path)?,
+        None => PresetAnswers::default(),
+    };
+
+    let mut succeeded = 0;
+    let mut failed = 0;
+    // Blank lines are ignored, but we still number runs by their line in the file so failures are
+    // easy to find
+    for (line_idx, line) in params_list.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_num = line_idx + 1;
+
+        match run_one(&script, line, line_num, &answers, &args) {
+            Ok(path) => {
+                succeeded += 1;
+                eprintln!("line {line_num} ... ok (written to {path:?})");
+            }
+            Err(message) => {
+                failed += 1;
+                eprintln!("line {line_num} ... FAILED");
+                eprintln!("    {message}");
+            }
+        }
+    }
+
+    eprintln!("\nbatch result: {succeeded} succeeded; {failed} failed");
+    if failed > 0 {
+        Err(Error::BatchFailed { failed })
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs the form with the parameters on a single line of the parameters file, writing its output
+/// and returning where it was written to. If anything goes wrong, this returns a description of
+/// the failure.
+fn run_one(
+    script: &str,
+    line: &str,
+    line_num: usize,
+    answers: &HashMap<String, Answer>,
+    args: &BatchArgs,
+) -> Result<PathBuf, String> {
+    let params: Value =
+        serde_json::from_str(line).map_err(|err| format!("failed to parse parameters: {err}"))?;
+    // Every run makes the same random choices, so outputs only differ by their parameters
+    let output = match run_headless(script, &params, 0, &HashMap::new(), answers) {
+        Ok(Ok(output)) => output,
+        Ok(Err(failure)) => return Err(failure.to_string()),
+        Err(err) => return Err(fmterr(&err)),
+    };
+
+    let file_name = match &args.name {
+        // Parameters are available to the template as well, as they're often the most natural
+        // way to distinguish runs
+        Some(template) => {
+            let params = match &params {
+                Value::Object(params) => params.clone(),
+                _ => serde_json::Map::new(),
+            };
+            render_path_template(template, &output, &params).map_err(|err| fmterr(&err))?
+        }
+        None => format!("{line_num}.json").into(),
+    };
+    let path = args.out_dir.join(file_name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create output directory {parent:?}: {err}"))?;
+    }
+    // This is already a `Value`, so serializing it can't fail
+    let output_str = serde_json::to_string(&output).unwrap();
+    fs::write(&path, output_str)
+        .map_err(|err| format!("failed to write output to {path:?}: {err}"))?;
+
+    Ok(path)
+}
@@ -1,18 +1,175 @@
-use clap::{Args, Parser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 /// birocrat-cli lets you run complex forms powered by Lua in your terminal!
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
-    /// Path to a Lua script that drives the form (if `-`, this will read from stdin)
+    #[command(subcommand)]
+    pub command: Command,
+    /// A directory of form scripts, which can then be referred to by name (e.g. `my-form` for
+    /// `my-form.lua`)
+    #[arg(long, env = "BIROCRAT_FORMS_DIR", global = true)]
+    pub forms_dir: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Runs a form interactively in the terminal
+    Run(RunArgs),
+    /// Runs a series of test scenarios against a form, checking their outputs
+    Test(TestArgs),
+    /// Runs a form once for each set of parameters in a file, without any user interaction
+    Batch(BatchArgs),
+    /// Shows the differences between two form outputs (or saved answers)
+    Diff(DiffArgs),
+    /// Creates a new form script, asking some questions to get you started
+    New(NewArgs),
+    /// Prints a shell script that sets up completions for this program
+    Completions(CompletionsArgs),
+    /// Prints completion candidates for the word at the given index (used by completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Path to a Lua script that drives the form, or the name of a form in the forms directory (if
+    /// `-`, this will read from stdin)
     pub script: String,
     /// Arbitrary parameters to go to the form
     #[command(flatten)]
     pub params: ParamsArgs,
-    /// Where to put the JSON output [default: stdout]
+    /// Where to put the JSON output, which may be a template like `configs/{{env}}/{{name}}.json`,
+    /// filled in from the output and answers [default: stdout]
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// The path to a JSON file of answers to use, keyed by question ID (any questions not
+    /// answered in here will be asked interactively)
+    #[arg(short, long)]
+    pub answers: Option<PathBuf>,
+    /// Where to write the full set of answers given once the form is complete
+    #[arg(long)]
+    pub save_answers: Option<PathBuf>,
+    /// The most errors to allow in a row when answering the same question before giving up
+    /// [default: no limit]
+    #[arg(long)]
+    pub max_errors: Option<usize>,
+    /// The seed for anything random the form does, to replay an earlier run exactly (the seed of
+    /// each run is logged) [default: a new one each time]
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// The variant of an experiment the form runs to use (`experiment=variant`), rather than one
+    /// chosen from the seed (the variants each run was given are logged)
+    #[arg(long = "variant", value_parser = parse_variant)]
+    pub variants: Vec<(String, String)>,
+    /// The most options to show at once in select questions, with the rest split into pages
+    /// [default: as many as fit in the terminal, or 30 with `--accessible`]
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub page_size: Option<usize>,
+    /// Print how long each call to the script takes
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Where to write a log of what happens while the form runs (including script output), as JSON
+    /// lines, with secrets redacted
+    #[arg(long)]
+    pub log_file: Option<PathBuf>,
+    /// Ask questions with plain, numbered prompts that work with screen readers, rather than
+    /// interactive widgets
+    #[arg(long)]
+    pub accessible: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TestArgs {
+    /// Path to the Lua script to test, or the name of a form in the forms directory (if `-`, this
+    /// will read from stdin)
+    pub script: String,
+    /// Path to a JSON file containing an array of test scenarios
+    pub scenarios: PathBuf,
+    /// Produce a machine-readable report of the results in this format
+    #[arg(short, long, value_enum)]
+    pub report: Option<ReportFormat>,
+    /// Where to write the report [default: stdout]
+    #[arg(long, requires = "report")]
+    pub report_file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Path to a Lua script that drives the form, or the name of a form in the forms directory (if
+    /// `-`, this will read from stdin)
+    pub script: String,
+    /// Path to a JSON Lines file with one object of parameters per line (the form will be run
+    /// once for each)
+    #[arg(short, long)]
+    pub params_list: PathBuf,
+    /// The path to a JSON file of answers to give every run, keyed by question ID
+    #[arg(short, long)]
+    pub answers: Option<PathBuf>,
+    /// The directory to write outputs to
     #[arg(short, long)]
-    pub output: Option<PathBuf>,
+    pub out_dir: PathBuf,
+    /// A template for the name of each output file, like `{{service}}.json`, filled in from the
+    /// output and parameters of each run [default: the line number of the parameters]
+    #[arg(short, long)]
+    pub name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Path to the old JSON output (or saved answers)
+    pub old: PathBuf,
+    /// Path to the new JSON output (or saved answers)
+    pub new: PathBuf,
+    /// Path to the Lua script that produced the outputs, or the name of a form in the forms
+    /// directory, whose metadata will be used to label the differences
+    #[arg(short, long)]
+    pub script: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// Where to create the new script
+    pub path: PathBuf,
+    /// Overwrite the file if it already exists
+    #[arg(short, long)]
+    pub force: bool,
+    /// Ask questions with plain, numbered prompts that work with screen readers, rather than
+    /// interactive widgets
+    #[arg(long)]
+    pub accessible: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// The shell to generate completions for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Args, Debug)]
+pub struct CompleteArgs {
+    /// The index of the word being completed
+    pub index: usize,
+    /// All the words on the command line, including the program name
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub words: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ReportFormat {
+    /// JUnit XML, as understood by most CI systems and test dashboards
+    Junit,
+    /// A JSON object with details of every scenario
+    Json,
 }
 
 #[derive(Args, Debug)]
@@ -25,3 +182,13 @@ pub struct ParamsArgs {
     #[arg(short = 'j', long = "json-params")]
     pub json_params: Option<PathBuf>,
 }
+
+/// Parses an `experiment=variant` pair given to `--variant`.
+fn parse_variant(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((experiment, variant)) if !experiment.is_empty() && !variant.is_empty() => {
+            Ok((experiment.to_string(), variant.to_string()))
+        }
+        _ => Err(format!("expected `experiment=variant`, found '{arg}'")),
+    }
+}
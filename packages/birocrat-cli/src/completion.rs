@@ -0,0 +1,179 @@
+use crate::{
+    cli::{CompleteArgs, Shell},
+    utils,
+};
+use birocrat::FormMeta;
+use mlua::Lua;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The name of this program, as the shell knows it.
+static BIN_NAME: &str = env!("CARGO_BIN_NAME");
+/// The subcommands users can complete.
+static SUBCOMMANDS: &[&str] = &["run", "test", "batch", "diff", "new", "completions"];
+/// Options that take a value, which we need to skip over when working out which positional
+/// argument is being completed.
+static VALUE_OPTIONS: &[&str] = &[
+    "-p",
+    "--param",
+    "-j",
+    "--json-params",
+    "-o",
+    "--output",
+    "-a",
+    "--answers",
+    "--save-answers",
+    "-r",
+    "--report",
+    "--report-file",
+    "--params-list",
+    "--out-dir",
+    "-n",
+    "--name",
+    "-s",
+    "--script",
+    "--forms-dir",
+];
+
+/// Prints a script for the given shell that will set up completions for this program. Rather than
+/// being static, these call back into this program (through the hidden `__complete` subcommand)
+/// so they can complete form names from the forms directory and parameters from each form's
+/// metadata.
+pub fn print_completion_script(shell: Shell) {
+    let fn_name = format!("_{}", BIN_NAME.replace('-', "_"));
+    let script = match shell {
+        Shell::Bash => format!(
+            r#"{fn_name}() {{
+    local IFS=$'\n'
+    COMPREPLY=($({BIN_NAME} __complete "$COMP_CWORD" "${{COMP_WORDS[@]}}" 2>/dev/null))
+    if [[ "${{COMPREPLY[0]}}" == *= ]]; then
+        compopt -o nospace
+    fi
+}}
+complete -o default -F {fn_name} {BIN_NAME}"#
+        ),
+        Shell::Zsh => format!(
+            r#"#compdef {BIN_NAME}
+{fn_name}() {{
+    local -a candidates
+    candidates=("${{(@f)$({BIN_NAME} __complete $((CURRENT - 1)) "${{words[@]}}" 2>/dev/null)}}")
+    candidates=(${{candidates:#}})
+    if (( ${{#candidates}} == 0 )); then
+        _files
+        return
+    fi
+    local candidate
+    for candidate in $candidates; do
+        if [[ $candidate == *= ]]; then
+            compadd -S '' -- $candidate
+        else
+            compadd -- $candidate
+        fi
+    done
+}}
+compdef {fn_name} {BIN_NAME}"#
+        ),
+        Shell::Fish => format!(
+            r#"function {fn_name}_complete
+    set -l tokens (commandline -opc)
+    {BIN_NAME} __complete (count $tokens) $tokens (commandline -ct) 2>/dev/null
+end
+complete -c {BIN_NAME} -a '({fn_name}_complete)'"#
+        ),
+    };
+
+    println!("{script}");
+}
+
+/// Prints completion candidates for the word at the given index, one per line. If we don't have
+/// anything useful to suggest, this prints nothing, and the shell will fall back to completing
+/// file paths.
+pub fn complete(args: CompleteArgs, forms_dir: Option<&Path>) {
+    let words = args.words;
+    let current = words.get(args.index).map(|w| w.as_str()).unwrap_or("");
+    // The user may have given a forms directory on the command line, which overrides any default
+    let forms_dir = words
+        .iter()
+        .position(|w| w == "--forms-dir")
+        .and_then(|i| words.get(i + 1))
+        .map(PathBuf::from)
+        .or_else(|| forms_dir.map(|dir| dir.to_path_buf()));
+
+    // Work out the subcommand and the positional arguments given to it (other than the word being
+    // completed)
+    let mut subcommand = None;
+    let mut positionals = Vec::new();
+    let mut skip_next = false;
+    for (i, word) in words.iter().enumerate().skip(1) {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if VALUE_OPTIONS.contains(&word.as_str()) {
+            skip_next = true;
+            continue;
+        }
+        if word.starts_with('-') || i == args.index {
+            continue;
+        }
+        if subcommand.is_none() {
+            subcommand = Some(word.as_str());
+        } else {
+            positionals.push((i, word.as_str()));
+        }
+    }
+
+    let previous = args
+        .index
+        .checked_sub(1)
+        .and_then(|i| words.get(i))
+        .map(|w| w.as_str());
+    let candidates = match subcommand {
+        None => SUBCOMMANDS.iter().map(|s| s.to_string()).collect(),
+        Some("run") if matches!(previous, Some("-p" | "--param")) => {
+            // Complete the parameter keys the form declares
+            match positionals.first() {
+                Some((_, script)) => param_candidates(script, forms_dir.as_deref()),
+                None => Vec::new(),
+            }
+        }
+        Some("run" | "test" | "batch")
+            if !current.starts_with('-')
+                && !previous.is_some_and(|p| VALUE_OPTIONS.contains(&p))
+                && positionals.iter().all(|(i, _)| *i > args.index) =>
+        {
+            // This is the script argument, so complete form names
+            forms_dir
+                .as_deref()
+                .map(utils::list_forms)
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    };
+
+    for candidate in candidates {
+        if candidate.starts_with(current) {
+            println!("{candidate}");
+        }
+    }
+}
+
+/// Gets completion candidates for the parameters of the given form, based on its metadata. Any
+/// failures here will just lead to no candidates.
+fn param_candidates(script: &str, forms_dir: Option<&Path>) -> Vec<String> {
+    let path = utils::resolve_script_path(script, forms_dir);
+    let Ok(script) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let vm = Lua::new();
+    match FormMeta::from_script(&script, &vm) {
+        Ok(Some(meta)) => meta
+            .params
+            .into_iter()
+            .map(|param| format!("{}=", param.name))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{cli::DiffArgs, error::Error, utils};
+use birocrat::FormMeta;
+use mlua::Lua;
+use serde_json::Value;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+/// Shows the differences between two form outputs (or two sets of saved answers), labelling each
+/// one using the form's metadata if a script was given.
+pub fn diff(args: DiffArgs, forms_dir: Option<&Path>) -> Result<(), Error> {
+    let old = read_json(&args.old)?;
+    let new = read_json(&args.new)?;
+    let labels = match &args.script {
+        Some(script) => {
+            let script = utils::read_script(script, forms_dir)?;
+            let vm = Lua::new();
+            FormMeta::from_script(&script, &vm)?
+                .map(|meta| meta.labels)
+                .unwrap_or_default()
+        }
+        None => HashMap::new(),
+    };
+
+    let diffs = json_diff(&old, &new);
+    if diffs.is_empty() {
+        eprintln!("no differences");
+        return Ok(());
+    }
+    for diff in &diffs {
+        let change = match (&diff.old, &diff.new) {
+            (Some(old), Some(new)) => format!("{old} -> {new}"),
+            (Some(old), None) => format!("removed {old}"),
+            (None, Some(new)) => format!("added {new}"),
+            (None, None) => unreachable!(),
+        };
+        match label_for(&diff.path, &labels) {
+            Some(label) => println!("{label} ({}): {change}", diff.path),
+            None => println!("{}: {change}", diff.path),
+        }
+    }
+
+    Err(Error::OutputsDiffer { count: diffs.len() })
+}
+
+/// Finds the label for the given JSON pointer, falling back to the labels of its parents, so that
+/// a label for an array or object applies to everything in it. Labels are keyed by dotted paths,
+/// where top-level keys double as question IDs for saved answers.
+fn label_for<'l>(pointer: &str, labels: &'l HashMap<String, String>) -> Option<&'l str> {
+    let parts = pointer
+        .split('/')
+        .skip(1)
+        .map(|part| part.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>();
+    (1..=parts.len())
+        .rev()
+        .find_map(|len| labels.get(&parts[..len].join(".")))
+        .map(|label| label.as_str())
+}
+
+/// Reads a JSON value from the given file, for comparison.
+fn read_json(path: &Path) -> Result<Value, Error> {
+    let contents = fs::read_to_string(path).map_err(|err| Error::ReadDiffInputFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })?;
+    serde_json::from_str(&contents).map_err(|err| Error::ParseDiffInputFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })
+}
+
+/// A single difference between two JSON values.
+#[derive(Debug)]
+pub struct Difference {
+    /// The JSON pointer to the value that differs (e.g. `/spice_levels/1`).
+    pub path: String,
+    /// The value in the old object, if it was present.
+    pub old: Option<Value>,
+    /// The value in the new object, if it is present.
+    pub new: Option<Value>,
+}
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "{}: expected {old}, found {new}", self.path),
+            (Some(old), None) => write!(f, "{}: expected {old}, but was missing", self.path),
+            (None, Some(new)) => write!(f, "{}: unexpected {new}", self.path),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+/// Computes the differences between two JSON values, recursing into objects and arrays so each
+/// difference is reported at the deepest path possible.
+pub fn json_diff(old: &Value, new: &Value) -> Vec<Difference> {
+    let mut diffs = Vec::new();
+    diff_at(String::new(), old, new, &mut diffs);
+    diffs
+}
+
+fn diff_at(path: String, old: &Value, new: &Value, diffs: &mut Vec<Difference>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_val) in old {
+                let path = format!("{path}/{}", escape_pointer(key));
+                match new.get(key) {
+                    Some(new_val) => diff_at(path, old_val, new_val, diffs),
+                    None => diffs.push(Difference {
+                        path,
+                        old: Some(old_val.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_val) in new {
+                if !old.contains_key(key) {
+                    diffs.push(Difference {
+                        path: format!("{path}/{}", escape_pointer(key)),
+                        old: None,
+                        new: Some(new_val.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for i in 0..old.len().max(new.len()) {
+                let path = format!("{path}/{i}");
+                match (old.get(i), new.get(i)) {
+                    (Some(old_val), Some(new_val)) => diff_at(path, old_val, new_val, diffs),
+                    (old_val, new_val) => diffs.push(Difference {
+                        path,
+                        old: old_val.cloned(),
+                        new: new_val.cloned(),
+                    }),
+                }
+            }
+        }
+        (old, new) if old != new => diffs.push(Difference {
+            path: if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            },
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Escapes an object key for use in a JSON pointer, per RFC 6901.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_find_deepest_differences() {
+        let old = json!({ "name": "Alice", "spice": ["Mild", "Hot"], "a/b": 1, "gone": true });
+        let new = json!({ "name": "Alice", "spice": ["Mild"], "a/b": 2, "added": null });
+        let diffs = json_diff(&old, &new)
+            .into_iter()
+            .map(|diff| diff.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(diffs.len(), 4);
+        assert!(diffs.contains(&"/spice/1: expected \"Hot\", but was missing".to_string()));
+        assert!(diffs.contains(&"/a~1b: expected 1, found 2".to_string()));
+        assert!(diffs.contains(&"/gone: expected true, but was missing".to_string()));
+        assert!(diffs.contains(&"/added: unexpected null".to_string()));
+
+        assert!(json_diff(&old, &old).is_empty());
+        assert_eq!(json_diff(&json!(1), &json!(2))[0].path, "/");
+    }
+
+    #[test]
+    fn should_label_differences_from_parents() {
+        let labels = HashMap::from([
+            ("spice".to_string(), "Spice levels".to_string()),
+            ("a/b".to_string(), "Ratio".to_string()),
+            ("address.city".to_string(), "City".to_string()),
+        ]);
+        assert_eq!(label_for("/spice/1", &labels), Some("Spice levels"));
+        assert_eq!(label_for("/a~1b", &labels), Some("Ratio"));
+        assert_eq!(label_for("/address/city", &labels), Some("City"));
+        assert_eq!(label_for("/address/street", &labels), None);
+    }
+}
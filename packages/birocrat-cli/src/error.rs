@@ -3,11 +3,28 @@ use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error(transparent)]
     DialogueError(#[from] dialoguer::Error),
     #[error(transparent)]
     FormError(#[from] birocrat::error::Error),
+    #[error("failed to read input from terminal")]
+    ReadInputFailed {
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to create log file at '{target:?}'")]
+    CreateLogFileFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("failed to capture script output for log")]
+    CapturePrintFailed {
+        #[source]
+        source: mlua::Error,
+    },
     #[error("failed to read driver script for form")]
     ReadScriptFailed {
         #[source]
@@ -30,6 +47,103 @@ pub enum Error {
         source: serde_json::Error,
         target: String,
     },
+    #[error("failed to read answers from '{target:?}'")]
+    ReadAnswersFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("failed to parse answers from '{target:?}'")]
+    ParseAnswersFailed {
+        #[source]
+        source: serde_json::Error,
+        target: PathBuf,
+    },
+    #[error("expected answers file to contain an object of question ids to answers")]
+    AnswersNotObject,
+    #[error(
+        "invalid answer for question '{id}' in answers file (expected string or array of strings)"
+    )]
+    InvalidAnswerValue { id: String },
+    #[error("environment variable '{var}' referenced by answer to question '{id}' is not set")]
+    AnswerEnvVarMissing { id: String, var: String },
+    #[error("failed to read file '{target:?}' referenced by answer to question '{id}'")]
+    ReadAnswerFileFailed {
+        #[source]
+        source: std::io::Error,
+        id: String,
+        target: PathBuf,
+    },
+    #[error("failed to write answers to '{target:?}'")]
+    WriteAnswersFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("failed to read test scenarios from '{target:?}'")]
+    ReadScenariosFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("failed to parse test scenarios from '{target:?}' (expected an array of scenarios)")]
+    ParseScenariosFailed {
+        #[source]
+        source: serde_json::Error,
+        target: PathBuf,
+    },
+    #[error("failed to write test report to '{target:?}'")]
+    WriteReportFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("{failed} test scenario(s) failed")]
+    TestsFailed { failed: usize },
+    #[error("failed to read parameters list from '{target:?}'")]
+    ReadParamsListFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("{failed} batch run(s) failed")]
+    BatchFailed { failed: usize },
+    #[error("failed to read '{target:?}' for comparison")]
+    ReadDiffInputFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("failed to parse '{target:?}' as json for comparison")]
+    ParseDiffInputFailed {
+        #[source]
+        source: serde_json::Error,
+        target: PathBuf,
+    },
+    #[error("{count} difference(s) found")]
+    OutputsDiffer { count: usize },
+    #[error("'{target:?}' already exists (use `--force` to overwrite it)")]
+    ScaffoldTargetExists { target: PathBuf },
+    #[error("failed to write new form script to '{target:?}'")]
+    WriteScaffoldFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
+    #[error("unclosed `{{{{` in output path template '{template}'")]
+    OutputTemplateUnclosed { template: String },
+    #[error("no value for '{key}' in form output or answers to fill in output path template")]
+    OutputTemplateMissingValue { key: String },
+    #[error("value for '{key}' in output path template must be a string, number, boolean, or an array of one of those")]
+    OutputTemplateNonScalar { key: String },
+    #[error("value '{value}' for '{key}' is not safe to use in an output path (it must be a single, non-empty path component)")]
+    OutputTemplateUnsafeValue { key: String, value: String },
+    #[error("failed to create directory '{target:?}' for form output")]
+    CreateOutputDirFailed {
+        #[source]
+        source: std::io::Error,
+        target: PathBuf,
+    },
     #[error("failed to write form output to '{target:?}'")]
     WriteOutputFailed {
         #[source]
@@ -0,0 +1,95 @@
+use crate::error::Error;
+use birocrat::{fold_case_and_whitespace, Answer, Form, FormOptions, FormPoll, Question};
+use mlua::Lua;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The ways a form run without any user interaction can fail, other than the engine itself
+/// failing.
+#[derive(Debug)]
+pub enum HeadlessFailure {
+    /// The form asked a question for which no answer was provided.
+    MissingAnswer { id: String, prompt: String },
+    /// The script rejected the answer provided for a question.
+    Rejected { id: String, message: String },
+}
+impl std::fmt::Display for HeadlessFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeadlessFailure::MissingAnswer { id, prompt } => {
+                write!(f, "no answer provided for question '{id}' ({prompt})")
+            }
+            HeadlessFailure::Rejected { id, message } => {
+                write!(f, "script rejected answer to question '{id}': {message}")
+            }
+        }
+    }
+}
+
+/// Runs the given form script to completion without any user interaction, answering every
+/// question it asks from the given answers (keyed by question ID). If the form completes, this
+/// returns its output. Anything random the script does is derived from the given seed, so runs
+/// are reproducible, and any experiments it runs will use the given variants, keyed by experiment,
+/// if they're there.
+///
+/// This creates a fresh Lua VM for the form, so runs can't interfere with each other. Selections
+/// are matched to options ignoring case and whitespace.
+pub fn run_headless(
+    script: &str,
+    params: &Value,
+    seed: u64,
+    variants: &HashMap<String, String>,
+    answers: &HashMap<String, Answer>,
+) -> Result<Result<Value, HeadlessFailure>, Error> {
+    let vm = Lua::new();
+    // Answers here are typed out by hand, so we'll forgive trivial differences in selections
+    let options = FormOptions {
+        option_normalize: Some(fold_case_and_whitespace),
+        seed: Some(seed),
+        variants: variants.clone(),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(script, params, &vm, options)?;
+
+    // We always go off whatever the next question is, and this loop will finish when the form
+    // has no more questions to ask (i.e. it's done)
+    while let Some((question, _)) = form.next_question() {
+        // Unwrap is safe because we know there's a next question
+        let id = form.next_question_id().unwrap().to_string();
+        let answer = match answers.get(&id) {
+            Some(answer) => answer.clone(),
+            // Information steps don't need anyone to answer them
+            None if matches!(question, Question::Info { .. }) => Answer::Acknowledged,
+            // Nor do optional questions, which are skipped if there's no answer
+            None if !question.is_required() => Answer::Skipped,
+            None => {
+                return Ok(Err(HeadlessFailure::MissingAnswer {
+                    id,
+                    prompt: question.prompt().to_string(),
+                }))
+            }
+        };
+
+        match form.progress_with_answer(form.next_index(), answer)? {
+            FormPoll::Question { .. } | FormPoll::Done(_) => {}
+            FormPoll::Error(message) => {
+                return Ok(Err(HeadlessFailure::Rejected { id, message }));
+            }
+            // Report the first question the script's review rejected, in the order they were asked
+            FormPoll::Rejected(mut field_errors) => {
+                let (id, message) = form
+                    .answers()
+                    .map(|(id, _, _)| id.to_string())
+                    .chain(std::iter::once(id))
+                    .find_map(|id| field_errors.remove(&id).map(|message| (id, message)))
+                    // The script might have given errors for questions that weren't asked
+                    .or_else(|| field_errors.into_iter().next())
+                    .unwrap_or_default();
+                return Ok(Err(HeadlessFailure::Rejected { id, message }));
+            }
+        }
+    }
+
+    // We only get out of the loop above when there's no next question, so the form is done
+    Ok(Ok(form.into_done().unwrap()))
+}
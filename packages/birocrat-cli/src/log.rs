@@ -0,0 +1,243 @@
+use crate::error::Error;
+use birocrat::{Answer, Question, REDACTED, SECRET_TAG};
+use chrono::Utc;
+use mlua::{Function, Lua, MultiValue};
+use serde_json::{json, Value};
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    rc::Rc,
+};
+
+/// A log of what happens while a form runs, written to a file as JSON lines, so users have
+/// something to send to whoever supports the form when it misbehaves. Answers to secret questions
+/// are redacted, as is anything else in the log that contains them (e.g. script output).
+///
+/// Logging is best-effort: if writing to the log fails, the form carries on regardless. The
+/// default logger doesn't log anything, and clones of a logger all write to the same file.
+#[derive(Clone, Default)]
+pub struct Logger {
+    inner: Option<Rc<RefCell<LogFile>>>,
+}
+struct LogFile {
+    writer: BufWriter<File>,
+    /// Every secret the user has given so far, which will be redacted from anything we log.
+    secrets: Vec<String>,
+}
+impl Logger {
+    /// Creates a logger that writes to the given file, replacing anything already in it.
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        let file = File::create(path).map_err(|err| Error::CreateLogFileFailed {
+            source: err,
+            target: path.to_path_buf(),
+        })?;
+        Ok(Self {
+            inner: Some(Rc::new(RefCell::new(LogFile {
+                writer: BufWriter::new(file),
+                secrets: Vec::new(),
+            }))),
+        })
+    }
+    /// Logs an event with the given fields (which should be an object), along with the time.
+    pub fn log(&self, event: &str, fields: Value) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let mut inner = inner.borrow_mut();
+        let mut entry = serde_json::Map::new();
+        entry.insert("time".to_string(), Value::String(Utc::now().to_rfc3339()));
+        entry.insert("event".to_string(), Value::String(event.to_string()));
+        if let Value::Object(fields) = fields {
+            entry.extend(fields);
+        }
+        let mut entry = Value::Object(entry);
+        redact(&mut entry, &inner.secrets);
+
+        // If we can't write to the log, there's nothing useful we can do about it
+        let _ = writeln!(inner.writer, "{entry}").and_then(|_| inner.writer.flush());
+    }
+    /// Logs an answer given to the given question, redacting it if the question is secret (in
+    /// which case it will also be redacted from everything logged after this). `source` says
+    /// where the answer came from (e.g. the user or an answers file).
+    pub fn log_answer(&self, id: &str, question: &Question, answer: &Answer, source: &str) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let mut value = answer.to_json();
+        if question.is_secret() || matches!(answer, Answer::Secret(_)) {
+            collect_secrets(question, answer, &mut inner.borrow_mut().secrets);
+            value = Value::String(REDACTED.to_string());
+        }
+        self.log(
+            "answer",
+            json!({ "question_id": id, "source": source, "answer": value }),
+        );
+    }
+    /// Makes everything the script in the given Lua VM prints go into the log too (as well as
+    /// wherever it would usually go). This does nothing if the logger doesn't log anything.
+    pub fn capture_print(&self, lua: &Lua) -> Result<(), Error> {
+        if self.inner.is_none() {
+            return Ok(());
+        }
+        let capture = || -> mlua::Result<()> {
+            let globals = lua.globals();
+            let print = lua.create_registry_value(globals.get::<_, Function>("print")?)?;
+            let tostring = lua.create_registry_value(globals.get::<_, Function>("tostring")?)?;
+            let logger = self.clone();
+            let capturing_print = lua.create_function(move |lua, args: MultiValue| {
+                let tostring = lua.registry_value::<Function>(&tostring)?;
+                let text = args
+                    .iter()
+                    .map(|arg| tostring.call::<_, String>(arg.clone()))
+                    .collect::<mlua::Result<Vec<_>>>()?
+                    .join("\t");
+                logger.log("print", json!({ "text": text }));
+                lua.registry_value::<Function>(&print)?.call::<_, ()>(args)
+            })?;
+            globals.set("print", capturing_print)
+        };
+
+        capture().map_err(|err| Error::CapturePrintFailed { source: err })
+    }
+}
+
+/// Replaces every occurrence of the given secrets in any string in the given value.
+fn redact(value: &mut Value, secrets: &[String]) {
+    match value {
+        Value::String(text) => {
+            for secret in secrets.iter().filter(|secret| !secret.is_empty()) {
+                if text.contains(secret.as_str()) {
+                    *text = text.replace(secret.as_str(), REDACTED);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact(value, secrets)),
+        Value::Object(fields) => fields.values_mut().for_each(|value| redact(value, secrets)),
+        _ => {}
+    }
+}
+
+/// Collects the strings in the given answer to the given question that are secret. Only the
+/// secret parts of pages and selections with follow-ups are collected, so that the other answers
+/// in them (which might be as short as `1`) aren't redacted from everything logged after them.
+fn collect_secrets(question: &Question, answer: &Answer, secrets: &mut Vec<String>) {
+    if question.has_tag(SECRET_TAG) {
+        collect_strings(&answer.to_json(), secrets);
+        return;
+    }
+    match (question, answer) {
+        (Question::Page { questions, .. }, Answer::Page(answers)) => {
+            for item in questions {
+                if let Some(answer) = answers.get(&item.id) {
+                    collect_secrets(&item.question, answer, secrets);
+                }
+            }
+        }
+        (
+            Question::Select {
+                followup: Some(followup),
+                ..
+            },
+            Answer::WithFollowUp {
+                followup: answer, ..
+            },
+        ) => collect_secrets(&followup.question, answer, secrets),
+        _ if question.is_secret() || matches!(answer, Answer::Secret(_)) => {
+            collect_strings(&answer.to_json(), secrets)
+        }
+        _ => {}
+    }
+}
+
+/// Collects every string in the given value.
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(text) => strings.push(text.clone()),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_strings(value, strings)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use birocrat::{PageQuestion, QuestionCommon, SecretText};
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_redact_secrets_on_pages() {
+        let path = std::env::temp_dir().join(format!("birocrat-log-{}.jsonl", std::process::id()));
+        let logger = Logger::create(&path).unwrap();
+        let text = |prompt: &str| Question::Simple {
+            prompt: prompt.to_string(),
+            default: None,
+            pattern: None,
+            pattern_hint: None,
+            min_length: None,
+            max_length: None,
+            required: true,
+            common: QuestionCommon::default(),
+        };
+        let page = Question::Page {
+            prompt: "Account".to_string(),
+            questions: vec![
+                PageQuestion {
+                    id: "user".to_string(),
+                    question: text("Username"),
+                },
+                PageQuestion {
+                    id: "token".to_string(),
+                    question: Question::Secret {
+                        prompt: "Token".to_string(),
+                        required: true,
+                        common: QuestionCommon::default(),
+                    },
+                },
+            ],
+            required: true,
+            common: QuestionCommon::default(),
+        };
+        let answer = Answer::Page(HashMap::from([
+            ("user".to_string(), Answer::Text("alex".to_string())),
+            (
+                "token".to_string(),
+                Answer::Secret(SecretText::new("hunter2".to_string())),
+            ),
+        ]));
+        logger.log_answer("account", &page, &answer, "user");
+        logger.log("print", json!({ "text": "logging in alex with hunter2" }));
+        // If the whole page is secret, so is everything on it
+        let secret_page = Question::Page {
+            prompt: "Recovery".to_string(),
+            questions: vec![PageQuestion {
+                id: "phrase".to_string(),
+                question: text("Phrase"),
+            }],
+            required: true,
+            common: QuestionCommon {
+                tags: vec![SECRET_TAG.to_string()],
+                ..Default::default()
+            },
+        };
+        let answer = Answer::Page(HashMap::from([(
+            "phrase".to_string(),
+            Answer::Text("correct horse".to_string()),
+        )]));
+        logger.log_answer("recovery", &secret_page, &answer, "user");
+        logger.log("error", json!({ "message": "bad phrase 'correct horse'" }));
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!log.contains("hunter2"), "{log}");
+        assert!(!log.contains("correct horse"), "{log}");
+        // Only the secret should be redacted
+        assert!(log.contains("logging in alex with [redacted]"), "{log}");
+    }
+}
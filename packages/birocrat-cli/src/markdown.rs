@@ -0,0 +1,189 @@
+use birocrat::{PromptFormat, Question};
+use console::{strip_ansi_codes, Style};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+/// Gets a copy of the given question with its prompt ready to show in the terminal, rendering it
+/// if it's written in markdown. With `styled`, emphasis, code, and headings will be styled (if
+/// the terminal supports it), and otherwise only the text and layout will be kept, which is better
+/// for screen readers.
+pub fn render_prompt(question: &Question, styled: bool) -> Question {
+    let mut question = question.clone();
+    if question.format() == PromptFormat::Markdown {
+        let prompt = prompt_mut(&mut question);
+        *prompt = render_markdown(prompt, styled);
+    }
+    question
+}
+
+/// Renders the given markdown as text for the terminal. Links are shown with their URLs after
+/// them, and lists are indented with bullets or numbers.
+fn render_markdown(markdown: &str, styled: bool) -> String {
+    let mut out = String::new();
+    // How text is currently being styled, by how many of each tag we're inside
+    let (mut strong, mut emphasis, mut strikethrough, mut heading) = (0, 0, 0, false);
+    // The next number of each list we're inside, if it's an ordered one
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // The URL of each link we're inside, and where its text starts
+    let mut links: Vec<(String, usize)> = Vec::new();
+
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                let mut style = Style::new();
+                if strong > 0 || heading {
+                    style = style.bold();
+                }
+                if emphasis > 0 {
+                    style = style.italic();
+                }
+                if strikethrough > 0 {
+                    style = style.strikethrough();
+                }
+                push_styled(&mut out, &text, style, styled);
+            }
+            Event::Code(code) if styled => push_styled(&mut out, &code, Style::new().cyan(), true),
+            Event::Code(code) => out.push_str(&format!("`{code}`")),
+            Event::SoftBreak => out.push(' '),
+            Event::HardBreak => out.push('\n'),
+            Event::Rule => {
+                blank_line(&mut out);
+                out.push_str("---");
+                blank_line(&mut out);
+            }
+            Event::Start(Tag::Strong) => strong += 1,
+            Event::End(TagEnd::Strong) => strong -= 1,
+            Event::Start(Tag::Emphasis) => emphasis += 1,
+            Event::End(TagEnd::Emphasis) => emphasis -= 1,
+            Event::Start(Tag::Strikethrough) => strikethrough += 1,
+            Event::End(TagEnd::Strikethrough) => strikethrough -= 1,
+            Event::Start(Tag::Heading { level, .. }) => {
+                blank_line(&mut out);
+                heading = true;
+                // Without styling, the only way to tell a heading apart is to keep its markers
+                if !styled {
+                    out.push_str(&"#".repeat(heading_depth(level)));
+                    out.push(' ');
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                heading = false;
+                blank_line(&mut out);
+            }
+            // Blocks in lists are kept together
+            Event::Start(Tag::Paragraph | Tag::CodeBlock(_) | Tag::BlockQuote(_))
+                if lists.is_empty() =>
+            {
+                blank_line(&mut out)
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::CodeBlock | TagEnd::BlockQuote(_)) => {
+                if lists.is_empty() {
+                    blank_line(&mut out);
+                } else {
+                    new_line(&mut out);
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                if lists.is_empty() {
+                    blank_line(&mut out);
+                }
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    blank_line(&mut out);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                new_line(&mut out);
+                out.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        out.push_str(&format!("{number}. "));
+                        *number += 1;
+                    }
+                    _ if styled => out.push_str("• "),
+                    _ => out.push_str("- "),
+                }
+            }
+            Event::End(TagEnd::Item) => new_line(&mut out),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                links.push((dest_url.to_string(), out.len()))
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((url, start)) = links.pop() {
+                    // Links that are just their URL don't need it twice
+                    if strip_ansi_codes(&out[start..]) != url {
+                        out.push_str(&format!(" ({url})"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out.trim().to_string()
+}
+
+/// Pushes the given text onto the output with the given style, if styling is enabled.
+fn push_styled(out: &mut String, text: &str, style: Style, styled: bool) {
+    if styled {
+        out.push_str(&style.apply_to(text).to_string());
+    } else {
+        out.push_str(text);
+    }
+}
+
+/// Ends the current line of the output, if there is one.
+fn new_line(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Leaves a blank line at the end of the output, if there's anything in it, to separate blocks.
+fn blank_line(out: &mut String) {
+    new_line(out);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+/// Gets how many `#`s the given level of heading is written with.
+fn heading_depth(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Gets the prompt of the given question, whatever type of question it is, so it can be replaced.
+fn prompt_mut(question: &mut Question) -> &mut String {
+    match question {
+        Question::Simple { prompt, .. }
+        | Question::Multiline { prompt, .. }
+        | Question::Email { prompt, .. }
+        | Question::Phone { prompt, .. }
+        | Question::Url { prompt, .. }
+        | Question::Secret { prompt, .. }
+        | Question::Select { prompt, .. }
+        | Question::Rank { prompt, .. }
+        | Question::Group { prompt, .. }
+        | Question::Info { prompt, .. }
+        | Question::Computed { prompt, .. }
+        | Question::Page { prompt, .. }
+        | Question::Date { prompt, .. }
+        | Question::Time { prompt, .. }
+        | Question::DateTime { prompt, .. }
+        | Question::File { prompt, .. }
+        | Question::Rating { prompt, .. }
+        | Question::Likert { prompt, .. }
+        | Question::Range { prompt, .. }
+        | Question::Decimal { prompt, .. }
+        | Question::Color { prompt, .. } => prompt,
+    }
+}
@@ -0,0 +1,156 @@
+use crate::cli::ReportFormat;
+use serde_json::json;
+use std::time::Duration;
+
+/// The result of running a single test scenario.
+pub struct ScenarioResult {
+    /// The name of the scenario.
+    pub name: String,
+    /// How long the scenario took to run.
+    pub duration: Duration,
+    /// Details of why the scenario failed, if it did.
+    pub failure: Option<ScenarioFailure>,
+}
+
+/// Details of why a test scenario failed.
+pub struct ScenarioFailure {
+    /// A description of the failure.
+    pub message: String,
+    /// Any differences between the expected and actual outputs of the form, one per line.
+    pub diff: Vec<String>,
+}
+
+/// Renders a machine-readable report of the given scenario results in the given format. The
+/// suite name will typically be the path to the script under test.
+pub fn render_report(format: ReportFormat, suite: &str, results: &[ScenarioResult]) -> String {
+    match format {
+        ReportFormat::Junit => render_junit(suite, results),
+        ReportFormat::Json => render_json(suite, results),
+    }
+}
+
+fn render_junit(suite: &str, results: &[ScenarioResult]) -> String {
+    let failures = results.iter().filter(|r| r.failure.is_some()).count();
+    let total_time: Duration = results.iter().map(|r| r.duration).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" time=\"{:.3}\">\n",
+        escape_xml(suite),
+        results.len(),
+        total_time.as_secs_f64()
+    ));
+    for result in results {
+        xml.push_str(&format!(
+            "  <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(&result.name),
+            escape_xml(suite),
+            result.duration.as_secs_f64()
+        ));
+        match &result.failure {
+            Some(failure) => {
+                xml.push_str(">\n");
+                xml.push_str(&format!(
+                    "    <failure message=\"{}\">{}</failure>\n",
+                    escape_xml(&failure.message),
+                    escape_xml(&failure.diff.join("\n"))
+                ));
+                xml.push_str("  </testcase>\n");
+            }
+            None => xml.push_str(" />\n"),
+        }
+    }
+    xml.push_str("</testsuite>\n");
+
+    xml
+}
+
+fn render_json(suite: &str, results: &[ScenarioResult]) -> String {
+    let failed = results.iter().filter(|r| r.failure.is_some()).count();
+    let scenarios = results
+        .iter()
+        .map(|result| {
+            json!({
+                "name": result.name,
+                "passed": result.failure.is_none(),
+                "duration": result.duration.as_secs_f64(),
+                "failure": result.failure.as_ref().map(|failure| json!({
+                    "message": failure.message,
+                    "diff": failure.diff,
+                })),
+            })
+        })
+        .collect::<Vec<_>>();
+    let report = json!({
+        "suite": suite,
+        "passed": results.len() - failed,
+        "failed": failed,
+        "duration": results.iter().map(|r| r.duration).sum::<Duration>().as_secs_f64(),
+        "scenarios": scenarios,
+    });
+
+    // This is already a `Value`, so serializing it can't fail
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+/// Escapes a string for use in XML text or attribute values.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn results() -> Vec<ScenarioResult> {
+        vec![
+            ScenarioResult {
+                name: "italian".to_string(),
+                duration: Duration::from_millis(250),
+                failure: None,
+            },
+            ScenarioResult {
+                name: "spice <hot> & \"extreme\"".to_string(),
+                duration: Duration::from_millis(500),
+                failure: Some(ScenarioFailure {
+                    message: "outputs differ".to_string(),
+                    diff: vec!["/spice: expected \"hot\", found \"mild\"".to_string()],
+                }),
+            },
+        ]
+    }
+
+    #[test]
+    fn should_render_junit_reports() {
+        let xml = render_report(ReportFormat::Junit, "form.lua", &results());
+        assert!(xml.contains(
+            r#"<testsuite name="form.lua" tests="2" failures="1" errors="0" time="0.750">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="italian" classname="form.lua" time="0.250" />"#));
+        assert!(xml.contains(r#"name="spice &lt;hot&gt; &amp; &quot;extreme&quot;""#));
+        assert!(xml.contains(
+            r#"<failure message="outputs differ">/spice: expected &quot;hot&quot;, found &quot;mild&quot;</failure>"#
+        ));
+    }
+
+    #[test]
+    fn should_render_json_reports() {
+        let report: Value =
+            serde_json::from_str(&render_report(ReportFormat::Json, "form.lua", &results()))
+                .unwrap();
+        assert_eq!(report["suite"], "form.lua");
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["duration"], 0.75);
+        assert_eq!(report["scenarios"][0]["failure"], Value::Null);
+        assert_eq!(
+            report["scenarios"][1]["failure"]["diff"][0],
+            "/spice: expected \"hot\", found \"mild\""
+        );
+    }
+}
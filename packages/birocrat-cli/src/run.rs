@@ -0,0 +1,732 @@
+use crate::{
+    answers, answers::PresetAnswers, cli::RunArgs, error::Error, log::Logger, markdown,
+    spinner::with_spinner, template::render_path_template, utils,
+};
+use birocrat::{
+    fold_case_and_whitespace, Answer, Form, FormMeta, FormOptions, FormPoll, Question, Script,
+    SecretText, SelectOption,
+};
+use fmterr::fmterr;
+use mlua::Lua;
+use serde_json::{json, Value};
+use std::{collections::HashMap, fs, path::Path};
+
+/// Runs a form interactively in the terminal, using any answers the user provided ahead of time.
+pub fn run(args: RunArgs, forms_dir: Option<&Path>) -> Result<(), Error> {
+    let logger = match &args.log_file {
+        Some(path) => Logger::create(path)?,
+        None => Logger::default(),
+    };
+    logger.log("start", json!({ "script": args.script }));
+    let result = run_logged(args, forms_dir, logger.clone());
+    match &result {
+        Ok(()) => logger.log("done", json!({})),
+        Err(err) => logger.log("error", json!({ "message": fmterr(err) })),
+    }
+
+    result
+}
+
+/// Same as [`run`], but with the logger already set up, so any errors can be logged.
+fn run_logged(args: RunArgs, forms_dir: Option<&Path>, logger: Logger) -> Result<(), Error> {
+    let drive_options = DriveOptions {
+        page_size: args.page_size,
+        accessible: args.accessible,
+        verbose: args.verbose,
+        logger,
+    };
+    let script = utils::read_script(&args.script, forms_dir)?;
+    let mut params = utils::parse_params(args.params)?;
+    // We only need to run the top level of the script once to get its metadata and then start
+    // the form
+    let vm = Lua::new();
+    drive_options.logger.capture_print(&vm)?;
+    let script = Script::load(&script, &vm)?;
+    if let Some(meta) = script.meta()? {
+        prompt_missing_params(&mut params, &meta, args.accessible)?;
+    }
+
+    // Answers given ahead of time are typed out by hand, so we'll forgive trivial differences in
+    // selections
+    let options = FormOptions {
+        option_normalize: Some(fold_case_and_whitespace),
+        max_errors: args.max_errors,
+        seed: args.seed,
+        variants: args.variants.into_iter().collect(),
+        ..Default::default()
+    };
+    // Starting the form calls the script for the first question
+    let mut form = call_script(&drive_options, || {
+        Form::from_script(&script, params, options)
+    })?;
+    // Seeds are logged as strings, because JSON numbers can't hold every one exactly
+    drive_options
+        .logger
+        .log("seed", json!({ "seed": form.seed().to_string() }));
+    // Any answers the user has given us ahead of time, which we'll use instead of asking
+    let PresetAnswers {
+        answers: mut preset_answers,
+        references: answer_references,
+    } = match &args.answers {
+        Some(path) => answers::read_answers(path)?,
+        None => PresetAnswers::default(),
+    };
+
+    drive_form(&mut form, &mut preset_answers, &drive_options)?;
+    drive_options
+        .logger
+        .log("variants", json!({ "variants": form.variants() }));
+
+    if let Some(path) = &args.save_answers {
+        answers::write_answers(&form, &answer_references, path)?;
+        eprintln!("Answers written to {path:?}.");
+    }
+
+    // We need these for templating the output path, and we won't have them after `into_done()`
+    let answer_values = answers::answer_values(&form);
+    // `drive_form` only returns successfully once the form is done
+    let output = form.into_done().unwrap();
+    // This is already a `Value`, so serializing it can't fail
+    let output_str = serde_json::to_string(&output).unwrap();
+
+    if let Some(output_template) = args.output {
+        let output_path = render_path_template(&output_template, &output, &answer_values)?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| Error::CreateOutputDirFailed {
+                source: err,
+                target: parent.to_path_buf(),
+            })?;
+        }
+        fs::write(&output_path, output_str).map_err(|err| Error::WriteOutputFailed {
+            source: err,
+            target: output_path.clone(),
+        })?;
+        eprintln!("Form output written to {output_path:?}.")
+    } else {
+        println!("{output_str}");
+    }
+
+    Ok(())
+}
+
+/// Asks the user for any parameters the form's metadata says are required, but which weren't
+/// provided, so the script doesn't fail part-way through without them.
+fn prompt_missing_params(
+    params: &mut Value,
+    meta: &FormMeta,
+    accessible: bool,
+) -> Result<(), Error> {
+    // If the parameters aren't an object, the script must be expecting something unusual, so we
+    // leave them alone
+    let Value::Object(params) = params else {
+        return Ok(());
+    };
+    for param in meta.params.iter().filter(|param| param.required) {
+        if params.contains_key(&param.name) {
+            continue;
+        }
+        let prompt = match &param.description {
+            Some(description) => format!("{} ({description})", param.name),
+            None => param.name.clone(),
+        };
+        let value = utils::read_simple(&prompt, None, accessible)?;
+        params.insert(param.name.clone(), Value::String(value));
+    }
+
+    Ok(())
+}
+
+/// How to go about asking the user questions in [`drive_form`].
+#[derive(Default)]
+pub struct DriveOptions {
+    /// The most options to show at once in select questions, if there's a limit.
+    pub page_size: Option<usize>,
+    /// Whether or not to ask questions with plain prompts that work with screen readers.
+    pub accessible: bool,
+    /// Whether or not to print how long each call to the script takes.
+    pub verbose: bool,
+    /// Where to record what happens while the form runs.
+    pub logger: Logger,
+}
+
+/// Drives the given form interactively until it's complete, asking the user each question in the
+/// terminal, unless it has an answer in the given preset answers (keyed by question ID), in which
+/// case that will be used instead (once).
+pub fn drive_form(
+    form: &mut Form,
+    preset_answers: &mut HashMap<String, Answer>,
+    options: &DriveOptions,
+) -> Result<(), Error> {
+    let DriveOptions {
+        page_size,
+        accessible,
+        logger,
+        ..
+    } = options;
+    let (page_size, accessible) = (*page_size, *accessible);
+    // Format the first question inside a `FormPoll` for consistency of handling logic
+    let mut poll = FormPoll::Question {
+        question: form.first_question()?,
+        answer: None,
+    };
+    // The last answer we gave, which we'll reuse if the script's review rejects other answers
+    let mut last_answer = None;
+    // How many of the form's warnings we've logged so far
+    let mut num_warnings = 0;
+    loop {
+        match poll {
+            FormPoll::Question { .. } => {
+                log_new_warnings(form, logger, &mut num_warnings);
+                // If we were given an answer for this question, use it, but only once (if the
+                // script rejects it, we'll ask the user instead)
+                // We're guaranteed to have a next question if we were just polled with one
+                let id = form.next_question_id().unwrap().to_string();
+                let question = form.next_question().unwrap().0;
+                logger.log(
+                    "question",
+                    json!({ "question_id": id, "prompt": question.prompt() }),
+                );
+                let answer = match preset_answers.remove(&id) {
+                    Some(answer) => {
+                        logger.log_answer(&id, question, &answer, "preset");
+                        answer
+                    }
+                    None => {
+                        let answer = ask_question(question, page_size, accessible)?;
+                        logger.log_answer(&id, question, &answer, "user");
+                        answer
+                    }
+                };
+                last_answer = Some(answer.clone());
+                poll = call_script(options, || {
+                    form.progress_with_answer(form.next_index(), answer)
+                })?;
+            }
+            FormPoll::Error(err) => {
+                // We have an error in the question we just tried to answer, so we should display
+                // this error message and then return to it
+                // TODO: Better printing
+                eprintln!("Error: {}", err);
+                logger.log(
+                    "validation_failed",
+                    json!({ "question_id": form.next_question_id(), "message": err }),
+                );
+
+                // We know an error just occurred, so the form still has the old question as the
+                // next one to ask
+                let (question, answer) = form.next_question().unwrap();
+                poll = FormPoll::Question { question, answer };
+            }
+            FormPoll::Rejected(field_errors) => {
+                // The script's review of the whole form rejected some answers, so we'll ask the
+                // earliest of those again, and then reuse every answer it didn't reject
+                logger.log("rejected", json!({ "errors": field_errors }));
+                let mut first_rejected = None;
+                for (idx, (id, question, answer)) in form.answers().enumerate() {
+                    if let Some(err) = field_errors.get(id) {
+                        eprintln!("Error in answer to '{}': {}", question.prompt(), err);
+                        first_rejected.get_or_insert((idx, id.to_string()));
+                    } else {
+                        preset_answers.insert(id.to_string(), answer.clone());
+                    }
+                }
+                // We know the question we just answered is still the next one to ask
+                let id = form.next_question_id().unwrap();
+                match field_errors.get(id) {
+                    Some(err) => eprintln!("Error: {}", err),
+                    None => {
+                        if let Some(answer) = last_answer.take() {
+                            preset_answers.insert(id.to_string(), answer);
+                        }
+                    }
+                }
+
+                poll = match first_rejected {
+                    Some((idx, id)) => {
+                        let question = form.get_question(idx).unwrap().0.clone();
+                        let answer = ask_question(&question, page_size, accessible)?;
+                        logger.log_answer(&id, &question, &answer, "user");
+                        call_script(options, || form.progress_with_answer(idx, answer))?
+                    }
+                    None => {
+                        let (question, answer) = form.next_question().unwrap();
+                        FormPoll::Question { question, answer }
+                    }
+                };
+            }
+            FormPoll::Done(_) => {
+                log_new_warnings(form, logger, &mut num_warnings);
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Logs any warnings the form has given since the first `num_logged`, and updates that to include
+/// them.
+fn log_new_warnings(form: &Form, logger: &Logger, num_logged: &mut usize) {
+    for warning in &form.warnings()[*num_logged..] {
+        logger.log("warning", json!({ "message": warning.to_string() }));
+    }
+    *num_logged = form.warnings().len();
+}
+
+/// Makes the given call to the driver script, showing a spinner if it takes a while, and printing
+/// and logging how long it took.
+fn call_script<T>(options: &DriveOptions, call: impl FnOnce() -> T) -> T {
+    // Spinners are redrawn in place, which screen readers can't follow
+    let (result, duration) = with_spinner(!options.accessible, call);
+    if options.verbose {
+        eprintln!("Script call took {duration:.2?}.");
+    }
+    options.logger.log(
+        "script_call",
+        json!({ "duration_ms": duration.as_secs_f64() * 1000.0 }),
+    );
+
+    result
+}
+
+/// Asks the given question interactively, returning the user's answer.
+fn ask_question(
+    question: &Question,
+    page_size: Option<usize>,
+    accessible: bool,
+) -> Result<Answer, Error> {
+    // Markdown is rendered up front, so every type of question can show it (screen readers would
+    // read out any styling, so it's left out for them)
+    let question = &markdown::render_prompt(question, !accessible);
+    // The prompt is where the user answers, so any help has to come before it
+    if let Some(help) = question.help() {
+        eprintln!("{help}");
+    }
+    // There's nothing to skip on an information step or a computed one
+    if !question.is_required()
+        && !matches!(question, Question::Info { .. } | Question::Computed { .. })
+        && utils::confirm(
+            &format!("Skip \"{}\"? (it's optional)", question.prompt()),
+            accessible,
+        )?
+    {
+        return Ok(Answer::Skipped);
+    }
+    let answer = match question {
+        // The form will validate email addresses and URLs for us
+        Question::Simple {
+            prompt, default, ..
+        }
+        | Question::Email {
+            prompt, default, ..
+        } => Answer::Text(utils::read_simple(prompt, default.clone(), accessible)?),
+        // The form will normalise phone numbers too, so we only need to say whether a country
+        // code is needed
+        Question::Phone {
+            prompt,
+            default,
+            region,
+            ..
+        } => {
+            let prompt = match region {
+                Some(region) => format!("{prompt} ({region} number, or with country code)"),
+                None => format!("{prompt} (with country code, like +44)"),
+            };
+            Answer::Text(utils::read_simple(&prompt, default.clone(), accessible)?)
+        }
+        Question::Url {
+            prompt,
+            default,
+            schemes,
+            ..
+        } => {
+            let prompt = if schemes.is_empty() {
+                prompt.to_string()
+            } else {
+                format!("{prompt} ({} URL)", schemes.join("/"))
+            };
+            Answer::Text(utils::read_simple(&prompt, default.clone(), accessible)?)
+        }
+        Question::Multiline {
+            prompt, default, ..
+        } => Answer::Text(utils::read_multiple(
+            prompt,
+            default.as_ref().unwrap_or(&String::new()),
+            accessible,
+        )?),
+        Question::Secret { prompt, .. } => {
+            Answer::Secret(SecretText::from(utils::read_secret(prompt, accessible)?))
+        }
+        // The form will parse and validate this for us
+        Question::Date {
+            prompt,
+            default,
+            min,
+            max,
+            ..
+        } => {
+            let range = match (min, max) {
+                (Some(min), Some(max)) => format!(", {min} to {max}"),
+                (Some(min), None) => format!(", from {min}"),
+                (None, Some(max)) => format!(", up to {max}"),
+                (None, None) => String::new(),
+            };
+            Answer::Text(utils::read_simple(
+                &format!("{prompt} (YYYY-MM-DD{range})"),
+                default.map(|date| date.to_string()),
+                accessible,
+            )?)
+        }
+        Question::Time {
+            prompt,
+            default,
+            min,
+            max,
+            seconds,
+            ..
+        } => {
+            let format = if *seconds { "HH:MM[:SS]" } else { "HH:MM" };
+            let range = match (min, max) {
+                (Some(min), Some(max)) => format!(", {min} to {max}"),
+                (Some(min), None) => format!(", from {min}"),
+                (None, Some(max)) => format!(", up to {max}"),
+                (None, None) => String::new(),
+            };
+            Answer::Text(utils::read_simple(
+                &format!("{prompt} ({format}{range})"),
+                default.map(|time| time.format("%H:%M:%S").to_string()),
+                accessible,
+            )?)
+        }
+        // The form will parse and validate this for us
+        Question::Range {
+            prompt,
+            default,
+            min,
+            max,
+            step,
+            ..
+        } => {
+            let scale = match step {
+                Some(step) => format!("{min} to {max}, in steps of {step}"),
+                None => format!("{min} to {max}"),
+            };
+            Answer::Text(utils::read_simple(
+                &format!("{prompt} ({scale})"),
+                default.map(|value| value.to_string()),
+                accessible,
+            )?)
+        }
+        // The form will parse and validate this for us
+        Question::Color {
+            prompt, default, ..
+        } => Answer::Text(utils::read_simple(
+            &format!("{prompt} (hex, like #ff8800)"),
+            default.map(|color| color.to_string()),
+            accessible,
+        )?),
+        // The form will parse and validate this for us
+        Question::Decimal {
+            prompt,
+            default,
+            currency,
+            ..
+        } => {
+            let prompt = match currency {
+                Some(currency) => format!("{prompt} ({currency})"),
+                None => prompt.to_string(),
+            };
+            Answer::Text(utils::read_simple(
+                &prompt,
+                default.map(|decimal| decimal.to_string()),
+                accessible,
+            )?)
+        }
+        // The form will parse and validate this for us
+        Question::Rating {
+            prompt,
+            default,
+            min,
+            max,
+            min_label,
+            max_label,
+            ..
+        } => {
+            let mut scale = format!("{min} to {max}");
+            if let Some(label) = min_label {
+                scale.push_str(&format!(", {min} = {label}"));
+            }
+            if let Some(label) = max_label {
+                scale.push_str(&format!(", {max} = {label}"));
+            }
+            Answer::Text(utils::read_simple(
+                &format!("{prompt} ({scale})"),
+                default.map(|rating| rating.to_string()),
+                accessible,
+            )?)
+        }
+        // The user chooses a label, which we give as its point on the scale
+        Question::Likert {
+            prompt,
+            default,
+            labels,
+            ..
+        } => {
+            let points = labels
+                .iter()
+                .enumerate()
+                .map(|(idx, label)| SelectOption {
+                    value: (idx + 1).to_string(),
+                    label: Some(label.clone()),
+                    description: None,
+                    image: None,
+                    specify: false,
+                })
+                .collect::<Vec<_>>();
+            let default = default.map(|point| point.to_string());
+            let point = utils::select_one(
+                prompt,
+                &points,
+                default.as_deref(),
+                false,
+                page_size,
+                accessible,
+            )?;
+            Answer::Text(point.value.clone())
+        }
+        // The form will check extensions for us
+        Question::File {
+            prompt,
+            extensions,
+            multiple,
+            ..
+        } => {
+            let prompt = if extensions.is_empty() {
+                format!("{prompt} (path)")
+            } else {
+                format!("{prompt} (path to .{} file)", extensions.join("/."))
+            };
+            Answer::Files(utils::read_paths(&prompt, *multiple, accessible)?)
+        }
+        Question::DateTime {
+            prompt,
+            default,
+            min,
+            max,
+            timezone,
+            ..
+        } => {
+            let timezone = match timezone {
+                Some(timezone) => format!("UTC{timezone}"),
+                None => "UTC".to_string(),
+            };
+            let range = match (min, max) {
+                (Some(min), Some(max)) => format!(", {min} to {max}"),
+                (Some(min), None) => format!(", from {min}"),
+                (None, Some(max)) => format!(", up to {max}"),
+                (None, None) => String::new(),
+            };
+            Answer::Text(utils::read_simple(
+                &format!("{prompt} (YYYY-MM-DD HH:MM in {timezone}{range})"),
+                default.map(|datetime| datetime.format("%Y-%m-%d %H:%M").to_string()),
+                accessible,
+            )?)
+        }
+        Question::Select {
+            prompt,
+            defaults,
+            options,
+            multiple,
+            allow_other,
+            searchable,
+            min_selected,
+            max_selected,
+            followup,
+            ..
+        } => {
+            // If the user can give their own value, they can do so by choosing an extra option at
+            // the end
+            let mut choices = options.clone();
+            if *allow_other {
+                choices.push(SelectOption {
+                    value: String::new(),
+                    label: Some("Other (enter your own)".to_string()),
+                    description: None,
+                    image: None,
+                    specify: false,
+                });
+            }
+            let is_other = |option: &SelectOption| {
+                *allow_other
+                    && choices
+                        .last()
+                        .is_some_and(|last| std::ptr::eq(option, last))
+            };
+
+            // If a default is the user's own value, the other option (which has an empty value)
+            // should be chosen by default, and we'll suggest it when we ask for it
+            let other_default = defaults.iter().find(|d| question.is_other(d)).cloned();
+            let defaults = defaults
+                .iter()
+                .map(|default| {
+                    if question.is_other(default) {
+                        ""
+                    } else {
+                        default.as_str()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let selection = if *multiple {
+                // The form will check these, but it's easier for the user to know up front
+                let prompt = match (min_selected, max_selected) {
+                    (Some(min), Some(max)) if min == max => format!("{prompt} (choose {min})"),
+                    (Some(min), Some(max)) => format!("{prompt} (choose {min} to {max})"),
+                    (Some(min), None) => format!("{prompt} (choose at least {min})"),
+                    (None, Some(max)) => format!("{prompt} (choose up to {max})"),
+                    (None, None) => prompt.to_string(),
+                };
+                utils::select_multiple(&prompt, &choices, &defaults, page_size, accessible)?
+            } else {
+                vec![utils::select_one(
+                    prompt,
+                    &choices,
+                    defaults.first().copied(),
+                    *searchable,
+                    page_size,
+                    accessible,
+                )?]
+            };
+            // Options that need details are followed up straight away
+            let selection = selection
+                .into_iter()
+                .map(|option| {
+                    if is_other(option) {
+                        let other = utils::read_simple(
+                            "Your own answer",
+                            other_default.clone(),
+                            accessible,
+                        )?;
+                        Ok((other, None))
+                    } else if option.specify {
+                        let details = utils::read_simple(
+                            &format!("Details for \"{}\"", option.label()),
+                            None,
+                            accessible,
+                        )?;
+                        Ok((option.value.clone(), Some(details)))
+                    } else {
+                        Ok((option.value.clone(), None))
+                    }
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let values = selection
+                .iter()
+                .map(|(option, _)| option.clone())
+                .collect::<Vec<_>>();
+            let answer = if selection.iter().any(|(_, details)| details.is_some()) {
+                Answer::OptionsWithText(selection)
+            } else {
+                Answer::Options(values.clone())
+            };
+            // So are follow-ups, if the options chosen bring them up
+            match followup {
+                Some(followup) if followup.is_asked(&values) => Answer::WithFollowUp {
+                    answer: Box::new(answer),
+                    followup: Box::new(ask_question(&followup.question, page_size, accessible)?),
+                },
+                _ => answer,
+            }
+        }
+        Question::Info { prompt, .. } => {
+            utils::acknowledge(prompt, accessible)?;
+            Answer::Acknowledged
+        }
+        // The form answers these itself, so we'll only get one if the script's review rejected the
+        // form as it was completed by one, in which case we give the same value again
+        Question::Computed { value, .. } => Answer::Computed(value.clone()),
+        // The terminal can only ask one thing at a time, so we ask each question on the page in
+        // turn, and the form checks them all together
+        Question::Page {
+            prompt, questions, ..
+        } => {
+            eprintln!("{prompt}");
+            Answer::Page(
+                questions
+                    .iter()
+                    .map(|item| {
+                        let answer = ask_question(&item.question, page_size, accessible)?;
+                        Ok((item.id.clone(), answer))
+                    })
+                    .collect::<Result<_, Error>>()?,
+            )
+        }
+        // The user adds entries one at a time until they're done (or there's no room for more)
+        Question::Group {
+            prompt,
+            fields,
+            min,
+            max,
+            ..
+        } => {
+            eprintln!("{prompt}");
+            let mut entries = Vec::new();
+            loop {
+                if max.is_some_and(|max| entries.len() >= max) {
+                    break;
+                }
+                let can_finish = entries.len() >= min.unwrap_or(0);
+                let add_prompt = if entries.is_empty() {
+                    "Add an entry?"
+                } else {
+                    "Add another entry?"
+                };
+                if can_finish && !utils::confirm(add_prompt, accessible)? {
+                    break;
+                }
+                let mut entry = HashMap::new();
+                for field in fields {
+                    let field_prompt = format!("{} (entry {})", field.prompt, entries.len() + 1);
+                    let value = if field.required {
+                        utils::read_simple(&field_prompt, None, accessible)?
+                    } else {
+                        utils::read_optional(&field_prompt, accessible)?
+                    };
+                    if !value.is_empty() {
+                        entry.insert(field.id.clone(), value);
+                    }
+                }
+                entries.push(entry);
+            }
+            Answer::Group(entries)
+        }
+        // The user picks the most important of the options left until there's only one
+        Question::Rank {
+            prompt, options, ..
+        } => {
+            let mut remaining = options.clone();
+            let mut ranked = Vec::new();
+            while remaining.len() > 1 {
+                let value = utils::select_one(
+                    &format!(
+                        "{prompt} (choice {} of {})",
+                        ranked.len() + 1,
+                        options.len()
+                    ),
+                    &remaining,
+                    None,
+                    false,
+                    page_size,
+                    accessible,
+                )?
+                .value
+                .clone();
+                remaining.retain(|option| option.value != value);
+                ranked.push(value);
+            }
+            ranked.extend(remaining.into_iter().map(|option| option.value));
+            Answer::Options(ranked)
+        }
+    };
+
+    Ok(answer)
+}
@@ -0,0 +1,62 @@
+-- The form `birocrat new` uses to find out what kind of starter script to generate. Yes, this is a
+-- Birocrat form that makes Birocrat forms.
+
+local questions = {
+	{
+		id = "description",
+		type = "simple",
+		text = "What is this form for?",
+	},
+	{
+		id = "question_types",
+		type = "select",
+		text = "What types of questions will it ask?",
+		options = { "simple", "multiline", "secret", "select" },
+		multiple = true,
+	},
+	{
+		id = "validation",
+		type = "select",
+		text = "Should it include helpers for validating answers?",
+		options = { "Yes", "No" },
+		default = "Yes",
+	},
+	{
+		id = "params",
+		type = "simple",
+		text = "What parameters will it take? (comma-separated, leave empty for none)",
+		default = "",
+	},
+}
+
+function Main(state, answer, _)
+	if state == nil then
+		return { "question", questions[1], { question = 1 } }
+	end
+
+	local id = questions[state.question].id
+	if answer.type == "text" then
+		state[id] = answer.text
+	elseif id == "question_types" then
+		if #answer.selected == 0 then
+			return { "error", "Please choose at least one type of question." }
+		end
+		state[id] = answer.selected
+	else
+		state[id] = answer.selected[1] == "Yes"
+	end
+
+	state.question = state.question + 1
+	if questions[state.question] == nil then
+		return {
+			"done",
+			{
+				description = state.description,
+				question_types = state.question_types,
+				validation = state.validation,
+				params = state.params,
+			},
+		}
+	end
+	return { "question", questions[state.question], state }
+end
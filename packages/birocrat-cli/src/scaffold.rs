@@ -0,0 +1,177 @@
+use crate::{
+    cli::NewArgs,
+    error::Error,
+    run::{drive_form, DriveOptions},
+};
+use birocrat::Form;
+use mlua::Lua;
+use serde::Deserialize;
+use std::{collections::HashMap, fs};
+
+/// The form we use to ask the user what kind of script they want.
+static SCAFFOLD_SCRIPT: &str = include_str!("scaffold.lua");
+
+/// The user's choices about the script to generate, as produced by the scaffolding form.
+#[derive(Deserialize)]
+struct ScaffoldOptions {
+    /// What the form is for.
+    description: String,
+    /// The types of questions the form will ask.
+    question_types: Vec<String>,
+    /// Whether or not to include validation helpers.
+    validation: bool,
+    /// The parameters the form takes, comma-separated.
+    params: String,
+}
+
+/// Asks the user some questions about the form they want to write (through a Birocrat form, of
+/// course), and then generates a starter script for it.
+pub fn new(args: NewArgs) -> Result<(), Error> {
+    if args.path.exists() && !args.force {
+        return Err(Error::ScaffoldTargetExists { target: args.path });
+    }
+
+    let vm = Lua::new();
+    let mut form = Form::new(SCAFFOLD_SCRIPT, (), &vm)?;
+    let options = DriveOptions {
+        accessible: args.accessible,
+        ..Default::default()
+    };
+    drive_form(&mut form, &mut HashMap::new(), &options)?;
+    // `drive_form` only returns successfully once the form is done
+    let output = form.into_done().unwrap();
+    // We control the scaffolding script, so this should always work
+    let options: ScaffoldOptions =
+        serde_json::from_value(output).expect("scaffolding form produced invalid output");
+
+    fs::write(&args.path, generate_script(&options)).map_err(|err| Error::WriteScaffoldFailed {
+        source: err,
+        target: args.path.clone(),
+    })?;
+    eprintln!("New form script written to {:?}.", args.path);
+
+    Ok(())
+}
+
+/// Generates a starter script from the given options.
+fn generate_script(options: &ScaffoldOptions) -> String {
+    let mut script = String::new();
+
+    // Header explaining the form and how scripts work
+    let description = options.description.trim();
+    if !description.is_empty() {
+        script.push_str(&format!("-- {description}\n--\n"));
+    }
+    script.push_str(
+        "-- This script drives a Birocrat form. Birocrat calls `Main` with the form's state, the answer to\n\
+         -- the last question, and the form's parameters, and it returns either the next question to ask,\n\
+         -- an error to show the user, or the final result of the form. `Main` should be a pure function:\n\
+         -- everything it needs to remember should go in `state`, which Birocrat will give back to it.\n",
+    );
+    let params = options
+        .params
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>();
+    if !params.is_empty() {
+        script.push_str(&format!(
+            "--\n-- Parameters (available in `params`): {}\n",
+            params.join(", ")
+        ));
+    }
+
+    // The questions themselves
+    script.push_str(
+        "\n-- Every question the form can ask. Each one needs a unique ID, which Birocrat uses to remember\n\
+         -- answers if the user goes back and changes an earlier one.\n\
+         local questions = {\n",
+    );
+    let example_questions = [
+        ("simple", "name", "What is your name?", ""),
+        ("multiline", "bio", "Tell us a bit about yourself.", ""),
+        ("secret", "token", "What is your access token?", ""),
+        (
+            "select",
+            "colour",
+            "What is your favourite colour?",
+            "\t\toptions = { \"Red\", \"Green\", \"Blue\" },\n\t\tmultiple = false,\n",
+        ),
+    ];
+    let mut text_ids = Vec::new();
+    for (ty, id, text, extra) in example_questions {
+        if !options.question_types.iter().any(|t| t == ty) {
+            continue;
+        }
+        if ty != "select" {
+            text_ids.push(id);
+        }
+        script.push_str(&format!(
+            "\t{{\n\t\tid = \"{id}\",\n\t\ttype = \"{ty}\",\n\t\ttext = \"{text}\",\n{extra}\t}},\n"
+        ));
+    }
+    script.push_str("}\n");
+
+    // Validation helpers, if the user wants them
+    if options.validation {
+        script.push_str(
+            "\n-- Returns an error message if the given text is empty, or `nil` otherwise.\n\
+             local function not_empty(text)\n\
+             \tif text:match(\"^%s*$\") then\n\
+             \t\treturn \"Please enter an answer.\"\n\
+             \tend\n\
+             \treturn nil\n\
+             end\n\
+             \n\
+             -- Validators for the answers to particular questions, keyed by question ID. Each is given the\n\
+             -- answer and returns an error message if it's invalid, or `nil` if it's fine.\n\
+             local validators = {\n",
+        );
+        for id in text_ids {
+            script.push_str(&format!("\t{id} = not_empty,\n"));
+        }
+        script.push_str("}\n");
+    }
+
+    // The driver function
+    script.push_str(
+        "\nfunction Main(state, answer, params)\n\
+         \t-- If this is the first time the script has been run, ask the first question\n\
+         \tif state == nil then\n\
+         \t\treturn { \"question\", questions[1], { question = 1, answers = {} } }\n\
+         \tend\n\
+         \n\
+         \t-- Record the answer to the question we just asked\n\
+         \tlocal question = questions[state.question]\n\
+         \tlocal value = answer.text\n\
+         \tif answer.type == \"options\" then\n\
+         \t\tvalue = answer.selected\n\
+         \tend\n",
+    );
+    if options.validation {
+        script.push_str(
+            "\tlocal validator = validators[question.id]\n\
+             \tif validator ~= nil then\n\
+             \t\tlocal err = validator(value)\n\
+             \t\tif err ~= nil then\n\
+             \t\t\t-- The user will be asked this question again\n\
+             \t\t\treturn { \"error\", err }\n\
+             \t\tend\n\
+             \tend\n",
+        );
+    }
+    script.push_str(
+        "\tstate.answers[question.id] = value\n\
+         \n\
+         \t-- Move on to the next question, or finish if there are none left (this is where you'd decide\n\
+         \t-- which question to ask next based on the answers so far)\n\
+         \tstate.question = state.question + 1\n\
+         \tif questions[state.question] == nil then\n\
+         \t\treturn { \"done\", state.answers }\n\
+         \tend\n\
+         \treturn { \"question\", questions[state.question], state }\n\
+         end\n",
+    );
+
+    script
+}
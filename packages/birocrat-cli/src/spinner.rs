@@ -0,0 +1,53 @@
+use dialoguer::console::Term;
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The frames of the spinner, which are shown one after the other.
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// How long to wait before showing the spinner, so calls that finish quickly don't make it flicker.
+const SPINNER_DELAY: Duration = Duration::from_millis(250);
+/// How long to show each frame of the spinner for.
+const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Makes the given call to the driver script, showing a spinner with the time elapsed in the
+/// terminal if it takes a while (scripts can call out to host functions that might be slow), so
+/// the user knows we haven't hung. The spinner is redrawn in place, so it's only shown if `spinner`
+/// is set and stderr is a terminal. This returns how long the call took along with its result.
+pub fn with_spinner<T>(spinner: bool, call: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let term = Term::stderr();
+    let spinner = (spinner && term.is_term()).then(|| {
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            if stop_rx.recv_timeout(SPINNER_DELAY) != Err(RecvTimeoutError::Timeout) {
+                return;
+            }
+            for frame in FRAMES.iter().cycle() {
+                // If we can't draw the spinner, there's nothing useful we can do about it
+                let _ = term.clear_line();
+                let _ = term.write_str(&format!(
+                    "{frame} Working... {:.1}s",
+                    start.elapsed().as_secs_f64()
+                ));
+                // We'll be stopped by the sender being dropped
+                if stop_rx.recv_timeout(FRAME_INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            }
+            let _ = term.clear_line();
+        });
+        (stop_tx, handle)
+    });
+
+    let result = call();
+    if let Some((stop_tx, handle)) = spinner {
+        drop(stop_tx);
+        // The spinner thread can't panic in any way we care about
+        let _ = handle.join();
+    }
+
+    (result, start.elapsed())
+}
@@ -0,0 +1,135 @@
+use crate::error::Error;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Renders an output path template like `configs/{{env}}/{{service}}.json`, filling in each
+/// `{{key}}` from the form's output object (where `key` may be a dotted path into nested objects),
+/// or, failing that, from the answer to the question with that ID.
+///
+/// Every substituted value must be a string, number, or boolean (or an array of exactly one of
+/// those, as with single selections), and must be safe to use as a single path component (i.e. it
+/// can't be empty, `.`, or `..`, or contain path or drive separators), so that form answers can
+/// never make us write outside the directory the template describes.
+pub fn render_path_template(
+    template: &str,
+    output: &Value,
+    answers: &serde_json::Map<String, Value>,
+) -> Result<PathBuf, Error> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_start = &rest[start + 2..];
+        let end = after_start
+            .find("}}")
+            .ok_or_else(|| Error::OutputTemplateUnclosed {
+                template: template.to_string(),
+            })?;
+        let key = after_start[..end].trim();
+
+        let value = lookup(output, key)
+            .or_else(|| answers.get(key))
+            .ok_or_else(|| Error::OutputTemplateMissingValue {
+                key: key.to_string(),
+            })?;
+        // Single selections from select questions will be arrays with one element
+        let value = match value {
+            Value::Array(arr) if arr.len() == 1 => &arr[0],
+            value => value,
+        };
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            _ => {
+                return Err(Error::OutputTemplateNonScalar {
+                    key: key.to_string(),
+                })
+            }
+        };
+        if !is_safe_component(&value) {
+            return Err(Error::OutputTemplateUnsafeValue {
+                key: key.to_string(),
+                value,
+            });
+        }
+        rendered.push_str(&value);
+
+        rest = &after_start[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(PathBuf::from(rendered))
+}
+
+/// Looks up a dotted path (e.g. `service.name`) in a JSON value.
+fn lookup<'v>(value: &'v Value, key: &str) -> Option<&'v Value> {
+    key.split('.').try_fold(value, |value, part| match value {
+        Value::Object(map) => map.get(part),
+        Value::Array(arr) => arr.get(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Checks whether or not the given value can be safely used as a single path component.
+fn is_safe_component(value: &str) -> bool {
+    !value.is_empty() && value != "." && value != ".." && !value.contains(['/', '\\', ':', '\0'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_render_templates() {
+        let output = json!({ "env": "prod", "service": { "name": "api", "port": 8080 } });
+        let answers = json!({ "region": ["eu"], "env": "dev" });
+        let path = render_path_template(
+            "configs/{{env}}/{{ service.name }}-{{service.port}}-{{region}}.json",
+            &output,
+            answers.as_object().unwrap(),
+        )
+        .unwrap();
+        // The output takes precedence over the answers
+        assert_eq!(path, PathBuf::from("configs/prod/api-8080-eu.json"));
+    }
+
+    #[test]
+    fn should_reject_unsafe_values() {
+        for value in ["", ".", "..", "a/b", "a\\b", "C:", "a\0b"] {
+            let output = json!({ "name": value });
+            assert!(
+                matches!(
+                    render_path_template("out/{{name}}.json", &output, &Default::default()),
+                    Err(Error::OutputTemplateUnsafeValue { .. })
+                ),
+                "{value:?} should be rejected"
+            );
+        }
+        assert!(is_safe_component("..."));
+        assert!(is_safe_component("v1.2"));
+    }
+
+    #[test]
+    fn should_reject_missing_and_non_scalar_values() {
+        let output = json!({ "tags": ["a", "b"], "nested": { "a": 1 } });
+        let render = |template| render_path_template(template, &output, &Default::default());
+        assert!(matches!(
+            render("{{tags}}"),
+            Err(Error::OutputTemplateNonScalar { .. })
+        ));
+        assert!(matches!(
+            render("{{nested}}"),
+            Err(Error::OutputTemplateNonScalar { .. })
+        ));
+        assert!(matches!(
+            render("{{missing}}"),
+            Err(Error::OutputTemplateMissingValue { .. })
+        ));
+        assert!(matches!(
+            render("{{nested.a"),
+            Err(Error::OutputTemplateUnclosed { .. })
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    answers,
+    cli::TestArgs,
+    diff::json_diff,
+    error::Error,
+    headless::run_headless,
+    report::{render_report, ScenarioFailure, ScenarioResult},
+    utils,
+};
+use fmterr::fmterr;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path, time::Instant};
+
+/// A single test scenario for a form script.
+#[derive(Deserialize)]
+struct Scenario {
+    /// The name of the scenario, used in reports.
+    name: String,
+    /// The parameters to give the form.
+    #[serde(default = "empty_object")]
+    params: Value,
+    /// The answers to give to the form's questions, keyed by question ID (in the same format
+    /// accepted by `--answers`).
+    #[serde(default = "empty_object")]
+    answers: Value,
+    /// The output the form should produce. If this isn't given, the scenario will pass as long as
+    /// the form completes.
+    expected: Option<Value>,
+    /// The seed for anything random the form does, so scenarios can cover each of its choices.
+    #[serde(default)]
+    seed: u64,
+    /// The variants of the experiments the form runs to use, keyed by experiment (any that aren't
+    /// given will be chosen from the seed).
+    #[serde(default)]
+    variants: HashMap<String, String>,
+}
+
+fn empty_object() -> Value {
+    Value::Object(serde_json::Map::new())
+}
+
+/// Runs every test scenario in the given file against a form script, printing the results and
+/// optionally writing a machine-readable report.
+pub fn test(args: TestArgs, forms_dir: Option<&Path>) -> Result<(), Error> {
+    let script = utils::read_script(&args.script, forms_dir)?;
+    let scenarios = read_scenarios(&args.scenarios)?;
+
+    let mut results = Vec::new();
+    for scenario in scenarios {
+        let start = Instant::now();
+        let failure = run_scenario(&script, &scenario);
+        let result = ScenarioResult {
+            name: scenario.name,
+            duration: start.elapsed(),
+            failure,
+        };
+
+        match &result.failure {
+            None => eprintln!(
+                "test {} ... ok ({:.3}s)",
+                result.name,
+                result.duration.as_secs_f64()
+            ),
+            Some(failure) => {
+                eprintln!(
+                    "test {} ... FAILED ({:.3}s)",
+                    result.name,
+                    result.duration.as_secs_f64()
+                );
+                eprintln!("    {}", failure.message);
+                for line in &failure.diff {
+                    eprintln!("    {line}");
+                }
+            }
+        }
+        results.push(result);
+    }
+
+    let failed = results.iter().filter(|r| r.failure.is_some()).count();
+    eprintln!(
+        "\ntest result: {} passed; {failed} failed",
+        results.len() - failed
+    );
+
+    if let Some(format) = args.report {
+        let report = render_report(format, &args.script, &results);
+        match args.report_file {
+            Some(path) => fs::write(&path, report).map_err(|err| Error::WriteReportFailed {
+                source: err,
+                target: path,
+            })?,
+            None => println!("{report}"),
+        }
+    }
+
+    if failed > 0 {
+        Err(Error::TestsFailed { failed })
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs a single scenario, returning details of why it failed, if it did.
+fn run_scenario(script: &str, scenario: &Scenario) -> Option<ScenarioFailure> {
+    let failure = |message: String| {
+        Some(ScenarioFailure {
+            message,
+            diff: Vec::new(),
+        })
+    };
+
+    let answers = match answers::parse_answers(scenario.answers.clone()) {
+        Ok(answers) => answers.answers,
+        Err(err) => return failure(fmterr(&err)),
+    };
+    let output = match run_headless(
+        script,
+        &scenario.params,
+        scenario.seed,
+        &scenario.variants,
+        &answers,
+    ) {
+        Ok(Ok(output)) => output,
+        Ok(Err(headless_failure)) => return failure(headless_failure.to_string()),
+        Err(err) => return failure(fmterr(&err)),
+    };
+
+    match &scenario.expected {
+        Some(expected) if expected != &output => Some(ScenarioFailure {
+            message: "form output did not match expected output".to_string(),
+            diff: json_diff(expected, &output)
+                .into_iter()
+                .map(|diff| diff.to_string())
+                .collect(),
+        }),
+        _ => None,
+    }
+}
+
+/// Reads an array of test scenarios from the given JSON file.
+fn read_scenarios(path: &Path) -> Result<Vec<Scenario>, Error> {
+    let contents = fs::read_to_string(path).map_err(|err| Error::ReadScenariosFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })?;
+    serde_json::from_str(&contents).map_err(|err| Error::ParseScenariosFailed {
+        source: err,
+        target: path.to_path_buf(),
+    })
+}
@@ -1,8 +1,117 @@
-use crate::error::Error;
-use dialoguer::{Editor, Input, MultiSelect, Select};
+use crate::{accessible, cli::ParamsArgs, error::Error};
+use birocrat::SelectOption;
+use dialoguer::{Confirm, Editor, FuzzySelect, Input, MultiSelect, Password, Select};
+use serde_json::Value;
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-/// Reads a single-line input from the terminal using `dialoguer`.
-pub fn read_simple(prompt: &str, default: Option<String>) -> Result<String, Error> {
+/// Reads the driver script for a form, taking it from stdin if the user gave `-`, and otherwise
+/// treating what they gave as a path, or, if there's no such file, the name of a form in the
+/// forms directory.
+pub fn read_script(script: &str, forms_dir: Option<&Path>) -> Result<String, Error> {
+    match script {
+        "-" => {
+            let mut buffer = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|err| Error::ReadScriptFromStdinFailed { source: err })?;
+            Ok(buffer)
+        }
+        _ => fs::read_to_string(resolve_script_path(script, forms_dir))
+            .map_err(|err| Error::ReadScriptFailed { source: err }),
+    }
+}
+
+/// Works out where the given script is, treating it as a path if there's a file there, and
+/// otherwise as the name of a form in the forms directory, if there's one of those.
+pub fn resolve_script_path(script: &str, forms_dir: Option<&Path>) -> PathBuf {
+    let path = PathBuf::from(script);
+    if path.exists() {
+        return path;
+    }
+    match forms_dir {
+        Some(forms_dir) => {
+            let form_path = forms_dir.join(format!("{script}.lua"));
+            if form_path.exists() {
+                form_path
+            } else {
+                path
+            }
+        }
+        None => path,
+    }
+}
+
+/// Lists the names of all the forms in the given forms directory (i.e. the Lua scripts in it,
+/// without their extensions), sorted alphabetically. If the directory can't be read, this will
+/// return no forms.
+pub fn list_forms(forms_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(forms_dir) else {
+        return Vec::new();
+    };
+    let mut forms = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "lua"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect::<Vec<_>>();
+    forms.sort();
+
+    forms
+}
+
+/// Parses the parameters the user gave for a form, which will either be a series of `key=value`
+/// pairs or the path to a JSON file.
+pub fn parse_params(args: ParamsArgs) -> Result<Value, Error> {
+    let params = match (args.params, args.json_params) {
+        (Some(params), None) => Value::Object(
+            params
+                .into_iter()
+                .map(|p| p.splitn(2, '=').map(|s| s.to_string()).collect())
+                .map(|mut parts: Vec<String>| {
+                    (
+                        parts.remove(0),
+                        if parts.is_empty() {
+                            String::new()
+                        } else {
+                            parts.remove(0)
+                        },
+                    )
+                })
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect::<serde_json::Map<_, _>>(),
+        ),
+        (None, Some(json_params)) => {
+            let json_params =
+                fs::read_to_string(&json_params).map_err(|err| Error::ReadJsonParamsFailed {
+                    source: err,
+                    target: json_params,
+                })?;
+            serde_json::from_str(&json_params).map_err(|err| Error::ParseJsonParamsFailed {
+                source: err,
+                target: json_params,
+            })?
+        }
+        (None, None) => Value::Object(serde_json::Map::new()),
+        _ => unreachable!(),
+    };
+
+    Ok(params)
+}
+
+/// Reads a single-line input from the terminal using `dialoguer` (or plain prompts, if
+/// `accessible` is set).
+pub fn read_simple(
+    prompt: &str,
+    default: Option<String>,
+    accessible: bool,
+) -> Result<String, Error> {
+    if accessible {
+        return accessible::read_simple(prompt, default);
+    }
     let input = if let Some(default) = default {
         Input::<String>::new().with_prompt(prompt).default(default)
     } else {
@@ -13,17 +122,113 @@ pub fn read_simple(prompt: &str, default: Option<String>) -> Result<String, Erro
     Ok(input)
 }
 
+/// Reads a single-line input from the terminal using `dialoguer` (or a plain prompt, if
+/// `accessible` is set), which the user can leave empty.
+pub fn read_optional(prompt: &str, accessible: bool) -> Result<String, Error> {
+    if accessible {
+        return accessible::read_simple(&format!("{prompt} (optional)"), None);
+    }
+    let input = Input::<String>::new()
+        .with_prompt(format!("{prompt} (optional)"))
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(input)
+}
+
+/// Shows the given information in the terminal and waits for the user to press Enter (using a
+/// plain prompt, if `accessible` is set).
+pub fn acknowledge(text: &str, accessible: bool) -> Result<(), Error> {
+    if accessible {
+        return accessible::acknowledge(text);
+    }
+    eprintln!("{text}");
+    Input::<String>::new()
+        .with_prompt("Press Enter to continue")
+        .allow_empty(true)
+        .interact()?;
+
+    Ok(())
+}
+
+/// Asks the user a yes-or-no question in the terminal using `dialoguer` (or a plain prompt, if
+/// `accessible` is set), returning their answer. If they don't give one, this will be `false`.
+pub fn confirm(prompt: &str, accessible: bool) -> Result<bool, Error> {
+    if accessible {
+        return accessible::confirm(prompt);
+    }
+    let answer = Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()?;
+
+    Ok(answer)
+}
+
+/// Reads paths to existing files from the terminal using `dialoguer`, asking again until each
+/// path the user gives exists. If `multiple` is set, the user can give as many paths as they like,
+/// finishing with an empty input. If `accessible` is set, this will use plain prompts.
+pub fn read_paths(prompt: &str, multiple: bool, accessible: bool) -> Result<Vec<PathBuf>, Error> {
+    if accessible {
+        return accessible::read_paths(prompt, multiple);
+    }
+    let mut paths = Vec::new();
+    loop {
+        let can_finish = multiple && !paths.is_empty();
+        let prompt = if can_finish {
+            format!("{prompt} (leave empty to finish)")
+        } else {
+            prompt.to_string()
+        };
+        let input = Input::<String>::new()
+            .with_prompt(prompt)
+            .allow_empty(can_finish)
+            .validate_with(|input: &String| {
+                if input.is_empty() || Path::new(input).is_file() {
+                    Ok(())
+                } else {
+                    Err(format!("no file found at '{input}'"))
+                }
+            })
+            .interact()?;
+        if input.is_empty() {
+            break;
+        }
+        paths.push(PathBuf::from(input));
+        if !multiple {
+            break;
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Reads a single-line secret input from the terminal using `dialoguer`, masking what the user
+/// types. If `accessible` is set, this will use a plain prompt.
+pub fn read_secret(prompt: &str, accessible: bool) -> Result<String, Error> {
+    if accessible {
+        return accessible::read_secret(prompt);
+    }
+    let input = Password::new().with_prompt(prompt).interact()?;
+
+    Ok(input)
+}
+
 /// Reads a multi-line input from the terminal using `dialoguer`.
 ///
 /// This takes a prompt, which will be provided as a comment, along with some starter text for the
-/// user to actually edit. This is performed through the system's text editor.
-pub fn read_multiple(prompt: &str, starter: &str) -> Result<String, Error> {
+/// user to actually edit. This is performed through the system's text editor, unless `accessible`
+/// is set, in which case the user will type their answer line by line instead.
+pub fn read_multiple(prompt: &str, starter: &str, accessible: bool) -> Result<String, Error> {
+    if accessible {
+        return accessible::read_multiple(prompt, starter);
+    }
     let prompt = prompt.replace("\n", "\n# ");
     let edit_str = format!("#{prompt}\n\n{starter}");
 
     let input = Editor::new().edit(&edit_str)?;
     // If the user didn't provide any input (i.e. file not saved in editor), return an empty string
-    let input = input.unwrap_or_else(|| String::new());
+    let input = input.unwrap_or_default();
 
     // Strip off the leading commented lines
     let real_input = input
@@ -37,27 +242,85 @@ pub fn read_multiple(prompt: &str, starter: &str) -> Result<String, Error> {
 }
 
 /// Gives the user an option between several values and allows them to select one, returning it.
+/// If a default value is given, it will be selected initially, otherwise the first option will be.
 ///
-/// This returns `&String` rather than `&str` for compatibility with [`select_multiple`].
-pub fn select_one<'o>(prompt: &str, options: &'o Vec<String>) -> Result<&'o String, Error> {
-    let selection = Select::new()
+/// If `searchable` is set, the user will be able to narrow down the options by typing. If
+/// `accessible` is set, the user will choose the option by its number instead. Either way, no more
+/// than `page_size` options will be shown at once, if it's given.
+pub fn select_one<'o>(
+    prompt: &str,
+    options: &'o [SelectOption],
+    default: Option<&str>,
+    searchable: bool,
+    page_size: Option<usize>,
+    accessible: bool,
+) -> Result<&'o SelectOption, Error> {
+    if accessible {
+        return accessible::select_one(prompt, options, default, page_size);
+    }
+    let default_idx = default
+        .and_then(|default| options.iter().position(|o| o.value == default))
+        .unwrap_or(0);
+    if searchable {
+        let mut select = FuzzySelect::new()
+            .with_prompt(prompt)
+            .items(&option_items(options))
+            .default(default_idx);
+        if let Some(page_size) = page_size {
+            select = select.max_length(page_size);
+        }
+        return Ok(&options[select.interact()?]);
+    }
+    let mut select = Select::new()
         .with_prompt(prompt)
-        .items(&options)
-        .interact()?;
+        .items(&option_items(options))
+        .default(default_idx);
+    if let Some(page_size) = page_size {
+        select = select.max_length(page_size);
+    }
+    let selection = select.interact()?;
 
     Ok(&options[selection])
 }
 
 /// Gives the user options between several values, allowing them to select multiple, and returning
-/// it.
+/// it. The options with the given default values will start selected. If `accessible` is set, the
+/// user will choose the options by their numbers instead. Either way, no more than `page_size`
+/// options will be shown at once, if it's given.
 pub fn select_multiple<'o>(
     prompt: &str,
-    options: &'o Vec<String>,
-) -> Result<Vec<&'o String>, Error> {
-    let selections = MultiSelect::new()
+    options: &'o [SelectOption],
+    defaults: &[&str],
+    page_size: Option<usize>,
+    accessible: bool,
+) -> Result<Vec<&'o SelectOption>, Error> {
+    if accessible {
+        return accessible::select_multiple(prompt, options, defaults, page_size);
+    }
+    let checked = options
+        .iter()
+        .map(|option| defaults.contains(&option.value.as_str()))
+        .collect::<Vec<_>>();
+    let mut select = MultiSelect::new()
         .with_prompt(prompt)
-        .items(&options)
-        .interact()?;
+        .items(&option_items(options))
+        .defaults(&checked);
+    if let Some(page_size) = page_size {
+        select = select.max_length(page_size);
+    }
+    let selections = select.interact()?;
 
     Ok(selections.into_iter().map(|i| &options[i]).collect())
 }
+
+/// Gets the text to show the user for each of the given options, which will be their labels,
+/// followed by their descriptions if they have them.
+pub fn option_items(options: &[SelectOption]) -> Vec<String> {
+    options
+        .iter()
+        .map(|option| match &option.description {
+            Some(description) => format!("{} ({description})", option.label()),
+            None => option.label().to_string(),
+        })
+        .collect()
+}
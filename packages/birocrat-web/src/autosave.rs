@@ -0,0 +1,104 @@
+use crate::{offline::Online, state::FormHandle};
+use leptos::{
+    create_effect, leptos_dom::helpers::TimeoutHandle, set_timeout_with_handle, spawn_local,
+    store_value, RwSignal, SignalGet, SignalSet, SignalWith,
+};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Settings for automatically saving the user's progress to a server.
+#[derive(Debug, Clone)]
+pub struct AutosaveOptions {
+    /// The URL to `POST` the user's progress to.
+    pub endpoint: String,
+    /// How long to wait after the user's last answer before saving, so we don't send a request
+    /// for every answer when the user is moving quickly.
+    pub delay: Duration,
+}
+
+/// Sets up automatic saving of the given form's progress to a server. Whenever the user's answers
+/// change, and once no further changes have been made for the configured delay, the session will be
+/// `POST`ed to the endpoint as JSON of the form
+/// `{ "params": { ... }, "seed": "...", "answers": { ... } }`. The seed and answers are in the same
+/// format the `seed` and `answers` options take, so a saved session can be resumed (e.g. on another
+/// device) by passing them back in. Each time a save succeeds, the answers saved
+/// will be put in `last_saved`. While the user is offline, saving will be held off until they're
+/// back online, when their latest answers will be saved.
+pub fn setup_autosave(
+    form: FormHandle,
+    params: Value,
+    seed: u64,
+    options: AutosaveOptions,
+    last_saved: RwSignal<Option<Map<String, Value>>>,
+    online: Online,
+) {
+    let pending = store_value(None::<TimeoutHandle>);
+    // This gives whether or not the form had been loaded the last time it ran
+    create_effect(move |loaded: Option<bool>| {
+        let answers = form.snapshot.with(|snapshot| snapshot.answers_json());
+        let online = online.0.get();
+        let loaded_now = !form.loading.get();
+        // There's no point saving the session as we loaded it
+        if loaded != Some(true) {
+            return loaded_now;
+        }
+
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        // This will run again when we come back online
+        if !online {
+            pending.set_value(None);
+            return true;
+        }
+        let body =
+            json!({ "params": params, "seed": seed.to_string(), "answers": answers }).to_string();
+        let endpoint = options.endpoint.clone();
+        let handle = set_timeout_with_handle(
+            move || {
+                spawn_local(async move {
+                    if save(endpoint, body).await {
+                        last_saved.set(Some(answers));
+                    }
+                })
+            },
+            options.delay,
+        )
+        .ok();
+        pending.set_value(handle);
+        true
+    });
+}
+
+/// Sends the given session to the autosave endpoint, returning whether or not it was saved. Any
+/// failures will be logged to the console (there's nothing the user can do about them, and they
+/// shouldn't interrupt the form).
+async fn save(endpoint: String, body: String) -> bool {
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_body(&JsValue::from_str(&body));
+    let headers = web_sys::Headers::new().unwrap();
+    headers.set("Content-Type", "application/json").unwrap();
+    init.set_headers(&headers);
+
+    let request = web_sys::window()
+        .unwrap()
+        .fetch_with_str_and_init(&endpoint, &init);
+    match JsFuture::from(request).await {
+        Ok(res) => {
+            let res = res.unchecked_into::<web_sys::Response>();
+            if !res.ok() {
+                web_sys::console::error_1(
+                    &format!("failed to autosave form (status {})", res.status()).into(),
+                );
+            }
+            res.ok()
+        }
+        Err(err) => {
+            web_sys::console::error_2(&"failed to autosave form:".into(), &err);
+            false
+        }
+    }
+}
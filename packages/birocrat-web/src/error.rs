@@ -0,0 +1,53 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum Error {
+    #[error(transparent)]
+    FormError(#[from] birocrat::error::Error),
+    #[error("options must be an object")]
+    OptionsNotObject,
+    #[error("invalid form parameters in options")]
+    InvalidParams {
+        #[source]
+        source: serde_wasm_bindgen::Error,
+    },
+    #[error("answers in options must be an object keyed by question id")]
+    AnswersNotObject,
+    #[error(
+        "invalid answer for question '{id}' in options (expected a string, a number, `true`, `null`, an array of options or group entries, or an object of answers)"
+    )]
+    InvalidAnswer { id: String },
+    #[error("invalid autosave options (expected an object with an `endpoint` string and an optional `delay` in milliseconds)")]
+    InvalidAutosave,
+    #[error("invalid seed (expected a string of digits, or a whole number)")]
+    InvalidSeed,
+    #[error("invalid variants (expected an object of variant names keyed by experiment)")]
+    InvalidVariants,
+    #[error("invalid routing option (expected a boolean)")]
+    InvalidRouting,
+    #[error("invalid challenge hook (expected a function)")]
+    InvalidChallenge,
+    #[error("invalid error hook (expected a function)")]
+    InvalidErrorHook,
+    #[error("invalid submission hook (expected a function)")]
+    InvalidSubmitHook,
+    #[error("invalid event hook (expected a function)")]
+    InvalidEventHook,
+    #[error("invalid layout '{layout}' (expected 'stepper' or 'single-page')")]
+    InvalidLayout { layout: String },
+    #[error("invalid transition '{transition}' (expected 'none', 'fade', or 'slide')")]
+    InvalidTransition { transition: String },
+    #[error("invalid focus option '{focus}' (expected 'stay' or 'question')")]
+    InvalidFocus { focus: String },
+    #[error("failed to start the worker to run the form in: {message}")]
+    SpawnWorkerFailed { message: String },
+}
+
+/// A hard error from a running form, which will be caught by the error boundary around it. Unlike
+/// [`Error`], this can be sent between threads, as error boundaries require.
+#[derive(Error, Debug, Clone)]
+#[error("{message}")]
+pub struct FormFailure {
+    pub message: String,
+}
@@ -0,0 +1,107 @@
+use js_sys::{Function, Promise};
+use serde_json::Value;
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Functions the host page has provided to customise how forms behave. These are provided as
+/// context to every component in a form.
+#[derive(Clone, Debug, Default)]
+pub struct Hooks {
+    /// A function to call before a form is submitted, which should have the user complete some
+    /// challenge (e.g. a captcha) and return a token (or a promise resolving to one) that the host
+    /// can verify along with the submission. If this throws or rejects, the form won't be
+    /// submitted.
+    pub challenge: Option<Function>,
+    /// A function to call with diagnostics whenever the form fails, so the host can report them.
+    /// See [`crate::recovery::report_failure`] for what this is given.
+    pub on_error: Option<Function>,
+    /// A function to call with a form's output (and the token from the challenge hook, if there
+    /// is one) when the user submits it, which should return a promise resolving once the host has
+    /// handled the submission. If the host rejects the submission, this should resolve to an
+    /// object with `fieldErrors`, mapping question IDs to error messages for those questions, which
+    /// will be shown to the user so they can change their answers. If this throws or rejects, the
+    /// user will be shown the error and can try again.
+    pub on_submit: Option<Function>,
+    /// A function to call with structured events as the user moves through a form (see
+    /// [`emit_event`]), so the host can feed them into their analytics.
+    pub on_event: Option<Function>,
+}
+
+/// Calls the host's challenge hook, waiting for it to produce a token. If it fails, this will
+/// return a message to show the user.
+pub async fn run_challenge(challenge: &Function) -> Result<String, String> {
+    let result = challenge
+        .call0(&JsValue::NULL)
+        .map_err(|err| describe_js_error(&err))?;
+    // Hooks can return tokens directly or through a promise, and this handles both
+    let token = JsFuture::from(Promise::resolve(&result))
+        .await
+        .map_err(|err| describe_js_error(&err))?;
+    token
+        .as_string()
+        .ok_or_else(|| "verification did not produce a token".to_string())
+}
+
+/// Calls the host's submission hook with the given output and challenge token, waiting for it to
+/// handle the submission. This will return any errors the host gave for particular questions,
+/// keyed by question ID (which will be empty if the host accepted the submission), or a message to
+/// show the user if the hook failed.
+pub async fn run_on_submit(
+    on_submit: &Function,
+    output: &Value,
+    challenge_token: Option<&str>,
+) -> Result<HashMap<String, String>, String> {
+    // This is valid JSON, so parsing it can't fail
+    let output = js_sys::JSON::parse(&output.to_string()).unwrap();
+    let challenge_token = challenge_token
+        .map(JsValue::from_str)
+        .unwrap_or(JsValue::NULL);
+    let result = on_submit
+        .call2(&JsValue::NULL, &output, &challenge_token)
+        .map_err(|err| describe_js_error(&err))?;
+    let result = JsFuture::from(Promise::resolve(&result))
+        .await
+        .map_err(|err| describe_js_error(&err))?;
+
+    if !result.is_object() {
+        return Ok(HashMap::new());
+    }
+    // We've checked this is an object, so this can't fail
+    let field_errors = js_sys::Reflect::get(&result, &"fieldErrors".into()).unwrap();
+    if field_errors.is_undefined() || field_errors.is_null() {
+        return Ok(HashMap::new());
+    }
+    serde_wasm_bindgen::from_value(field_errors).map_err(|_| {
+        "submission was rejected with invalid field errors (expected an object of strings)"
+            .to_string()
+    })
+}
+
+/// Sends an event to the host's event hook, if they provided one. This will be given an object with
+/// the `type` of event (`"questionViewed"`, `"answered"`, `"validationFailed"`, `"abandoned"`, or
+/// `"completed"`) and the given details (e.g. `questionId`). If the hook throws, the error is only
+/// logged, because analytics shouldn't get in the user's way.
+pub fn emit_event(on_event: Option<&Function>, ty: &str, details: Value) {
+    let Some(on_event) = on_event else {
+        return;
+    };
+    let mut event = match details {
+        Value::Object(details) => details,
+        _ => serde_json::Map::new(),
+    };
+    event.insert("type".to_string(), Value::String(ty.to_string()));
+    // This is valid JSON, so parsing it can't fail
+    let event = js_sys::JSON::parse(&Value::Object(event).to_string()).unwrap();
+    if let Err(err) = on_event.call1(&JsValue::NULL, &event) {
+        web_sys::console::error_2(&"event hook failed:".into(), &err);
+    }
+}
+
+/// Gets a message describing an error thrown from JS code.
+fn describe_js_error(err: &JsValue) -> String {
+    err.dyn_ref::<js_sys::Error>()
+        .map(|err| String::from(err.message()))
+        .or_else(|| err.as_string())
+        .unwrap_or_else(|| "unknown error".to_string())
+}
@@ -0,0 +1,563 @@
+use crate::{
+    hooks::{run_challenge, run_on_submit, Hooks},
+    markdown::prompt_text,
+    offline::Online,
+    question::{QuestionInput, Suggest},
+    routing::{on_navigate, Route},
+    state::FormHandle,
+    transcript::{answer_text, TranscriptView},
+};
+use birocrat::{Answer, Question};
+use leptos::{
+    component, create_effect, create_node_ref, create_rw_signal, html::Div,
+    request_animation_frame, spawn_local, store_value, untrack, use_context, view, window,
+    Callable, Callback, CollectView, IntoView, NodeRef, SignalGet, SignalGetUntracked, SignalSet,
+    SignalWith, SignalWithUntracked,
+};
+use std::{collections::HashMap, rc::Rc};
+use wasm_bindgen::JsCast;
+
+/// Shown when the host rejects a submission because of some of the user's answers.
+const FIELD_ERRORS_MESSAGE: &str = "Some of your answers need to be changed before you can submit.";
+/// Shown while a submission is waiting for the user to come back online.
+const QUEUED_MESSAGE: &str =
+    "You're offline, so your answers will be submitted as soon as you're back online.";
+
+/// The ways a form can be laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// One question at a time, with a button to go back to previous questions. This suits shorter
+    /// forms best.
+    #[default]
+    Stepper,
+    /// Every question on a single page, which grows as the user answers questions. Answered
+    /// questions remain visible and editable above the current one, which suits longer forms
+    /// best.
+    SinglePage,
+}
+
+/// How a new question should appear when the user moves to it. Whatever this is, users who've
+/// asked their browser to reduce motion won't see any.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transition {
+    /// The new question just appears.
+    #[default]
+    None,
+    /// The new question fades in.
+    Fade,
+    /// The new question slides in from below as it fades in.
+    Slide,
+}
+impl Transition {
+    /// Gets the class that animates a question appearing with this transition (see `index.css`).
+    fn class(self) -> &'static str {
+        match self {
+            Transition::None => "",
+            Transition::Fade => "birocrat-fade",
+            Transition::Slide => "birocrat-slide",
+        }
+    }
+}
+
+/// Where focus should go when the user moves to a new question.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Focus {
+    /// Focus stays where it is (which may be nowhere, if the element that had it was replaced).
+    #[default]
+    Stay,
+    /// Focus moves to the first input of the new question, so keyboard users can carry straight
+    /// on.
+    Question,
+}
+
+/// A form laid out one question at a time, with a list of the user's answers so far that they can
+/// jump back to. If `routing` is set, the question being shown will be kept in the URL hash, so the
+/// browser's back and forward buttons move between questions. `transition` and `focus` control
+/// what happens when the user moves to another question.
+#[component]
+pub fn Stepper(
+    form: FormHandle,
+    routing: bool,
+    transition: Transition,
+    focus: Focus,
+) -> impl IntoView {
+    // The index of the question being shown, which will be the next question unless the user has
+    // gone back
+    let viewing = create_rw_signal(form.snapshot.with_untracked(|s| s.answered.len()));
+    // If the host rejects a submission, take the user to the first question it had a problem with
+    create_effect(move |_| {
+        let first_error_idx = form.field_errors.with(|errors| {
+            form.snapshot.with_untracked(|s| {
+                s.answered
+                    .iter()
+                    .position(|answered| errors.contains_key(&answered.id))
+            })
+        });
+        if let Some(idx) = first_error_idx {
+            viewing.set(idx);
+        }
+    });
+
+    if routing {
+        // If the user has linked to a question they've answered, show that (otherwise, correct the
+        // URL to the question we're showing)
+        let linked_idx =
+            Route::current().and_then(|route| form.snapshot.with_untracked(|s| route.index_in(s)));
+        match linked_idx {
+            Some(idx) => viewing.set(idx),
+            None => form
+                .snapshot
+                .with_untracked(|s| Route::at(s, viewing.get_untracked()))
+                .set(true),
+        }
+        create_effect(move |prev: Option<()>| {
+            let route = form.snapshot.with(|s| Route::at(s, viewing.get()));
+            if prev.is_some() {
+                route.set(false);
+            }
+        });
+        on_navigate(move |route| {
+            let idx = route.and_then(|route| form.snapshot.with_untracked(|s| route.index_in(s)));
+            match idx {
+                Some(idx) => viewing.set(idx),
+                // The user has gone to a question that's no longer in the form's history (e.g.
+                // because they changed an earlier answer), so stay where we are
+                None => form
+                    .snapshot
+                    .with_untracked(|s| Route::at(s, viewing.get_untracked()))
+                    .set(true),
+            }
+        });
+    }
+    let container = create_node_ref::<Div>();
+    if focus == Focus::Question {
+        create_effect(move |prev: Option<()>| {
+            viewing.track();
+            if prev.is_some() {
+                focus_current_question(container);
+            }
+        });
+    }
+
+    let questions = move || {
+        // Once the form has been submitted, it can't be changed
+        if form.submission.with(Option::is_some) {
+            return view! { <Completion form=form/> }.into_view();
+        }
+        let snapshot = form.snapshot.get();
+        // The form answers computed steps itself, so the user skips over them both ways
+        let is_computed = |idx: usize| {
+            snapshot
+                .answered
+                .get(idx)
+                .is_some_and(|entry| matches!(entry.question, Question::Computed { .. }))
+        };
+        let mut idx = viewing.get().min(snapshot.answered.len());
+        while is_computed(idx) {
+            idx += 1;
+        }
+        let number = (0..idx).filter(|idx| !is_computed(*idx)).count() + 1;
+        let back_button = (0..idx).rev().find(|idx| !is_computed(*idx)).map(|back_idx| {
+            view! {
+                <button class="self-start underline min-h-[44px]" on:click=move |_| viewing.set(back_idx)>
+                    "Back"
+                </button>
+            }
+        });
+
+        let current = match snapshot.answered.into_iter().nth(idx) {
+            Some(answered) => Some((answered.id, answered.question, Some(answered.answer))),
+            None => snapshot.next,
+        };
+        match current {
+            Some((id, question, initial)) => view! {
+                <div class=format!("flex flex-col gap-2 {}", transition.class()) data-current-question>
+                    <p class="text-sm text-gray-500">{format!("Question {number}")}</p>
+                    <QuestionInput
+                        question=question
+                        initial=initial
+                        submit_label="Next"
+                        pending=move || form.pending.get() == Some(idx)
+                        on_answer=move |answer| {
+                            if confirm_change(form, idx, &answer) {
+                                form.answer(idx, answer, move || viewing.set(idx + 1))
+                            }
+                        }
+                        suggest=suggester(form, id.clone())
+                    />
+                    <QuestionErrors form=form idx=idx id=id/>
+                    {back_button}
+                </div>
+            }
+            .into_view(),
+            None => view! {
+                <div class=format!("flex flex-col gap-2 {}", transition.class()) data-current-question>
+                    <Completion form=form/>
+                    {back_button}
+                </div>
+            }
+            .into_view(),
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-4">
+            {move || {
+                form.submission
+                    .with(Option::is_none)
+                    .then(|| view! { <AnsweredList form=form on_edit=move |idx| viewing.set(idx)/> })
+            }}
+            <div node_ref=container>{questions}</div>
+        </div>
+    }
+}
+
+/// A collapsible, read-only list of the questions the user has answered so far and their answers,
+/// each with a button to edit it, which calls `on_edit` with the index of the question. This is
+/// only shown once there's something in it.
+#[component]
+fn AnsweredList(form: FormHandle, #[prop(into)] on_edit: Callback<usize>) -> impl IntoView {
+    move || {
+        let answered = form.snapshot.with(|s| s.answered.clone());
+        (!answered.is_empty()).then(|| {
+            let num_answered = answered.len();
+            let items = answered
+                .into_iter()
+                .enumerate()
+                // Information steps weren't really answered, so there's nothing to edit
+                .filter(|(_, entry)| !matches!(entry.question, Question::Info { .. }))
+                .map(|(idx, entry)| {
+                    // Computed steps are shown for the record, but the user can't change them
+                    let editable = !matches!(entry.question, Question::Computed { .. });
+                    view! {
+                        <li class="flex items-start justify-between gap-2">
+                            <div class="flex flex-col">
+                                <span class="font-semibold">{prompt_text(&entry.question)}</span>
+                                <span class="whitespace-pre-wrap">
+                                    {answer_text(&entry.question, entry.answer)}
+                                </span>
+                            </div>
+                            {editable
+                                .then(|| {
+                                    view! {
+                                        <button
+                                            type="button"
+                                            class="border rounded px-2 py-1 min-h-[44px]"
+                                            aria-label=format!("Edit answer to question {}", idx + 1)
+                                            on:click=move |_| on_edit.call(idx)
+                                        >
+                                            "Edit"
+                                        </button>
+                                    }
+                                })}
+                        </li>
+                    }
+                })
+                .collect_view();
+            view! {
+                <details class="border rounded p-2">
+                    <summary class="cursor-pointer min-h-[44px]">
+                        {format!("Your answers ({num_answered})")}
+                    </summary>
+                    <ol class="flex flex-col gap-2 mt-2">{items}</ol>
+                </details>
+            }
+        })
+    }
+}
+
+/// Gets suggestions for answers to the question with the given ID from the given form's script.
+fn suggester(form: FormHandle, id: String) -> Suggest {
+    Rc::new(move |text, on_suggested| form.suggest(id.clone(), text, on_suggested))
+}
+
+/// Checks with the user before changing the answer to the question at the given index if there are
+/// answers to later questions, because the script might ask different questions after it, and
+/// then those answers would have to be given again. New answers, and answers that haven't changed,
+/// don't need checking.
+fn confirm_change(form: FormHandle, idx: usize, answer: &Answer) -> bool {
+    let needs_confirming = form
+        .snapshot
+        .with_untracked(|s| idx + 1 < s.answered.len() && s.answered[idx].answer != *answer);
+    // If the browser won't show the dialog, we'll assume the user meant it
+    !needs_confirming
+        || window()
+            .confirm_with_message(
+                "Changing this answer might mean you have to answer some later questions again. \
+                Continue?",
+            )
+            .unwrap_or(true)
+}
+
+/// A form laid out on a single page, with answered questions remaining editable above the current
+/// one. If `routing` is set, the latest question will be kept in the URL hash, so users can link to
+/// questions and move between them with the browser's back and forward buttons. `transition` and
+/// `focus` control what happens when a new question appears.
+#[component]
+pub fn SinglePage(
+    form: FormHandle,
+    routing: bool,
+    transition: Transition,
+    focus: Focus,
+) -> impl IntoView {
+    if routing {
+        // If the user has linked to a question, scroll to it once it's been rendered
+        let linked_route = Route::current().filter(|route| {
+            form.snapshot
+                .with_untracked(|s| route.index_in(s))
+                .is_some()
+        });
+        match linked_route {
+            Some(route) => request_animation_frame(move || route.scroll_into_view()),
+            None => form
+                .snapshot
+                .with_untracked(|s| Route::at(s, s.answered.len()))
+                .set(true),
+        }
+        create_effect(move |prev: Option<()>| {
+            let route = form.snapshot.with(|s| Route::at(s, s.answered.len()));
+            if prev.is_some() {
+                route.set(false);
+                request_animation_frame(move || route.scroll_into_view());
+            }
+        });
+        on_navigate(|route| {
+            if let Some(route) = route {
+                route.scroll_into_view();
+            }
+        });
+    }
+    let element_id = move |route: Route| routing.then(|| route.element_id());
+    let container = create_node_ref::<Div>();
+    if focus == Focus::Question {
+        create_effect(move |prev: Option<()>| {
+            form.snapshot.with(|s| s.answered.len());
+            if prev.is_some() {
+                focus_current_question(container);
+            }
+        });
+    }
+
+    let questions = move || {
+        if form.submission.with(Option::is_some) {
+            return view! { <Completion form=form/> }.into_view();
+        }
+        let snapshot = form.snapshot.get();
+        let next_idx = snapshot.answered.len();
+        let answered = snapshot
+            .answered
+            .into_iter()
+            .enumerate()
+            // The form answers computed steps itself, so there's nothing for the user to update
+            .filter(|(_, answered)| !matches!(answered.question, Question::Computed { .. }))
+            .map(|(idx, answered)| {
+                view! {
+                    <div id=element_id(Route::Question(answered.id.clone()))>
+                        <QuestionInput
+                            question=answered.question
+                            initial=Some(answered.answer)
+                            submit_label="Update"
+                            pending=move || form.pending.get() == Some(idx)
+                            on_answer=move |answer| {
+                                if confirm_change(form, idx, &answer) {
+                                    form.answer(idx, answer, || {})
+                                }
+                            }
+                            suggest=suggester(form, answered.id.clone())
+                        />
+                        <QuestionErrors form=form idx=idx id=answered.id/>
+                    </div>
+                }
+            })
+            .collect_view();
+        let next = match snapshot.next {
+            Some((id, question, answer)) => view! {
+                <div
+                    id=element_id(Route::Question(id.clone()))
+                    class=transition.class()
+                    data-current-question
+                >
+                    <QuestionInput
+                        question=question
+                        initial=answer
+                        submit_label="Next"
+                        pending=move || form.pending.get() == Some(next_idx)
+                        on_answer=move |answer| form.answer(next_idx, answer, || {})
+                        suggest=suggester(form, id.clone())
+                    />
+                    <QuestionErrors form=form idx=next_idx id=id/>
+                </div>
+            }
+            .into_view(),
+            None => view! {
+                <div id=element_id(Route::Complete) class=transition.class() data-current-question>
+                    <Completion form=form/>
+                </div>
+            }
+            .into_view(),
+        };
+
+        view! {
+            <div class="flex flex-col gap-4">
+                {answered}
+                {next}
+            </div>
+        }
+        .into_view()
+    };
+
+    view! { <div node_ref=container>{questions}</div> }
+}
+
+/// Moves focus to the first input of the current question in the given container, once it's been
+/// rendered.
+fn focus_current_question(container: NodeRef<Div>) {
+    request_animation_frame(move || {
+        let input = container.get_untracked().and_then(|container| {
+            container
+                .query_selector("[data-current-question] :is(input, select, textarea, button)")
+                .ok()
+                .flatten()
+        });
+        if let Some(input) = input.and_then(|input| input.dyn_into::<web_sys::HtmlElement>().ok()) {
+            // If the input can't be focused, the user can still get to it themselves
+            let _ = input.focus();
+        }
+    });
+}
+
+/// Shows any error the script gave in response to the answer to the question at the given index,
+/// and any error the host gave for the question with the given ID when rejecting a submission.
+#[component]
+fn QuestionErrors(form: FormHandle, idx: usize, id: String) -> impl IntoView {
+    let script_error = move || {
+        form.script_error.get().and_then(|(err_idx, err)| {
+            (err_idx == idx).then(|| view! { <p class="text-red-600">{err}</p> })
+        })
+    };
+    let field_error = move || {
+        form.field_errors
+            .with(|errors| errors.get(&id).cloned())
+            .map(|err| view! { <p class="text-red-600">{err}</p> })
+    };
+
+    view! {
+        {script_error}
+        {field_error}
+    }
+}
+
+/// Shown once every question in a form has been answered, allowing the user to submit the form
+/// (once they've completed the host's challenge, if there is one), and showing a transcript of
+/// their answers once the submission has been accepted.
+#[component]
+fn Completion(form: FormHandle) -> impl IntoView {
+    let hooks = store_value(use_context::<Hooks>().unwrap_or_default());
+    let online = use_context::<Online>().map(|online| online.0);
+    let is_online = move || online.is_none_or(|online| online.get());
+    let verifying = create_rw_signal(false);
+    let submit_error = create_rw_signal(None::<String>);
+    // Whether or not the user has tried to submit while offline, in which case we'll submit as soon
+    // as they're back online
+    let queued = create_rw_signal(false);
+    let submit = move || {
+        let hooks = hooks.get_value();
+        submit_error.set(None);
+        if !untrack(is_online) {
+            queued.set(true);
+            return;
+        }
+        spawn_local(async move {
+            let challenge_token = match &hooks.challenge {
+                Some(challenge) => {
+                    verifying.set(true);
+                    let token = run_challenge(challenge).await;
+                    verifying.set(false);
+                    match token {
+                        Ok(token) => Some(token),
+                        Err(err) => {
+                            submit_error.set(Some(err));
+                            return;
+                        }
+                    }
+                }
+                None => None,
+            };
+            form.finish(move |finished| {
+                let Some((output, transcript)) = finished else {
+                    return;
+                };
+                let Some(on_submit) = hooks.on_submit else {
+                    form.accept(output, transcript, challenge_token);
+                    return;
+                };
+                spawn_local(async move {
+                    match run_on_submit(&on_submit, &output, challenge_token.as_deref()).await {
+                        Ok(field_errors) if field_errors.is_empty() => {
+                            form.accept(output, transcript, challenge_token)
+                        }
+                        Ok(field_errors) => form.reopen(transcript, field_errors),
+                        // If the connection dropped while we were submitting, try again once it's
+                        // back
+                        Err(_) if !untrack(is_online) => {
+                            form.reopen(transcript, HashMap::new());
+                            queued.set(true);
+                        }
+                        Err(err) => {
+                            form.reopen(transcript, HashMap::new());
+                            submit_error.set(Some(err));
+                        }
+                    }
+                });
+            });
+        });
+    };
+    create_effect(move |_| {
+        if is_online() && queued.get_untracked() {
+            queued.set(false);
+            submit();
+        }
+    });
+
+    move || match form.submission.get() {
+        Some(submission) => view! {
+            <div class="flex flex-col gap-2">
+                <p>"Your answers have been submitted."</p>
+                <TranscriptView transcript=submission.transcript/>
+            </div>
+        }
+        .into_view(),
+        None => view! {
+            <div class="flex flex-col gap-2">
+                <p>"You've answered every question."</p>
+                <button
+                    class="self-start border rounded px-2 py-1 min-h-[44px]"
+                    disabled=move || verifying.get() || form.submitting.get() || queued.get()
+                    on:click=move |_| submit()
+                >
+                    {move || {
+                        if queued.get() {
+                            "Waiting for connection..."
+                        } else if verifying.get() {
+                            "Verifying..."
+                        } else if form.submitting.get() {
+                            "Submitting..."
+                        } else {
+                            "Submit"
+                        }
+                    }}
+                </button>
+                {move || {
+                    form.field_errors
+                        .with(|errors| !errors.is_empty())
+                        .then(|| view! { <p class="text-red-600">{FIELD_ERRORS_MESSAGE}</p> })
+                }}
+                {move || queued.get().then(|| view! { <p role="status">{QUEUED_MESSAGE}</p> })}
+                {move || {
+                    submit_error.get().map(|err| view! { <p class="text-red-600">{err}</p> })
+                }}
+            </div>
+        }
+        .into_view(),
+    }
+}
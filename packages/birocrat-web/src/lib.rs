@@ -1,10 +1,110 @@
-use leptos::{component, view, IntoView};
+mod autosave;
+mod error;
+mod hooks;
+mod layout;
+mod markdown;
+mod offline;
+mod options;
+mod question;
+mod recovery;
+mod routing;
+mod state;
+mod transcript;
+mod worker;
+
+use crate::{
+    autosave::setup_autosave,
+    error::FormFailure,
+    hooks::Hooks,
+    layout::{Layout, SinglePage, Stepper},
+    offline::track_online,
+    options::{parse_answers, Options},
+    recovery::{handle_panics, report_failure},
+    state::{random_seed, FormHandle},
+};
+use fmterr::fmterr;
+use leptos::{
+    component, create_effect, create_rw_signal, ev, provide_context, store_value, view,
+    window_event_listener, ErrorBoundary, Errors, IntoView, RwSignal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalWith, SignalWithUntracked,
+};
+use serde_json::Value;
+use std::collections::HashMap;
 use wasm_bindgen::{prelude::*, JsCast};
 
-/// Mounts Birocrat at the provided ID. This will return `true` if mounting was successful, and
-/// `false` otherwise.
+/// Shown while the user is offline.
+const OFFLINE_MESSAGE: &str = "You're offline. You can keep answering, and anything that needs to \
+    be sent will be sent once you're back online.";
+
+/// Mounts a form driven by the given script at the provided ID. Options can be provided as an
+/// object with the following properties (all optional):
+///
+/// - `params`: parameters to pass to the form script
+/// - `seed`: the seed for everything random the script does, as a string of digits (e.g. from a
+///   saved session, so the script makes the same choices again); if this isn't given, a new one
+///   will be generated
+/// - `variants`: the variants of experiments the script runs (with `birocrat.variant`) this
+///   session should be in, as an object of variant names keyed by experiment; experiments that
+///   aren't given here will have a variant chosen from the seed
+/// - `layout`: either `"stepper"` (the default), to show one question at a time, or
+///   `"single-page"`, to show every question on one page
+/// - `answers`: answers to fill in before the user sees the form, keyed by question ID, where text
+///   answers are strings, selections are arrays of strings, ratings are numbers, and skipped
+///   questions are `null` (e.g. from a saved session)
+/// - `autosave`: an object with an `endpoint` to `POST` the user's progress to as they answer
+///   questions, and optionally a `delay` in milliseconds to wait after the last answer before
+///   saving (defaults to 1000)
+/// - `routing`: if `true`, the current question will be kept in the URL hash, so the browser's back
+///   and forward buttons move between questions, and users can link to questions they've reached
+/// - `transition`: how a new question should appear when the user moves to it, which can be
+///   `"none"` (the default), `"fade"`, or `"slide"` (users who've asked their browser to reduce
+///   motion won't see any)
+/// - `focus`: where focus should go when the user moves to a new question, either `"stay"` (the
+///   default), to leave it where it is, or `"question"`, to move it to the new question's first
+///   input
+/// - `challenge`: a function to call before the form is submitted, which should have the user
+///   complete a challenge like a captcha, and return a token (or a promise resolving to one) that
+///   will be provided with the submission for the host to verify
+/// - `onError`: a function to call with diagnostics whenever the form fails, which will be given
+///   an object with the `kind` of failure (`"error"` or `"panic"`), a `message`, and the `answers`
+///   the user had given, if they're available
+/// - `onSubmit`: a function to call with the form's output (and the token from `challenge`, if
+///   there is one) when the user submits it, which should return a promise that resolves once the
+///   host has handled the submission; to reject it, this should resolve to an object with
+///   `fieldErrors` mapping question IDs to messages, which will be shown on those questions so the
+///   user can change their answers
+/// - `onEvent`: a function to call as the user moves through the form, for analytics, which will be
+///   given an object with the `type` of event and its details: `"questionViewed"` and `"answered"`
+///   (with a `questionId`), `"validationFailed"` (with a `questionId` and the `message` the user
+///   was shown), `"abandoned"` (when the user leaves the page without submitting, with the
+///   `questionId` they were on and the `durationMs` since they started), and `"completed"` (with
+///   the `durationMs` the user took, and the `variants` of experiments they were in, keyed by
+///   experiment)
+///
+/// The form's script runs in a web worker, so the page stays responsive however long the script
+/// takes. The worker loads this program from the same place the page did, so its JS and WASM files
+/// should be left next to each other. If the script has a `Suggest` global, its suggestions will be
+/// offered on simple text questions once the user pauses typing.
+///
+/// The form keeps working if the user goes offline, since it runs entirely in the browser. While
+/// they're offline, autosaves will be held back, and submitting the form will queue the submission
+/// until they're back online, at which point it will be retried automatically (the user will be
+/// told what's happening).
+///
+/// If the form fails, the user will be offered ways to recover, including restoring the last
+/// autosave, if there is one.
+///
+/// This will return `true` if mounting was successful, and `false` otherwise (e.g. if the options
+/// were invalid, in which case the reason will be logged to the console).
 #[wasm_bindgen]
-pub fn birocrat(id: &str) -> bool {
+pub fn birocrat(id: &str, script: String, options: JsValue) -> bool {
+    let options = match Options::from_js(&options) {
+        Ok(options) => options,
+        Err(err) => {
+            web_sys::console::error_1(&fmterr(&err).into());
+            return false;
+        }
+    };
     let root = web_sys::window()
         .unwrap()
         .document()
@@ -12,7 +112,8 @@ pub fn birocrat(id: &str) -> bool {
         .get_element_by_id(id);
     if let Some(root) = root {
         let root = root.dyn_into::<web_sys::HtmlElement>().unwrap();
-        leptos::mount_to(root, || view! { <App /> });
+        handle_panics(id, options.on_error.clone());
+        leptos::mount_to(root, move || view! { <App script=script options=options/> });
 
         true
     } else {
@@ -21,6 +122,203 @@ pub fn birocrat(id: &str) -> bool {
 }
 
 #[component]
-fn App() -> impl IntoView {
-    view! {}
+fn App(script: String, options: Options) -> impl IntoView {
+    let Options {
+        params,
+        seed,
+        variants,
+        layout,
+        answers,
+        autosave,
+        routing,
+        transition,
+        focus,
+        challenge,
+        on_error,
+        on_submit,
+        on_event,
+    } = options;
+    let hooks = Hooks {
+        challenge,
+        on_error,
+        on_submit,
+        on_event,
+    };
+    provide_context(hooks.clone());
+    let online = track_online();
+    provide_context(online);
+
+    // The answers most recently saved to the server, which we can restore from if something goes
+    // wrong
+    let last_saved = create_rw_signal(None::<serde_json::Map<String, Value>>);
+    // Starts a new form with the given answers filled in (this has to happen inside the reactive
+    // system, because it creates signals), always with the same seed, so restoring answers gives
+    // the same questions
+    let seed = seed.unwrap_or_else(random_seed);
+    let start = store_value(move |answers| {
+        let form = FormHandle::new(
+            &script,
+            &params,
+            seed,
+            variants.clone(),
+            answers,
+            hooks.on_event.clone(),
+        )
+        .map_err(|err| FormFailure {
+            message: fmterr(&err),
+        })?;
+        if let Some(autosave) = autosave.clone() {
+            setup_autosave(form, params.clone(), seed, autosave, last_saved, online);
+        }
+        Ok::<_, FormFailure>(form)
+    });
+    let form = create_rw_signal(start.with_value(|start| start(answers)));
+
+    // Let the host know if the user leaves without submitting
+    window_event_listener(ev::pagehide, move |_| {
+        if let Ok(form) = form.get_untracked() {
+            form.abandon();
+        }
+    });
+
+    // Let the host know whenever something goes wrong
+    let on_error = hooks.on_error;
+    create_effect(move |_| {
+        let failure = form.with(|form| match form {
+            Ok(form) => form.hard_error.get().map(|message| {
+                let answers = form.snapshot.with_untracked(|s| s.answers_json());
+                (message, Some(answers))
+            }),
+            Err(failure) => Some((failure.message.clone(), None)),
+        });
+        if let Some((message, answers)) = failure {
+            report_failure(on_error.as_ref(), "error", &message, answers);
+        }
+    });
+
+    let fallback = move |errors: RwSignal<Errors>| {
+        let message = move || {
+            errors.with(|errors| {
+                errors
+                    .iter()
+                    .next()
+                    .map(|(_, err)| err.to_string())
+                    .unwrap_or_default()
+            })
+        };
+        // Hard errors in a running form don't change its state, so the user can just carry on,
+        // unless the form couldn't start or its worker failed
+        let can_continue = form
+            .with_untracked(|form| form.as_ref().is_ok_and(|form| !form.broken.get_untracked()));
+        let on_continue = move |_| {
+            if let Ok(form) = form.get_untracked() {
+                form.hard_error.set(None);
+            }
+        };
+        // The old form won't be used again, so its VM can be freed before we start a new one
+        let close = move || {
+            if let Ok(form) = form.get_untracked() {
+                form.close();
+            }
+        };
+        let on_restore = move |_| {
+            let answers = last_saved
+                .get_untracked()
+                .map(|answers| parse_answers(Value::Object(answers)).unwrap_or_default())
+                .unwrap_or_default();
+            close();
+            form.set(start.with_value(|start| start(answers)));
+        };
+        let on_restart = move |_| {
+            close();
+            form.set(start.with_value(|start| start(HashMap::new())));
+        };
+
+        view! {
+            <div role="alert" class="flex flex-col gap-2">
+                <p class="text-red-600">"Something went wrong: " {message}</p>
+                <div class="flex gap-2">
+                    {can_continue
+                        .then(|| {
+                            view! {
+                                <button class="border rounded px-2 py-1 min-h-[44px]" on:click=on_continue>
+                                    "Try again"
+                                </button>
+                            }
+                        })}
+                    {move || {
+                        last_saved
+                            .with(Option::is_some)
+                            .then(|| {
+                                view! {
+                                    <button class="border rounded px-2 py-1 min-h-[44px]" on:click=on_restore>
+                                        "Restore last save"
+                                    </button>
+                                }
+                            })
+                    }}
+                    <button class="border rounded px-2 py-1 min-h-[44px]" on:click=on_restart>
+                        "Start over"
+                    </button>
+                </div>
+            </div>
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-2">
+            {move || {
+                (!online.0.get())
+                    .then(|| {
+                        view! {
+                            <p role="status" class="border rounded px-2 py-1">
+                                {OFFLINE_MESSAGE}
+                            </p>
+                        }
+                    })
+            }}
+            <ErrorBoundary fallback=fallback>
+                {move || {
+                    let form = form.get()?;
+                    if let Some(message) = form.hard_error.get() {
+                        return Err(FormFailure { message });
+                    }
+                    if form.loading.get() {
+                        return Ok(
+                            view! {
+                                <p role="status" aria-busy="true">
+                                    "Loading..."
+                                </p>
+                            }
+                                .into_view(),
+                        );
+                    }
+                    Ok(
+                        match layout {
+                            Layout::Stepper => {
+                                view! {
+                                    <Stepper
+                                        form=form
+                                        routing=routing
+                                        transition=transition
+                                        focus=focus
+                                    />
+                                }
+                            }
+                            Layout::SinglePage => {
+                                view! {
+                                    <SinglePage
+                                        form=form
+                                        routing=routing
+                                        transition=transition
+                                        focus=focus
+                                    />
+                                }
+                            }
+                        },
+                    )
+                }}
+            </ErrorBoundary>
+        </div>
+    }
 }
@@ -0,0 +1,77 @@
+use birocrat::{PromptFormat, Question};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+/// The URL schemes links and images in markdown prompts may use. Anything else (like
+/// `javascript:`) is dropped, since prompts can include parameters the host got from users.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Renders the given markdown as HTML. Any raw HTML in the markdown is escaped rather than passed
+/// through, and links with unsafe URLs are dropped, so this can be put straight into the page.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let events =
+        Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH).map(|event| match event {
+            Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            event => event,
+        });
+    let mut html = String::new();
+    html::push_html(&mut html, events);
+    html
+}
+
+/// Gets the prompt of the given question as plain text, with any markdown in it stripped out, for
+/// places where it's shown as a label (like summaries of the user's answers).
+pub fn prompt_text(question: &Question) -> String {
+    let prompt = question.prompt();
+    if question.format() == PromptFormat::Plain {
+        return prompt.to_string();
+    }
+
+    let mut text = String::new();
+    for event in Parser::new_ext(prompt, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Text(part) | Event::Code(part) => text.push_str(&part),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Checks the given URL from a markdown prompt, replacing it with an empty one if it uses a scheme
+/// that isn't known to be safe. Relative URLs are left alone.
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    // A colon before any slash, query, or fragment means the URL has a scheme
+    let scheme = url
+        .split(['/', '?', '#'])
+        .next()
+        .and_then(|start| start.split_once(':'))
+        .map(|(scheme, _)| scheme.trim().to_lowercase());
+    match scheme {
+        Some(scheme) if !SAFE_SCHEMES.contains(&scheme.as_str()) => CowStr::Borrowed(""),
+        _ => url,
+    }
+}
@@ -0,0 +1,17 @@
+use leptos::{create_rw_signal, ev, window_event_listener, ReadSignal, SignalSet};
+
+/// Whether or not the browser is connected to the network, as far as it can tell.
+#[derive(Clone, Copy)]
+pub struct Online(pub ReadSignal<bool>);
+
+/// Creates a signal tracking whether or not the browser is online, which will be updated whenever
+/// the connection drops or comes back. The form itself runs entirely in the browser, so this only
+/// matters for things that talk to the host (autosaves and the final submission), which should be
+/// held back until the user is online again.
+pub fn track_online() -> Online {
+    let online = create_rw_signal(web_sys::window().unwrap().navigator().on_line());
+    window_event_listener(ev::online, move |_| online.set(true));
+    window_event_listener(ev::offline, move |_| online.set(false));
+
+    Online(online.read_only())
+}
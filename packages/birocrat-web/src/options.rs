@@ -0,0 +1,229 @@
+use crate::{
+    autosave::AutosaveOptions,
+    error::Error,
+    layout::{Focus, Layout, Transition},
+};
+use birocrat::Answer;
+use js_sys::Function;
+use serde_json::Value;
+use std::{collections::HashMap, time::Duration};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// How long autosaving waits after the user's last answer by default.
+const DEFAULT_AUTOSAVE_DELAY: Duration = Duration::from_millis(1000);
+
+/// The largest integer JS numbers can hold exactly.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Options for mounting a form, which the host page provides as a JS object.
+#[derive(Debug)]
+pub struct Options {
+    /// Parameters to pass to the form script.
+    pub params: Value,
+    /// The seed for everything random the script does, if the host gave one (e.g. to resume a
+    /// saved session).
+    pub seed: Option<u64>,
+    /// Variants of experiments the host has assigned this session to, keyed by experiment.
+    pub variants: HashMap<String, String>,
+    /// How the form should be laid out.
+    pub layout: Layout,
+    /// Answers to fill in before the user sees the form, keyed by question ID.
+    pub answers: HashMap<String, Answer>,
+    /// Where to automatically save the user's progress to, if anywhere.
+    pub autosave: Option<AutosaveOptions>,
+    /// Whether or not to keep the current question in the URL hash.
+    pub routing: bool,
+    /// How new questions should appear.
+    pub transition: Transition,
+    /// Where focus should go when the user moves to a new question.
+    pub focus: Focus,
+    /// A function to have the user complete a challenge (e.g. a captcha) before submitting.
+    pub challenge: Option<Function>,
+    /// A function to report diagnostics to when the form fails.
+    pub on_error: Option<Function>,
+    /// A function to hand the form's output to when the user submits it. Answers to file questions
+    /// only carry the names of the files the user chose, never their contents, so hosts that need
+    /// the files themselves will have to read them separately.
+    pub on_submit: Option<Function>,
+    /// A function to send analytics events to as the user moves through the form.
+    pub on_event: Option<Function>,
+}
+impl Options {
+    /// Parses options from the given JS value, which may be `undefined` or `null` to use the
+    /// defaults for everything.
+    pub fn from_js(options: &JsValue) -> Result<Self, Error> {
+        if options.is_undefined() || options.is_null() {
+            return Ok(Self {
+                params: Value::Object(serde_json::Map::new()),
+                seed: None,
+                variants: HashMap::new(),
+                layout: Layout::default(),
+                answers: HashMap::new(),
+                autosave: None,
+                routing: false,
+                transition: Transition::default(),
+                focus: Focus::default(),
+                challenge: None,
+                on_error: None,
+                on_submit: None,
+                on_event: None,
+            });
+        }
+        if !options.is_object() {
+            return Err(Error::OptionsNotObject);
+        }
+
+        let params = match get(options, "params") {
+            Some(params) => serde_wasm_bindgen::from_value(params)
+                .map_err(|err| Error::InvalidParams { source: err })?,
+            None => Value::Object(serde_json::Map::new()),
+        };
+        // Seeds are given as strings, because JS numbers can't hold every one exactly (but small
+        // ones can be numbers)
+        let seed = get(options, "seed")
+            .map(|seed| match seed.as_string() {
+                Some(seed) => seed.parse().map_err(|_| Error::InvalidSeed),
+                None => seed
+                    .as_f64()
+                    .filter(|seed| seed.fract() == 0.0 && (0.0..=MAX_SAFE_INTEGER).contains(seed))
+                    .map(|seed| seed as u64)
+                    .ok_or(Error::InvalidSeed),
+            })
+            .transpose()?;
+        let variants = match get(options, "variants") {
+            Some(variants) => {
+                serde_wasm_bindgen::from_value(variants).map_err(|_| Error::InvalidVariants)?
+            }
+            None => HashMap::new(),
+        };
+        let layout = match get(options, "layout").map(|layout| layout.as_string()) {
+            None => Layout::default(),
+            Some(Some(layout)) if layout == "stepper" => Layout::Stepper,
+            Some(Some(layout)) if layout == "single-page" => Layout::SinglePage,
+            Some(layout) => {
+                return Err(Error::InvalidLayout {
+                    layout: layout.unwrap_or_default(),
+                })
+            }
+        };
+
+        let answers = match get(options, "answers") {
+            Some(answers) => parse_answers(
+                serde_wasm_bindgen::from_value(answers).map_err(|_| Error::AnswersNotObject)?,
+            )?,
+            None => HashMap::new(),
+        };
+        let autosave = get(options, "autosave")
+            .map(|autosave| {
+                if !autosave.is_object() {
+                    return Err(Error::InvalidAutosave);
+                }
+                let endpoint = get(&autosave, "endpoint")
+                    .and_then(|endpoint| endpoint.as_string())
+                    .ok_or(Error::InvalidAutosave)?;
+                let delay = match get(&autosave, "delay") {
+                    Some(delay) => delay
+                        .as_f64()
+                        .filter(|delay| *delay >= 0.0)
+                        .map(|delay| Duration::from_millis(delay as u64))
+                        .ok_or(Error::InvalidAutosave)?,
+                    None => DEFAULT_AUTOSAVE_DELAY,
+                };
+
+                Ok(AutosaveOptions { endpoint, delay })
+            })
+            .transpose()?;
+        let routing = match get(options, "routing") {
+            Some(routing) => routing.as_bool().ok_or(Error::InvalidRouting)?,
+            None => false,
+        };
+        let transition = match get(options, "transition").map(|transition| transition.as_string()) {
+            None => Transition::default(),
+            Some(Some(transition)) if transition == "none" => Transition::None,
+            Some(Some(transition)) if transition == "fade" => Transition::Fade,
+            Some(Some(transition)) if transition == "slide" => Transition::Slide,
+            Some(transition) => {
+                return Err(Error::InvalidTransition {
+                    transition: transition.unwrap_or_default(),
+                })
+            }
+        };
+        let focus = match get(options, "focus").map(|focus| focus.as_string()) {
+            None => Focus::default(),
+            Some(Some(focus)) if focus == "stay" => Focus::Stay,
+            Some(Some(focus)) if focus == "question" => Focus::Question,
+            Some(focus) => {
+                return Err(Error::InvalidFocus {
+                    focus: focus.unwrap_or_default(),
+                })
+            }
+        };
+        let challenge = get(options, "challenge")
+            .map(|challenge| {
+                challenge
+                    .dyn_into::<Function>()
+                    .map_err(|_| Error::InvalidChallenge)
+            })
+            .transpose()?;
+        let on_error = get(options, "onError")
+            .map(|on_error| {
+                on_error
+                    .dyn_into::<Function>()
+                    .map_err(|_| Error::InvalidErrorHook)
+            })
+            .transpose()?;
+        let on_submit = get(options, "onSubmit")
+            .map(|on_submit| {
+                on_submit
+                    .dyn_into::<Function>()
+                    .map_err(|_| Error::InvalidSubmitHook)
+            })
+            .transpose()?;
+        let on_event = get(options, "onEvent")
+            .map(|on_event| {
+                on_event
+                    .dyn_into::<Function>()
+                    .map_err(|_| Error::InvalidEventHook)
+            })
+            .transpose()?;
+
+        Ok(Self {
+            params,
+            seed,
+            variants,
+            layout,
+            answers,
+            autosave,
+            routing,
+            transition,
+            focus,
+            challenge,
+            on_error,
+            on_submit,
+            on_event,
+        })
+    }
+}
+
+/// Parses answers keyed by question ID, each in the format [`Answer::from_json`] reads.
+pub fn parse_answers(answers: Value) -> Result<HashMap<String, Answer>, Error> {
+    let Value::Object(answers) = answers else {
+        return Err(Error::AnswersNotObject);
+    };
+    answers
+        .into_iter()
+        .map(|(id, answer)| {
+            let answer =
+                Answer::from_json(answer).ok_or(Error::InvalidAnswer { id: id.clone() })?;
+            Ok((id, answer))
+        })
+        .collect()
+}
+
+/// Gets the property with the given name from a JS object, returning `None` if it's `undefined`
+/// or `null`.
+fn get(object: &JsValue, key: &str) -> Option<JsValue> {
+    // We've already checked this is an object, so this can't fail
+    let value = js_sys::Reflect::get(object, &key.into()).unwrap();
+    (!value.is_undefined() && !value.is_null()).then_some(value)
+}
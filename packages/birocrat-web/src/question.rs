@@ -0,0 +1,1368 @@
+use crate::markdown::markdown_to_html;
+use birocrat::{Answer, PromptFormat, Question, SelectOption};
+use chrono::{DateTime, FixedOffset, NaiveTime, Timelike};
+use leptos::{
+    component, create_rw_signal, ev::SubmitEvent, event_target, event_target_checked,
+    event_target_value, leptos_dom::helpers::TimeoutHandle, set_timeout_with_handle, store_value,
+    view, Callable, Callback, CollectView, IntoView, RwSignal, Signal, SignalGet, SignalSet,
+    SignalUpdate, SignalWith, View,
+};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use web_sys::HtmlInputElement;
+
+/// The number to give the next list of options for a searchable select question (or suggestions
+/// for a text question), so each input can refer to its own list.
+static NEXT_LIST_ID: AtomicUsize = AtomicUsize::new(0);
+/// How long to wait after the user stops typing before asking the script for suggestions, so it
+/// isn't run for every keystroke.
+const SUGGEST_DELAY: Duration = Duration::from_millis(200);
+
+/// A function that gets suggestions for an answer to a question from the text the user has typed
+/// so far, calling the function it's given with them once they're ready.
+pub type Suggest = Rc<dyn Fn(String, Box<dyn FnOnce(Vec<String>)>)>;
+
+/// An input for a single question, which will call `on_answer` with the user's answer when they
+/// submit it.
+#[component]
+pub fn QuestionInput(
+    /// The question to ask.
+    question: Question,
+    /// An answer to fill in initially (e.g. the user's previous answer to this question). If this
+    /// isn't given, the question's default will be used.
+    initial: Option<Answer>,
+    /// The text of the button that submits the answer.
+    #[prop(into)]
+    submit_label: String,
+    /// Whether or not the user's answer is being processed, in which case they'll be shown an
+    /// indicator and won't be able to submit again until it's done.
+    #[prop(into)]
+    pending: Signal<bool>,
+    /// A function to call with the user's answer when they submit it.
+    #[prop(into)]
+    on_answer: Callback<Answer>,
+    /// Where to get suggestions for the user's answer from as they type, if anywhere. These are
+    /// only shown for simple text questions.
+    #[prop(optional)]
+    suggest: Option<Suggest>,
+) -> impl IntoView {
+    // Answers to readonly questions can't be changed, but the user can still submit the same one
+    // to move past them
+    let locked = question.is_readonly() && initial.is_some();
+    let prompt = prompt_view(&question, "font-semibold");
+    let help = question.help().map(String::from);
+    let skippable = !question.is_required() && !locked;
+    let (input, get_answer) = answer_input(question, initial, suggest);
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        on_answer.call(get_answer());
+    };
+
+    view! {
+        <form class="flex flex-col gap-2" on:submit=on_submit>
+            {prompt}
+            {help_text(help)}
+            {locked
+                .then(|| {
+                    view! { <p class="text-sm text-gray-600">"This answer can't be changed."</p> }
+                })}
+            <fieldset class="contents" disabled=locked>
+                {input}
+            </fieldset>
+            <button
+                type="submit"
+                class="self-start border rounded px-2 py-1 min-h-[44px]"
+                disabled=move || pending.get()
+            >
+                {move || if pending.get() { "Checking...".to_string() } else { submit_label.clone() }}
+            </button>
+            {skippable
+                .then(|| {
+                    view! {
+                        <button
+                            type="button"
+                            class="self-start border rounded px-2 py-1 min-h-[44px]"
+                            disabled=move || pending.get()
+                            on:click=move |_| on_answer.call(Answer::Skipped)
+                        >
+                            "Skip"
+                        </button>
+                    }
+                })}
+        </form>
+    }
+}
+
+/// Renders the prompt of the given question, as HTML if the script wrote it in markdown. Prompts
+/// of questions are labels with the given class, while those of information steps are the body of
+/// the step. Markdown can have block elements in it (like lists), so those prompts can't go in a
+/// `<label>`.
+fn prompt_view(question: &Question, class: &'static str) -> View {
+    let prompt = question.prompt().to_string();
+    let is_info = matches!(question, Question::Info { .. });
+    match question.format() {
+        PromptFormat::Markdown => {
+            let class = if is_info {
+                "birocrat-markdown".to_string()
+            } else {
+                format!("birocrat-markdown {class}")
+            };
+            view! { <div class=class inner_html=markdown_to_html(&prompt)></div> }.into_view()
+        }
+        PromptFormat::Plain if is_info => {
+            view! { <p class="whitespace-pre-wrap">{prompt}</p> }.into_view()
+        }
+        PromptFormat::Plain => view! { <label class=class>{prompt}</label> }.into_view(),
+    }
+}
+
+/// Renders the help text for a question, if it has any, under its prompt.
+fn help_text(help: Option<String>) -> Option<View> {
+    help.map(|help| {
+        view! { <p class="text-sm text-gray-600 whitespace-pre-wrap">{help}</p> }.into_view()
+    })
+}
+
+/// Builds the inputs for answering the given question, starting with the given answer (or the
+/// question's default), and returns them with a function that gets the user's answer from them.
+/// If the question is a simple text question, and a source of suggestions is given, they'll be
+/// offered as the user types.
+fn answer_input(
+    mut question: Question,
+    initial: Option<Answer>,
+    suggest: Option<Suggest>,
+) -> (View, Rc<dyn Fn() -> Answer>) {
+    // The form answers computed steps itself, so there's nothing to do but show the value
+    if let Question::Computed { value, .. } = question {
+        let text = match &value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        let input = view! { <p class="whitespace-pre-wrap">{text}</p> }.into_view();
+        return (input, Rc::new(move || Answer::Computed(value.clone())));
+    }
+    // Each question on a page gets its own inputs, and they're all answered together
+    if let Question::Page { questions, .. } = question {
+        let mut initial = match initial {
+            Some(Answer::Page(answers)) => answers,
+            _ => HashMap::new(),
+        };
+        let (inputs, getters): (Vec<_>, Vec<_>) = questions
+            .into_iter()
+            .map(|item| {
+                let prompt = prompt_view(&item.question, "");
+                let help = item.question.help().map(String::from);
+                let required = item.question.is_required();
+                let (input, get_answer) =
+                    answer_input(item.question, initial.remove(&item.id), None);
+                // Optional text questions on a page are skipped by leaving them empty
+                let get_answer = move || match get_answer() {
+                    Answer::Text(text) if text.is_empty() && !required => Answer::Skipped,
+                    answer => answer,
+                };
+                let input = view! {
+                    <div class="flex flex-col gap-1">
+                        {prompt}
+                        {help_text(help)}
+                        {input}
+                    </div>
+                };
+                (input, (item.id, get_answer))
+            })
+            .unzip();
+        let get_answer = move || {
+            Answer::Page(
+                getters
+                    .iter()
+                    .map(|(id, get_answer)| (id.clone(), get_answer()))
+                    .collect(),
+            )
+        };
+        return (inputs.collect_view(), Rc::new(get_answer));
+    }
+    // Follow-ups get their own inputs under the selection, which are only shown while the options
+    // chosen bring them up (but keep what the user typed if they're hidden and shown again)
+    if let Question::Select { followup, .. } = &mut question {
+        if let Some(followup) = followup.take() {
+            let (initial, followup_initial) = match initial {
+                Some(Answer::WithFollowUp { answer, followup }) => (Some(*answer), Some(*followup)),
+                initial => (initial, None),
+            };
+            let prompt = prompt_view(&followup.question, "");
+            let help = followup.question.help().map(String::from);
+            let required = followup.question.is_required();
+            let (input, get_selection) = answer_input(question, initial, None);
+            let (followup_input, get_followup) =
+                answer_input(*followup.question.clone(), followup_initial, None);
+            let is_asked = Rc::new(move |selection: &Answer| match selection {
+                Answer::Options(selected) => followup.is_asked(selected),
+                Answer::OptionsWithText(selected) => followup.is_asked(
+                    &selected
+                        .iter()
+                        .map(|(option, _)| option.clone())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => false,
+            });
+
+            let shown = {
+                let (is_asked, get_selection) = (is_asked.clone(), get_selection.clone());
+                move || is_asked(&get_selection())
+            };
+            let input = view! {
+                {input}
+                <div class="flex flex-col gap-1 ml-6" class:hidden=move || !shown()>
+                    {prompt}
+                    {help_text(help)}
+                    {followup_input}
+                </div>
+            }
+            .into_view();
+            let get_answer = move || {
+                let selection = get_selection();
+                if !is_asked(&selection) {
+                    return selection;
+                }
+                // Optional text follow-ups are skipped by leaving them empty, as on pages
+                let followup = match get_followup() {
+                    Answer::Text(text) if text.is_empty() && !required => Answer::Skipped,
+                    answer => answer,
+                };
+                Answer::WithFollowUp {
+                    answer: Box::new(selection),
+                    followup: Box::new(followup),
+                }
+            };
+            return (input, Rc::new(get_answer));
+        }
+    }
+
+    // Each entry in a group question has a signal for each of the question's fields, so typing in
+    // one doesn't redraw the others
+    let field_ids = match &question {
+        Question::Group { fields, .. } => fields.iter().map(|field| field.id.clone()).collect(),
+        _ => Vec::new(),
+    };
+    let initial_entries = match &initial {
+        Some(Answer::Group(entries)) => entries
+            .iter()
+            .map(|entry| {
+                field_ids
+                    .iter()
+                    .map(|id| create_rw_signal(entry.get(id).cloned().unwrap_or_default()))
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let entries = create_rw_signal(initial_entries);
+    // Options that need details get a text input each while they're chosen, and those details are
+    // kept by option so they survive the option being unchosen and chosen again
+    let specify_options = match &question {
+        Question::Select { options, .. } => options
+            .iter()
+            .filter(|option| option.specify)
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
+    let details = create_rw_signal(match &initial {
+        Some(Answer::OptionsWithText(selected)) => selected
+            .iter()
+            .filter_map(|(option, text)| Some((option.clone(), text.clone()?)))
+            .collect(),
+        _ => HashMap::new(),
+    });
+    let (initial_text, initial_selected) = match (initial, &question) {
+        (Some(Answer::Text(text)), _) => (text, Vec::new()),
+        (Some(Answer::Secret(secret)), _) => (secret.expose().to_string(), Vec::new()),
+        (Some(Answer::Options(selected)), _) => (String::new(), selected),
+        (Some(Answer::OptionsWithText(selected)), _) => (
+            String::new(),
+            selected.into_iter().map(|(option, _)| option).collect(),
+        ),
+        (Some(Answer::Date(date)), _) => (date.to_string(), Vec::new()),
+        (Some(Answer::Rating(rating)), _) => (rating.to_string(), Vec::new()),
+        (Some(Answer::Range(value)), _) => (value.to_string(), Vec::new()),
+        (Some(Answer::Decimal(decimal)), _) => (decimal.to_string(), Vec::new()),
+        (Some(Answer::Color(color)), _) => (color.to_string(), Vec::new()),
+        // Follow-ups have been split off by now
+        (
+            Some(
+                Answer::Group(_)
+                | Answer::Acknowledged
+                | Answer::Computed(_)
+                | Answer::Page(_)
+                | Answer::Skipped
+                | Answer::WithFollowUp { .. },
+            ),
+            _,
+        )
+        | (
+            None,
+            Question::Group { .. }
+            | Question::Info { .. }
+            | Question::Computed { .. }
+            | Question::Page { .. },
+        ) => (String::new(), Vec::new()),
+        (Some(Answer::Time(time)), _) => (format_time(&time), Vec::new()),
+        (Some(Answer::DateTime(datetime)), _) => (format_datetime(&datetime), Vec::new()),
+        // Browsers won't let us fill in file inputs, but we can keep the files the user chose
+        // before unless they choose new ones
+        (Some(Answer::Files(paths)), _) => (
+            String::new(),
+            paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+        ),
+        (
+            None,
+            Question::Simple { default, .. }
+            | Question::Multiline { default, .. }
+            | Question::Email { default, .. }
+            | Question::Phone { default, .. }
+            | Question::Url { default, .. },
+        ) => (default.clone().unwrap_or_default(), Vec::new()),
+        (None, Question::Secret { .. } | Question::File { .. }) => (String::new(), Vec::new()),
+        (None, Question::Rating { default, .. } | Question::Likert { default, .. }) => (
+            default.map(|rating| rating.to_string()).unwrap_or_default(),
+            Vec::new(),
+        ),
+        // Sliders always have a value, so they start at the bottom if there's no default
+        (None, Question::Range { default, min, .. }) => {
+            (default.unwrap_or(*min).to_string(), Vec::new())
+        }
+        // Colour pickers always have a value too, so they start on black
+        (None, Question::Color { default, .. }) => (
+            default
+                .map(|color| color.to_string())
+                .unwrap_or_else(|| "#000000".to_string()),
+            Vec::new(),
+        ),
+        (None, Question::Decimal { default, .. }) => (
+            default
+                .map(|decimal| decimal.to_string())
+                .unwrap_or_default(),
+            Vec::new(),
+        ),
+        (None, Question::Date { default, .. }) => (
+            default.map(|date| date.to_string()).unwrap_or_default(),
+            Vec::new(),
+        ),
+        (None, Question::Time { default, .. }) => (
+            default.as_ref().map(format_time).unwrap_or_default(),
+            Vec::new(),
+        ),
+        (None, Question::DateTime { default, .. }) => (
+            default.as_ref().map(format_datetime).unwrap_or_default(),
+            Vec::new(),
+        ),
+        // Rankings start in the order the script gave
+        (None, Question::Rank { options, .. }) => (
+            String::new(),
+            options.iter().map(|option| option.value.clone()).collect(),
+        ),
+        // Single selections start on the first option if there's no default, as they would in a
+        // native `<select>` (but searchable ones start empty, so the user can start typing)
+        (
+            None,
+            Question::Select {
+                defaults,
+                options,
+                multiple,
+                searchable,
+                ..
+            },
+        ) => (
+            String::new(),
+            if defaults.is_empty() && !multiple && !searchable {
+                options
+                    .first()
+                    .map(|option| option.value.clone())
+                    .into_iter()
+                    .collect()
+            } else {
+                defaults.clone()
+            },
+        ),
+    };
+    // The user's own value for a select question (if they can give one) is kept apart from the
+    // options they chose, and is `None` if they haven't chosen to give one
+    let (initial_selected, initial_other): (Vec<_>, Vec<_>) = initial_selected
+        .into_iter()
+        .partition(|value| !question.is_other(value));
+    let text = create_rw_signal(initial_text);
+    let selected = create_rw_signal(initial_selected);
+    let other = create_rw_signal(initial_other.into_iter().next());
+
+    let is_select = matches!(question, Question::Select { .. } | Question::Rank { .. });
+    let is_file = matches!(question, Question::File { .. });
+    let is_group = matches!(question, Question::Group { .. });
+    let is_info = matches!(question, Question::Info { .. });
+    let input = match question {
+        // There's nothing to answer, just the information in the prompt (and pages and computed
+        // steps are handled above)
+        Question::Info { .. } | Question::Computed { .. } | Question::Page { .. } => {
+            ().into_view()
+        }
+        // Phones can show a keypad if the pattern only allows numbers
+        Question::Simple {
+            pattern,
+            min_length,
+            max_length,
+            ..
+        } => {
+            let suggestions = create_rw_signal(Vec::<String>::new());
+            let list_id = suggest.is_some().then(|| {
+                format!(
+                    "question-suggestions-{}",
+                    NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed)
+                )
+            });
+            // Waiting for the user to stop typing, which is reset on every keystroke
+            let waiting = store_value(None::<TimeoutHandle>);
+            let on_input = move |ev| {
+                let value = event_target_value(&ev);
+                text.set(value.clone());
+                let Some(suggest) = suggest.clone() else {
+                    return;
+                };
+                if let Some(handle) = waiting.get_value() {
+                    handle.clear();
+                }
+                let handle = set_timeout_with_handle(
+                    move || suggest(value, Box::new(move |found| suggestions.set(found))),
+                    SUGGEST_DELAY,
+                )
+                .ok();
+                waiting.set_value(handle);
+            };
+            view! {
+                <input
+                    type="text"
+                    class="border rounded p-1 min-h-[44px]"
+                    inputmode=input_mode(pattern.as_deref())
+                    minlength=min_length
+                    maxlength=max_length
+                    list=list_id.clone()
+                    prop:value=move || text.get()
+                    on:input=on_input
+                />
+                {list_id
+                    .map(|list_id| {
+                        view! {
+                            <datalist id=list_id>
+                                {move || {
+                                    suggestions
+                                        .get()
+                                        .into_iter()
+                                        .map(|suggestion| view! { <option value=suggestion></option> })
+                                        .collect_view()
+                                }}
+                            </datalist>
+                        }
+                    })}
+            }
+            .into_view()
+        }
+        // Browsers will check these too, but the engine has the final say
+        Question::Email { .. } => view! {
+            <input
+                type="email"
+                class="border rounded p-1 min-h-[44px]"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        // Phones will offer the user's own number, and the engine will normalise whatever they give
+        Question::Phone { .. } => view! {
+            <input
+                type="tel"
+                autocomplete="tel"
+                class="border rounded p-1 min-h-[44px]"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        Question::Url { .. } => view! {
+            <input
+                type="url"
+                class="border rounded p-1 min-h-[44px]"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        Question::Secret { .. } => view! {
+            <input
+                type="password"
+                class="border rounded p-1 min-h-[44px]"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        // Browsers give the value of these as `YYYY-MM-DD`, which the engine will parse
+        Question::Date { min, max, .. } => view! {
+            <input
+                type="date"
+                class="border rounded p-1 min-h-[44px]"
+                min=min.map(|date| date.to_string())
+                max=max.map(|date| date.to_string())
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        // Browsers give these as `HH:MM`, or `HH:MM:SS` if we allow seconds
+        Question::Time {
+            min, max, seconds, ..
+        } => view! {
+            <input
+                type="time"
+                class="border rounded p-1 min-h-[44px]"
+                min=min.as_ref().map(format_time)
+                max=max.as_ref().map(format_time)
+                step=seconds.then_some("1")
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        // Browsers give these without a timezone, so the engine will take them to be in the
+        // question's
+        Question::DateTime {
+            min, max, timezone, ..
+        } => {
+            let timezone = timezone.unwrap_or(FixedOffset::east_opt(0).unwrap());
+            let in_timezone = move |datetime: DateTime<FixedOffset>| {
+                format_datetime(&datetime.with_timezone(&timezone))
+            };
+            view! {
+                <input
+                    type="datetime-local"
+                    class="border rounded p-1 min-h-[44px]"
+                    min=min.map(in_timezone)
+                    max=max.map(in_timezone)
+                    prop:value=move || text.get()
+                    on:input=move |ev| text.set(event_target_value(&ev))
+                />
+                <span class="text-sm text-gray-600">{format!("UTC{timezone}")}</span>
+            }
+            .into_view()
+        }
+        Question::Rating {
+            min,
+            max,
+            min_label,
+            max_label,
+            ..
+        } => view! {
+            <div class="flex items-end gap-2">
+                {min_label.map(|label| view! { <span class="text-sm text-gray-600">{label}</span> })}
+                {(min..=max)
+                    .map(|rating| {
+                        let value = rating.to_string();
+                        let is_checked = value.clone();
+                        view! {
+                            <label class="flex flex-col items-center min-w-[44px] py-1">
+                                <input
+                                    type="radio"
+                                    prop:checked=move || text.with(|text| *text == is_checked)
+                                    on:change=move |_| text.set(value.clone())
+                                />
+                                {rating}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+                {max_label.map(|label| view! { <span class="text-sm text-gray-600">{label}</span> })}
+            </div>
+        }
+        .into_view(),
+        // Every point gets its label underneath, all in a row
+        Question::Likert { labels, .. } => view! {
+            <div class="flex items-start gap-2" role="radiogroup">
+                {labels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, label)| {
+                        let value = (idx + 1).to_string();
+                        let is_checked = value.clone();
+                        view! {
+                            <label class="flex flex-col items-center flex-1 min-w-[44px] py-1 text-center text-sm">
+                                <input
+                                    type="radio"
+                                    prop:checked=move || text.with(|text| *text == is_checked)
+                                    on:change=move |_| text.set(value.clone())
+                                />
+                                {label}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+        }
+        .into_view(),
+        // Browsers give the value of these as a number, which the engine will parse
+        Question::Range { min, max, step, .. } => view! {
+            <div class="flex items-center gap-2">
+                <input
+                    type="range"
+                    min=min
+                    max=max
+                    step=step.map(|step| step.to_string()).unwrap_or_else(|| "any".to_string())
+                    prop:value=move || text.get()
+                    on:input=move |ev| text.set(event_target_value(&ev))
+                />
+                <span>{move || text.get()}</span>
+            </div>
+        }
+        .into_view(),
+        // Browsers give these as `#rrggbb`, which the engine will parse
+        Question::Color { .. } => view! {
+            <input
+                type="color"
+                class="border rounded min-h-[44px] min-w-[44px]"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        // This is text rather than a number input, because browsers would give us a float (and
+        // the engine will parse it exactly)
+        Question::Decimal {
+            precision,
+            currency,
+            ..
+        } => view! {
+            <div class="flex items-center gap-2">
+                <input
+                    type="text"
+                    class="border rounded p-1 min-h-[44px]"
+                    inputmode=if precision == 0 { "numeric" } else { "decimal" }
+                    prop:value=move || text.get()
+                    on:input=move |ev| text.set(event_target_value(&ev))
+                />
+                {currency.map(|currency| view! { <span class="text-sm text-gray-600">{currency}</span> })}
+            </div>
+        }
+        .into_view(),
+        // Only the names of the chosen files are kept, not their contents: the answer records which
+        // files the user picked, and hosts that need the files themselves must read them separately
+        Question::File {
+            extensions,
+            multiple,
+            ..
+        } => {
+            let accept = (!extensions.is_empty()).then(|| {
+                extensions
+                    .iter()
+                    .map(|ext| format!(".{ext}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            });
+            view! {
+                <input
+                    type="file"
+                    class="border rounded p-1 min-h-[44px]"
+                    accept=accept
+                    multiple=multiple
+                    on:change=move |ev| {
+                        let files = event_target::<HtmlInputElement>(&ev).files();
+                        let names = files
+                            .map(|files| {
+                                (0..files.length())
+                                    .filter_map(|idx| files.get(idx))
+                                    .map(|file| file.name())
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        selected.set(names);
+                    }
+                />
+                {move || {
+                    selected
+                        .with(|names| !names.is_empty())
+                        .then(|| {
+                            view! {
+                                <p class="text-sm text-gray-600">
+                                    "Chosen: " {move || selected.get().join(", ")}
+                                </p>
+                            }
+                        })
+                }}
+            }
+            .into_view()
+        }
+        Question::Multiline {
+            min_length,
+            max_length,
+            ..
+        } => view! {
+            <textarea
+                class="border rounded p-1 min-h-[44px]"
+                minlength=min_length
+                maxlength=max_length
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            ></textarea>
+        }
+        .into_view(),
+        // Browsers will suggest options as the user types, and, if the user can't give their own
+        // value, the engine will catch anything that isn't an option
+        Question::Select {
+            options,
+            multiple: false,
+            searchable: true,
+            ..
+        } => {
+            let list_id = format!(
+                "question-options-{}",
+                NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed)
+            );
+            view! {
+                <input
+                    type="text"
+                    role="combobox"
+                    class="border rounded p-1 min-h-[44px]"
+                    placeholder="Start typing to search"
+                    list=list_id.clone()
+                    prop:value=move || {
+                        selected
+                            .with(|selected| selected.first().cloned())
+                            .or_else(|| other.get())
+                            .unwrap_or_default()
+                    }
+                    on:input=move |ev| {
+                        selected.set(vec![event_target_value(&ev)]);
+                        other.set(None);
+                    }
+                />
+                <datalist id=list_id>
+                    {options
+                        .into_iter()
+                        .map(|option| {
+                            view! {
+                                <option value=option.value.clone()>
+                                    {option.label().to_string()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </datalist>
+            }
+            .into_view()
+        }
+        // Native selects can't show images, so visual pickers get a grid of choices instead
+        Question::Select {
+            options,
+            multiple: false,
+            allow_other,
+            ..
+        } if options.iter().any(|option| option.image.is_some()) => {
+            let group = format!(
+                "question-options-{}",
+                NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed)
+            );
+            view! {
+                <div class="grid grid-cols-2 sm:grid-cols-3 gap-2" role="radiogroup">
+                    {options
+                        .into_iter()
+                        .map(|option| {
+                            let value = option.value.clone();
+                            let chosen = option.value.clone();
+                            let image = option_image(&option);
+                            let label = option.label().to_string();
+                            view! {
+                                <label class="flex flex-col gap-1 border rounded p-2">
+                                    {image}
+                                    <span>
+                                        <input
+                                            type="radio"
+                                            class="mr-2"
+                                            name=group.clone()
+                                            prop:checked=move || {
+                                                selected.with(|s| s.contains(&value))
+                                            }
+                                            on:change=move |_| {
+                                                selected.set(vec![chosen.clone()]);
+                                                other.set(None);
+                                            }
+                                        />
+                                        {label}
+                                    </span>
+                                    {option
+                                        .description
+                                        .map(|description| {
+                                            view! {
+                                                <span class="text-sm text-gray-600">
+                                                    {description}
+                                                </span>
+                                            }
+                                        })}
+                                </label>
+                            }
+                        })
+                        .collect_view()}
+                    {allow_other
+                        .then(|| {
+                            view! {
+                                <label class="flex items-center border rounded p-2">
+                                    <input
+                                        type="radio"
+                                        class="mr-2"
+                                        name=group.clone()
+                                        prop:checked=move || other.with(Option::is_some)
+                                        on:change=move |_| {
+                                            selected.set(Vec::new());
+                                            other.set(Some(String::new()));
+                                        }
+                                    />
+                                    "Other"
+                                </label>
+                            }
+                        })}
+                </div>
+                {move || other_input(other)}
+            }
+            .into_view()
+        }
+        // Native selects are fiddly on phones, so small screens get a list that slides up from the
+        // bottom instead
+        Question::Select {
+            options,
+            multiple: false,
+            allow_other,
+            ..
+        } => view! {
+            {bottom_sheet(options.clone(), allow_other, selected, other)}
+            <select
+                class="hidden sm:block border rounded p-1 min-h-[44px]"
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    // The other option is the only one without a value
+                    if allow_other && value.is_empty() {
+                        selected.set(Vec::new());
+                        other.update(|other| {
+                            other.get_or_insert_with(String::new);
+                        });
+                    } else {
+                        selected.set(vec![value]);
+                        other.set(None);
+                    }
+                }
+            >
+                {options
+                    .into_iter()
+                    .map(|option| {
+                        let value = option.value.clone();
+                        let label = option.label().to_string();
+                        view! {
+                            // Native options can't show anything else, so descriptions go in
+                            // tooltips
+                            <option
+                                value=option.value
+                                title=option.description
+                                selected=move || selected.with(|s| s.contains(&value))
+                            >
+                                {label}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+                {allow_other
+                    .then(|| {
+                        view! {
+                            <option value="" selected=move || other.with(Option::is_some)>
+                                "Other"
+                            </option>
+                        }
+                    })}
+            </select>
+            {move || other_input(other)}
+        }
+        .into_view(),
+        Question::Select {
+            options,
+            multiple: true,
+            allow_other,
+            min_selected,
+            max_selected,
+            ..
+        } => view! {
+            <div class="flex flex-col">
+                // The engine will check these, but it's easier for the user to know up front
+                {match (min_selected, max_selected) {
+                    (Some(min), Some(max)) if min == max => Some(format!("Choose {min}.")),
+                    (Some(min), Some(max)) => Some(format!("Choose {min} to {max}.")),
+                    (Some(min), None) => Some(format!("Choose at least {min}.")),
+                    (None, Some(max)) => Some(format!("Choose up to {max}.")),
+                    (None, None) => None,
+                }
+                    .map(|hint| view! { <p class="text-sm text-gray-600">{hint}</p> })}
+                {options
+                    .into_iter()
+                    .map(|option| {
+                        let value = option.value.clone();
+                        let toggled = option.value.clone();
+                        let image = option_image(&option);
+                        let label = option.label().to_string();
+                        view! {
+                            <label class="block py-2.5">
+                                {image}
+                                <input
+                                    type="checkbox"
+                                    class="mr-2"
+                                    prop:checked=move || selected.with(|s| s.contains(&value))
+                                    on:change=move |ev| {
+                                        let checked = event_target_checked(&ev);
+                                        selected
+                                            .update(|s| {
+                                                s.retain(|o| o != &toggled);
+                                                if checked {
+                                                    s.push(toggled.clone());
+                                                }
+                                            })
+                                    }
+                                />
+                                {label}
+                                {option
+                                    .description
+                                    .map(|description| {
+                                        view! {
+                                            <span class="block ml-6 text-sm text-gray-600">
+                                                {description}
+                                            </span>
+                                        }
+                                    })}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+                {allow_other
+                    .then(|| {
+                        view! {
+                            <label class="block py-2.5">
+                                <input
+                                    type="checkbox"
+                                    class="mr-2"
+                                    prop:checked=move || other.with(Option::is_some)
+                                    on:change=move |ev| {
+                                        if event_target_checked(&ev) {
+                                            other.set(Some(String::new()));
+                                        } else {
+                                            other.set(None);
+                                        }
+                                    }
+                                />
+                                "Other"
+                            </label>
+                        }
+                    })}
+                {move || other_input(other)}
+            </div>
+        }
+        .into_view(),
+        Question::Group { fields, max, .. } => {
+            let num_fields = fields.len();
+            view! {
+                <div class="flex flex-col gap-2">
+                    {move || {
+                        entries
+                            .get()
+                            .into_iter()
+                            .enumerate()
+                            .map(|(idx, values)| {
+                                let inputs = fields
+                                    .iter()
+                                    .zip(values)
+                                    .map(|(field, value)| {
+                                        view! {
+                                            <label class="flex flex-col">
+                                                {field.prompt.clone()}
+                                                <input
+                                                    type="text"
+                                                    class="border rounded p-1 min-h-[44px]"
+                                                    required=field.required
+                                                    inputmode=input_mode(field.pattern.as_deref())
+                                                    prop:value=move || value.get()
+                                                    on:input=move |ev| {
+                                                        value.set(event_target_value(&ev))
+                                                    }
+                                                />
+                                            </label>
+                                        }
+                                    })
+                                    .collect_view();
+                                view! {
+                                    <fieldset class="flex flex-col gap-1 border rounded p-2">
+                                        <legend>{format!("Entry {}", idx + 1)}</legend>
+                                        {inputs}
+                                        <button
+                                            type="button"
+                                            class="self-start border rounded px-1 min-h-[44px]"
+                                            on:click=move |_| {
+                                                entries
+                                                    .update(|entries| {
+                                                        entries.remove(idx);
+                                                    })
+                                            }
+                                        >
+                                            "Remove"
+                                        </button>
+                                    </fieldset>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                    <button
+                        type="button"
+                        class="self-start border rounded px-2 py-1 min-h-[44px]"
+                        disabled=move || {
+                            max.is_some_and(|max| entries.with(|entries| entries.len() >= max))
+                        }
+                        on:click=move |_| {
+                            entries
+                                .update(|entries| {
+                                    entries
+                                        .push(
+                                            (0..num_fields)
+                                                .map(|_| create_rw_signal(String::new()))
+                                                .collect(),
+                                        )
+                                })
+                        }
+                    >
+                        "Add entry"
+                    </button>
+                </div>
+            }
+            .into_view()
+        }
+        Question::Rank { options, .. } => view! {
+            <ol class="flex flex-col gap-1">
+                {move || {
+                    let order = selected.get();
+                    let len = order.len();
+                    order
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, value)| {
+                            let label = options
+                                .iter()
+                                .find(|option| option.value == value)
+                                .map_or(value.clone(), |option| option.label().to_string());
+                            view! {
+                                <li class="flex items-center gap-2">
+                                    <span>{format!("{}. {label}", idx + 1)}</span>
+                                    <button
+                                        type="button"
+                                        class="border rounded px-1 min-h-[44px] min-w-[44px]"
+                                        aria-label="Move up"
+                                        disabled=idx == 0
+                                        on:click=move |_| {
+                                            if idx > 0 {
+                                                selected.update(|order| order.swap(idx - 1, idx));
+                                            }
+                                        }
+                                    >
+                                        "↑"
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="border rounded px-1 min-h-[44px] min-w-[44px]"
+                                        aria-label="Move down"
+                                        disabled=idx + 1 == len
+                                        on:click=move |_| {
+                                            if idx + 1 < len {
+                                                selected.update(|order| order.swap(idx, idx + 1));
+                                            }
+                                        }
+                                    >
+                                        "↓"
+                                    </button>
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ol>
+        }
+        .into_view(),
+    };
+
+    let input = if specify_options.is_empty() {
+        input
+    } else {
+        view! {
+            {input}
+            {move || details_inputs(&specify_options, selected, details)}
+        }
+        .into_view()
+    };
+
+    let get_answer = move || {
+        if is_select {
+            let selected = selected.get().into_iter().chain(other.get());
+            // The engine will tell the user if they've left out any details it needs
+            let with_details = details.with(|details| {
+                selected
+                    .map(|option| {
+                        let text = details.get(&option).cloned();
+                        (option, text)
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if with_details.iter().any(|(_, text)| text.is_some()) {
+                Answer::OptionsWithText(with_details)
+            } else {
+                Answer::Options(with_details.into_iter().map(|(option, _)| option).collect())
+            }
+        } else if is_info {
+            Answer::Acknowledged
+        } else if is_group {
+            // Fields left empty are left out, as the engine expects
+            Answer::Group(
+                entries
+                    .get()
+                    .iter()
+                    .map(|values| {
+                        field_ids
+                            .iter()
+                            .zip(values)
+                            .map(|(id, value)| (id.clone(), value.get()))
+                            .filter(|(_, value)| !value.is_empty())
+                            .collect()
+                    })
+                    .collect(),
+            )
+        } else if is_file {
+            Answer::Files(selected.get().into_iter().map(PathBuf::from).collect())
+        } else {
+            Answer::Text(text.get())
+        }
+    };
+
+    (input, Rc::new(get_answer))
+}
+
+/// The image for the given select option, if it has one. The option's label is always shown next to
+/// it, so the image is decorative for screen readers.
+fn option_image(option: &SelectOption) -> Option<View> {
+    option.image.clone().map(|image| {
+        view! { <img src=image alt="" class="w-full max-w-[12rem] aspect-video object-cover rounded"/> }
+            .into_view()
+    })
+}
+
+/// A text input for the user's own value in a select question, which is only shown if they've
+/// chosen to give one.
+fn other_input(other: RwSignal<Option<String>>) -> impl IntoView {
+    other.with(Option::is_some).then(|| {
+        view! {
+            <input
+                type="text"
+                class="border rounded p-1 min-h-[44px]"
+                placeholder="Your own answer"
+                prop:value=move || other.get().unwrap_or_default()
+                on:input=move |ev| other.set(Some(event_target_value(&ev)))
+            />
+        }
+    })
+}
+
+/// Text inputs for the details of each of the given options (which all need details) that the
+/// user has chosen.
+fn details_inputs(
+    specify_options: &[SelectOption],
+    selected: RwSignal<Vec<String>>,
+    details: RwSignal<HashMap<String, String>>,
+) -> impl IntoView {
+    specify_options
+        .iter()
+        .filter(|option| selected.with(|selected| selected.contains(&option.value)))
+        .map(|option| {
+            let value = option.value.clone();
+            let updated = option.value.clone();
+            view! {
+                <label class="flex flex-col">
+                    {format!("Details for \"{}\"", option.label())}
+                    <input
+                        type="text"
+                        class="border rounded p-1 min-h-[44px]"
+                        required=true
+                        prop:value=move || {
+                            details.with(|details| details.get(&value).cloned().unwrap_or_default())
+                        }
+                        on:input=move |ev| {
+                            let text = event_target_value(&ev);
+                            details.update(|details| {
+                                details.insert(updated.clone(), text);
+                            })
+                        }
+                    />
+                </label>
+            }
+        })
+        .collect_view()
+}
+
+/// A list of the options in a single-choice select question that slides up from the bottom of the
+/// screen, which is only shown on small screens in place of a native select. This sets `selected`
+/// and `other` in the same way the native select would.
+fn bottom_sheet(
+    options: Vec<SelectOption>,
+    allow_other: bool,
+    selected: RwSignal<Vec<String>>,
+    other: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let open = create_rw_signal(false);
+    let labels = options
+        .iter()
+        .map(|option| (option.value.clone(), option.label().to_string()))
+        .collect::<Vec<_>>();
+    let chosen = move || {
+        if other.with(Option::is_some) {
+            return "Other".to_string();
+        }
+        selected
+            .with(|selected| {
+                labels
+                    .iter()
+                    .find(|(value, _)| selected.contains(value))
+                    .map(|(_, label)| label.clone())
+            })
+            .unwrap_or_else(|| "Choose an option".to_string())
+    };
+    let items = options
+        .into_iter()
+        .map(|option| {
+            let value = option.value.clone();
+            let is_selected = option.value.clone();
+            let label = option.label().to_string();
+            view! {
+                <li>
+                    <button
+                        type="button"
+                        role="option"
+                        class="w-full text-left px-4 py-2 min-h-[44px] border-b"
+                        aria-selected=move || {
+                            selected.with(|s| s.contains(&is_selected)).to_string()
+                        }
+                        on:click=move |_| {
+                            selected.set(vec![value.clone()]);
+                            other.set(None);
+                            open.set(false);
+                        }
+                    >
+                        {label}
+                        {option
+                            .description
+                            .map(|description| {
+                                view! {
+                                    <span class="block text-sm text-gray-600">{description}</span>
+                                }
+                            })}
+                    </button>
+                </li>
+            }
+        })
+        .collect_view();
+    let other_item = allow_other.then(|| {
+        view! {
+            <li>
+                <button
+                    type="button"
+                    role="option"
+                    class="w-full text-left px-4 py-2 min-h-[44px]"
+                    aria-selected=move || other.with(Option::is_some).to_string()
+                    on:click=move |_| {
+                        selected.set(Vec::new());
+                        other.update(|other| {
+                            other.get_or_insert_with(String::new);
+                        });
+                        open.set(false);
+                    }
+                >
+                    "Other"
+                </button>
+            </li>
+        }
+    });
+
+    view! {
+        <div class="sm:hidden">
+            <button
+                type="button"
+                class="w-full text-left border rounded p-1 min-h-[44px]"
+                aria-haspopup="listbox"
+                aria-expanded=move || open.get().to_string()
+                on:click=move |_| open.set(true)
+            >
+                {chosen}
+            </button>
+            // Tapping outside the list closes it without changing anything
+            <div
+                class="fixed inset-0 z-10 flex items-end bg-black/40"
+                class:hidden=move || !open.get()
+                on:click=move |_| open.set(false)
+            >
+                <ul
+                    role="listbox"
+                    class="w-full max-h-[70vh] overflow-y-auto rounded-t-lg bg-white"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    {items}
+                    {other_item}
+                </ul>
+            </div>
+        </div>
+    }
+}
+
+/// Guesses which on-screen keyboard suits a text input from the pattern its answer has to match,
+/// giving `numeric` if the pattern only allows digits, and `decimal` if it also allows a decimal
+/// point. Anything else gets the default keyboard.
+fn input_mode(pattern: Option<&str>) -> Option<&'static str> {
+    // Escaped points are marked with a character that can't otherwise be in a pattern, so we can
+    // tell them apart from unescaped ones (which match anything)
+    let pattern = pattern?.replace("\\d", "0").replace("\\.", "\u{1}");
+    let pattern = pattern.strip_prefix('^').unwrap_or(&pattern);
+    // Anchors, groups, and quantifiers don't change which characters can be typed
+    let only_numbers = pattern
+        .chars()
+        .all(|c| c.is_ascii_digit() || "\u{1}[]-(){},|?*+$".contains(c));
+    if !only_numbers || !pattern.chars().any(|c| c.is_ascii_digit()) {
+        None
+    } else if pattern.contains('\u{1}') {
+        Some("decimal")
+    } else {
+        Some("numeric")
+    }
+}
+
+/// Formats a time of day the way a time input expects, leaving out the seconds unless there are
+/// some.
+fn format_time(time: &NaiveTime) -> String {
+    if time.second() == 0 {
+        time.format("%H:%M").to_string()
+    } else {
+        time.format("%H:%M:%S").to_string()
+    }
+}
+
+/// Formats a date and time the way a local datetime input expects (i.e. without its timezone).
+fn format_datetime(datetime: &DateTime<FixedOffset>) -> String {
+    datetime.format("%Y-%m-%dT%H:%M").to_string()
+}
@@ -0,0 +1,64 @@
+use js_sys::Function;
+use serde_json::{json, Map, Value};
+use std::{cell::RefCell, sync::Once};
+use wasm_bindgen::JsValue;
+
+/// What we show in place of a form if this program panics. At that point, nothing else we've
+/// rendered can be trusted to work, so this is plain HTML.
+static PANIC_MESSAGE: &str = r#"<div role="alert"><p>Something went wrong with this form, and it can't continue. Please reload the page to try again.</p></div>"#;
+
+thread_local! {
+    /// The forms mounted on this page, by the IDs of their root elements, along with the hooks
+    /// their hosts gave for reporting errors. A panic could happen anywhere, so we need to know
+    /// about all of these globally to handle one.
+    static MOUNTED: RefCell<Vec<(String, Option<Function>)>> = const { RefCell::new(Vec::new()) };
+}
+static SET_PANIC_HOOK: Once = Once::new();
+
+/// Reports a failure to the host through their error hook, if they provided one. This will be
+/// given an object with the `kind` of failure (`"error"` for errors we can recover from, or
+/// `"panic"`), a `message` describing it, and the `answers` the user had given (in the same format
+/// the `answers` option takes), if they're still available.
+pub fn report_failure(
+    on_error: Option<&Function>,
+    kind: &str,
+    message: &str,
+    answers: Option<Map<String, Value>>,
+) {
+    let Some(on_error) = on_error else {
+        return;
+    };
+    let diagnostics = json!({ "kind": kind, "message": message, "answers": answers });
+    // This is valid JSON, so parsing it can't fail
+    let diagnostics = js_sys::JSON::parse(&diagnostics.to_string()).unwrap();
+    if let Err(err) = on_error.call1(&JsValue::NULL, &diagnostics) {
+        web_sys::console::error_2(&"error hook failed:".into(), &err);
+    }
+}
+
+/// Makes sure that, if this program panics, the form mounted at the element with the given ID will
+/// be replaced with a message telling the user, and the panic will be reported to the host through
+/// the given error hook. Panics will also be logged to the console.
+pub fn handle_panics(root_id: &str, on_error: Option<Function>) {
+    MOUNTED.with(|mounted| mounted.borrow_mut().push((root_id.to_string(), on_error)));
+    SET_PANIC_HOOK.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            console_error_panic_hook::hook(info);
+
+            let message = info.to_string();
+            MOUNTED.with(|mounted| {
+                // If we panicked while registering a form, there's nothing we can do
+                let Ok(mounted) = mounted.try_borrow() else {
+                    return;
+                };
+                let document = web_sys::window().unwrap().document().unwrap();
+                for (root_id, on_error) in mounted.iter() {
+                    if let Some(root) = document.get_element_by_id(root_id) {
+                        root.set_inner_html(PANIC_MESSAGE);
+                    }
+                    report_failure(on_error.as_ref(), "panic", &message, None);
+                }
+            });
+        }));
+    });
+}
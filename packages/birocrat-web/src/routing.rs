@@ -0,0 +1,107 @@
+use crate::state::FormSnapshot;
+use leptos::{ev, on_cleanup, window_event_listener};
+use wasm_bindgen::JsValue;
+
+/// A place in a form that can be linked to from the URL. These are represented in the URL hash as
+/// `#question-<id>` or `#complete`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Route {
+    /// The question with the given ID.
+    Question(String),
+    /// The end of the form, once every question has been answered.
+    Complete,
+}
+impl Route {
+    /// Gets the route for the question at the given index in the given snapshot, which may be the
+    /// next question, or the end of the form if that's done.
+    pub fn at(snapshot: &FormSnapshot, idx: usize) -> Self {
+        match snapshot.answered.get(idx) {
+            Some(answered) => Self::Question(answered.id.clone()),
+            None => match &snapshot.next {
+                Some((id, _, _)) => Self::Question(id.clone()),
+                None => Self::Complete,
+            },
+        }
+    }
+    /// Gets the index this route refers to in the given snapshot, if the user can get there.
+    /// Questions that haven't been reached yet can't be linked to, because the script determines
+    /// which questions are asked.
+    pub fn index_in(&self, snapshot: &FormSnapshot) -> Option<usize> {
+        let next_idx = snapshot.answered.len();
+        match self {
+            Self::Question(id) => snapshot
+                .answered
+                .iter()
+                .position(|answered| &answered.id == id)
+                .or_else(|| {
+                    snapshot
+                        .next
+                        .as_ref()
+                        .filter(|(next_id, _, _)| next_id == id)
+                        .map(|_| next_idx)
+                }),
+            Self::Complete => snapshot.next.is_none().then_some(next_idx),
+        }
+    }
+    /// Gets the ID of the element that should be rendered for this route, so the browser can
+    /// scroll to it.
+    pub fn element_id(&self) -> String {
+        match self {
+            Self::Question(id) => format!("question-{id}"),
+            Self::Complete => "complete".to_string(),
+        }
+    }
+
+    /// Gets the route in the current URL hash, if there is one.
+    pub fn current() -> Option<Self> {
+        let hash = web_sys::window().unwrap().location().hash().ok()?;
+        let hash = hash.strip_prefix('#')?;
+        if hash == "complete" {
+            Some(Self::Complete)
+        } else {
+            let id = js_sys::decode_uri_component(hash.strip_prefix("question-")?).ok()?;
+            Some(Self::Question(id.into()))
+        }
+    }
+    /// Puts this route in the URL hash, adding an entry to the browser's history (unless
+    /// `replace` is set), so the back and forward buttons can move between questions. This does
+    /// nothing if the URL already has this route.
+    pub fn set(&self, replace: bool) {
+        if Self::current().as_ref() == Some(self) {
+            return;
+        }
+        let hash = match self {
+            Self::Question(id) => format!("#question-{}", js_sys::encode_uri_component(id)),
+            Self::Complete => "#complete".to_string(),
+        };
+        let history = web_sys::window().unwrap().history().unwrap();
+        // This can only fail for cross-origin URLs, which a hash can't produce
+        if replace {
+            history
+                .replace_state_with_url(&JsValue::NULL, "", Some(&hash))
+                .unwrap();
+        } else {
+            history
+                .push_state_with_url(&JsValue::NULL, "", Some(&hash))
+                .unwrap();
+        }
+    }
+    /// Scrolls the element for this route into view, if it's been rendered.
+    pub fn scroll_into_view(&self) {
+        let element = web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .get_element_by_id(&self.element_id());
+        if let Some(element) = element {
+            element.scroll_into_view();
+        }
+    }
+}
+
+/// Calls the given function with the route in the URL whenever the user navigates through their
+/// history (e.g. with the back and forward buttons) for as long as the current component exists.
+pub fn on_navigate(f: impl Fn(Option<Route>) + 'static) {
+    let handle = window_event_listener(ev::popstate, move |_| f(Route::current()));
+    on_cleanup(move || handle.remove());
+}
@@ -0,0 +1,463 @@
+use crate::{
+    error::Error,
+    hooks::emit_event,
+    worker::{Answered, FormWorker, Request, Response},
+};
+use birocrat::{Answer, Form, Question, Transcript, TranscriptEntry};
+use js_sys::Function;
+use leptos::{
+    create_rw_signal, request_idle_callback, store_value, RwSignal, SignalGetUntracked, SignalSet,
+    SignalUpdate, SignalWithUntracked, StoredValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Everything needed to render a form at a particular point. Unlike the form itself, this can be
+/// cloned freely, and so stored in signals, and sent from the worker the form runs in.
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FormSnapshot {
+    /// The questions that have been answered, in the order they were asked.
+    pub answered: Vec<TranscriptEntry>,
+    /// The next question to be answered, along with its ID and any cached answer for it. If this
+    /// is `None`, the form is done, and can be submitted.
+    pub next: Option<(String, Question, Option<Answer>)>,
+}
+impl FormSnapshot {
+    /// Takes a snapshot of the given form.
+    pub fn of(form: &Form) -> Self {
+        Self {
+            answered: form.transcript().entries,
+            next: form.next_question().map(|(question, answer)| {
+                (
+                    // This will always exist if there's a next question
+                    form.next_question_id().unwrap().to_string(),
+                    question.clone(),
+                    answer.cloned(),
+                )
+            }),
+        }
+    }
+    /// Gets the answers in this snapshot as a JSON object keyed by question ID, in the format
+    /// [`crate::options::parse_answers`] takes. Answers to secret questions (including those tagged
+    /// as secret, and pages with secret questions on them) are left out, so they never leave the
+    /// page.
+    pub fn answers_json(&self) -> serde_json::Map<String, Value> {
+        self.answered
+            .iter()
+            .filter(|answered| !answered.question.is_secret())
+            .filter_map(|answered| Some((answered.id.clone(), answer_to_json(&answered.answer)?)))
+            .collect()
+    }
+}
+
+/// Converts an answer into JSON (see [`Answer::to_json`]). Secrets give `None`, so they're never
+/// saved, as do computed steps, because the form works those out again itself. Pages and
+/// selections with follow-ups that include either of these also give `None`, because they can't
+/// be answered without them.
+fn answer_to_json(answer: &Answer) -> Option<Value> {
+    is_restorable(answer).then(|| answer.to_json())
+}
+
+/// Whether or not the given answer can be saved and given back to the form later, which is the
+/// case unless it is, or contains, a secret or a computed value.
+fn is_restorable(answer: &Answer) -> bool {
+    match answer {
+        Answer::Secret(_) | Answer::Computed(_) => false,
+        Answer::WithFollowUp { answer, followup } => {
+            is_restorable(answer) && is_restorable(followup)
+        }
+        Answer::Page(answers) => answers.values().all(is_restorable),
+        _ => true,
+    }
+}
+
+/// Generates a new seed for a form. The engine can't do this itself in the browser, so we use
+/// `Math.random`, which is plenty for choosing between variants of questions.
+pub fn random_seed() -> u64 {
+    let half = || (js_sys::Math::random() * 2_f64.powi(32)) as u64;
+    (half() << 32) | half()
+}
+
+/// A form that has been submitted.
+#[derive(Clone, PartialEq)]
+pub struct Submission {
+    /// The final output of the form script.
+    pub output: Value,
+    /// A transcript of the questions the user answered.
+    pub transcript: Transcript,
+    /// The token the host's challenge hook produced before the form was submitted, if there is
+    /// one, which the host should verify before accepting the submission.
+    pub challenge_token: Option<String>,
+}
+
+/// A reactive handle to a form running in the browser. This is `Copy`, so it can be moved into as
+/// many event handlers as necessary, and the signals it holds will update whenever the form
+/// progresses.
+///
+/// The form's script runs in a worker (see [`FormWorker`]), so the page stays responsive however
+/// long it takes. Everything that needs the script is asynchronous as a result, and the signals
+/// here will update when the worker replies.
+#[derive(Clone, Copy)]
+pub struct FormHandle {
+    /// The worker the form runs in, which owns the VM its script runs in, so the VM is freed
+    /// whenever the worker is replaced. This will be `None` once the form has been closed.
+    worker: StoredValue<Option<FormWorker>>,
+    /// The script driving the form, which we need to rebuild it if the host rejects a submission.
+    script: StoredValue<String>,
+    /// The parameters the form was given, which we need to rebuild it if the host rejects a
+    /// submission.
+    params: StoredValue<Value>,
+    /// A snapshot of the form's current state.
+    pub snapshot: RwSignal<FormSnapshot>,
+    /// Whether or not the form is still being built, in which case [`Self::snapshot`] is empty.
+    pub loading: RwSignal<bool>,
+    /// The index of the question whose answer is currently being processed, if there is one.
+    pub pending: RwSignal<Option<usize>>,
+    /// The last error the script gave in response to an answer, along with the index of the
+    /// question that answer was for. This is cleared whenever an answer is accepted.
+    pub script_error: RwSignal<Option<(usize, String)>>,
+    /// Whether or not the form is in the process of being submitted, in which case it can't be
+    /// changed.
+    pub submitting: RwSignal<bool>,
+    /// The form's submission, once it has been submitted.
+    pub submission: RwSignal<Option<Submission>>,
+    /// Errors the host gave for particular questions when it rejected a submission, keyed by
+    /// question ID. Each of these is cleared when its question is answered again.
+    pub field_errors: RwSignal<HashMap<String, String>>,
+    /// A description of the last hard error the form produced, if there was one. Hard errors
+    /// indicate a problem with the script or this interface, rather than with the user's answers.
+    pub hard_error: RwSignal<Option<String>>,
+    /// Whether or not the form can't be used any more, because its script failed to start or its
+    /// worker failed. This is set along with [`Self::hard_error`], and, unlike other hard errors,
+    /// the user can't carry on from these.
+    pub broken: RwSignal<bool>,
+    /// The number of the last request for suggestions, so replies to older requests, which the
+    /// user has typed past, can be ignored.
+    latest_suggest: StoredValue<u64>,
+    /// The host's event hook, if they gave one (see [`emit_event`]).
+    on_event: StoredValue<Option<Function>>,
+    /// When the form was started, in milliseconds since the epoch, which completion and
+    /// abandonment events are timed from.
+    started: f64,
+}
+impl FormHandle {
+    /// Creates a new form from the given script, parameters, seed, and variants assigned by the
+    /// host, filling in any answers given ahead of time (e.g. from a saved session), keyed by
+    /// question ID. Events will be sent to the given hook as the user moves through the form.
+    ///
+    /// This starts the worker the form runs in, and returns straight away, with
+    /// [`Self::loading`] set until the form has been built. If the script fails to start, that
+    /// will be reflected in [`Self::hard_error`].
+    pub fn new(
+        script: &str,
+        params: &Value,
+        seed: u64,
+        variants: HashMap<String, String>,
+        preset_answers: HashMap<String, Answer>,
+        on_event: Option<Function>,
+    ) -> Result<Self, Error> {
+        let this = Self {
+            worker: store_value(None),
+            script: store_value(script.to_string()),
+            params: store_value(params.clone()),
+            snapshot: create_rw_signal(FormSnapshot::default()),
+            loading: create_rw_signal(true),
+            pending: create_rw_signal(None),
+            script_error: create_rw_signal(None),
+            submitting: create_rw_signal(false),
+            submission: create_rw_signal(None),
+            field_errors: create_rw_signal(HashMap::new()),
+            hard_error: create_rw_signal(None),
+            broken: create_rw_signal(false),
+            latest_suggest: store_value(0),
+            on_event: store_value(on_event),
+            started: js_sys::Date::now(),
+        };
+        let worker = FormWorker::spawn(move |message| this.fail(message)).map_err(|err| {
+            Error::SpawnWorkerFailed {
+                message: format!("{err:?}"),
+            }
+        })?;
+        this.worker.set_value(Some(worker));
+
+        this.build(seed, variants, preset_answers, move |snapshot| {
+            // Everything else the interface shows depends on the snapshot, so this has to come
+            // after it
+            this.snapshot.set(snapshot);
+            this.loading.set(false);
+            this.emit_viewed();
+        });
+        Ok(this)
+    }
+    /// Answers the question at the given index (which may be a question that was answered before,
+    /// in which case all later questions will be discarded), calling `on_accepted` if the script
+    /// accepts the answer. If the script rejects the answer, this will be reflected in
+    /// [`Self::script_error`]. If the answer was of the wrong type for the question, that will be
+    /// reflected in [`Self::hard_error`].
+    ///
+    /// The script can take a while to process answers, so this sets [`Self::pending`] until the
+    /// worker replies, so the user can see their answer is being processed, while the page stays
+    /// responsive. While an answer is pending, or while the form is being submitted, any others
+    /// will be ignored.
+    pub fn answer(&self, idx: usize, answer: Answer, on_accepted: impl FnOnce() + 'static) {
+        if self.pending.get_untracked().is_some() || self.submitting.get_untracked() {
+            return;
+        }
+        self.pending.set(Some(idx));
+
+        let this = *self;
+        self.send(Request::Answer { idx, answer }, move |response| {
+            let Response::Answered(result) = response else {
+                unreachable!("worker gave wrong response to answer");
+            };
+            let accepted = this.apply_answer(idx, result);
+            this.pending.set(None);
+            if accepted {
+                this.prefetch_when_idle();
+                on_accepted();
+            }
+        });
+    }
+    /// Applies the worker's response to an answer to the question at the given index, returning
+    /// whether or not the script accepted the answer.
+    fn apply_answer(&self, idx: usize, result: Result<Answered, String>) -> bool {
+        match result {
+            Ok(Answered {
+                snapshot,
+                id,
+                script_error,
+                review_errors,
+            }) => {
+                let accepted = script_error.is_none() && review_errors.is_none();
+                if let Some(message) = &script_error {
+                    self.emit(
+                        "validationFailed",
+                        json!({ "questionId": id, "message": message }),
+                    );
+                }
+                for (id, message) in review_errors.iter().flatten() {
+                    self.emit(
+                        "validationFailed",
+                        json!({ "questionId": id, "message": message }),
+                    );
+                }
+                if accepted {
+                    self.emit("answered", json!({ "questionId": id }));
+                }
+                // Errors from the script's review of the whole form are shown on the questions
+                // they're for, just like errors from the host
+                if let Some(review_errors) = review_errors {
+                    self.field_errors.set(review_errors);
+                }
+                if accepted {
+                    if let Some(answered) = snapshot.answered.get(idx) {
+                        self.field_errors.update(|errors| {
+                            errors.remove(&answered.id);
+                        });
+                    }
+                }
+                self.snapshot.set(snapshot);
+                self.script_error.set(script_error.map(|err| (idx, err)));
+                if accepted {
+                    self.emit_viewed();
+                }
+                accepted
+            }
+            Err(message) => {
+                self.hard_error.set(Some(message));
+                false
+            }
+        }
+    }
+    /// Works out what the script would do with likely answers to the next question whenever the
+    /// browser is idle, one answer at a time, so answering it feels instant even if the script is
+    /// slow (see [`Form::prefetch`]). This stops once there's nothing left to work out, or the
+    /// user starts answering.
+    fn prefetch_when_idle(&self) {
+        let this = *self;
+        request_idle_callback(move || {
+            if this.pending.get_untracked().is_some() || this.submitting.get_untracked() {
+                return;
+            }
+            this.send(Request::Prefetch, move |response| {
+                if matches!(response, Response::Prefetched { num_tried } if num_tried > 0) {
+                    this.prefetch_when_idle();
+                }
+            });
+        });
+    }
+    /// Finishes the form, if it's done, calling `on_finished` with its output and a transcript of
+    /// the user's answers, so they can be submitted, or with `None` if it isn't done. Until the
+    /// submission is either accepted with [`Self::accept`] or rejected with [`Self::reopen`], the
+    /// form can't be changed.
+    pub fn finish(&self, on_finished: impl FnOnce(Option<(Value, Transcript)>) + 'static) {
+        if self.pending.get_untracked().is_some() || self.submitting.get_untracked() {
+            return;
+        }
+        self.submitting.set(true);
+
+        let this = *self;
+        self.send(Request::Finish, move |response| {
+            let Response::Finished(finished) = response else {
+                unreachable!("worker gave wrong response to finish");
+            };
+            if finished.is_none() {
+                this.submitting.set(false);
+            }
+            on_finished(finished);
+        });
+    }
+    /// Gets suggestions for an answer to the question with the given ID, based on the text the
+    /// user has typed so far (see [`Form::suggest`]), calling `on_suggested` with them once the
+    /// worker replies. If this is called again before then, the earlier request is cancelled, and
+    /// `on_suggested` won't be called for it, so suggestions for old text never replace newer ones.
+    ///
+    /// Errors from the script are logged to the console, rather than shown to the user, since
+    /// they can still answer without suggestions.
+    pub fn suggest(
+        &self,
+        question_id: String,
+        text: String,
+        on_suggested: impl FnOnce(Vec<String>) + 'static,
+    ) {
+        self.latest_suggest.update_value(|latest| *latest += 1);
+        let request_num = self.latest_suggest.get_value();
+
+        let this = *self;
+        self.send(Request::Suggest { question_id, text }, move |response| {
+            let Response::Suggested(suggested) = response else {
+                unreachable!("worker gave wrong response to suggest");
+            };
+            if this.latest_suggest.get_value() != request_num {
+                return;
+            }
+            match suggested {
+                Ok(suggestions) => on_suggested(suggestions),
+                Err(message) => web_sys::console::error_1(
+                    &format!("failed to get suggestions: {message}").into(),
+                ),
+            }
+        });
+    }
+    /// Accepts a submission of the form, along with the token from the host's challenge hook, if
+    /// there was one. After this, the form can no longer be changed.
+    pub fn accept(&self, output: Value, transcript: Transcript, challenge_token: Option<String>) {
+        self.emit(
+            "completed",
+            json!({ "durationMs": self.elapsed(), "variants": transcript.variants }),
+        );
+        self.submitting.set(false);
+        self.submission.set(Some(Submission {
+            output,
+            transcript,
+            challenge_token,
+        }));
+    }
+    /// Reopens the form after a submission was rejected, rebuilding it from the given transcript
+    /// of the submission, and showing the given errors on the questions with those IDs. The form
+    /// stays in [`Self::submitting`] until it's been rebuilt.
+    pub fn reopen(&self, transcript: Transcript, field_errors: HashMap<String, String>) {
+        let answers = transcript
+            .entries
+            .into_iter()
+            .map(|entry| (entry.id, entry.answer))
+            .collect();
+        let this = *self;
+        // The transcript records the seed and variants, so the script will make the same choices it
+        // did before
+        self.build(
+            transcript.seed,
+            transcript.variants,
+            answers,
+            move |snapshot| {
+                this.snapshot.set(snapshot);
+                this.field_errors.set(field_errors);
+                this.submitting.set(false);
+            },
+        );
+    }
+    /// Builds the form in its worker with the given seed, variants, and answers, calling
+    /// `on_built` with a snapshot of it once it's been built. If the script fails to start, the
+    /// form will be marked as broken instead.
+    fn build(
+        &self,
+        seed: u64,
+        variants: HashMap<String, String>,
+        answers: HashMap<String, Answer>,
+        on_built: impl FnOnce(FormSnapshot) + 'static,
+    ) {
+        let request = Request::Build {
+            script: self.script.get_value(),
+            params: self.params.get_value(),
+            seed,
+            variants,
+            answers,
+        };
+        let this = *self;
+        self.send(request, move |response| {
+            let Response::Built(built) = response else {
+                unreachable!("worker gave wrong response to build");
+            };
+            match built {
+                Ok(snapshot) => {
+                    on_built(snapshot);
+                    this.prefetch_when_idle();
+                }
+                Err(message) => this.fail(message),
+            }
+        });
+    }
+    /// Marks the form as broken, because of the error with the given message.
+    fn fail(&self, message: String) {
+        // The interface decides what the user can do from this, so it has to come first
+        self.broken.set(true);
+        self.hard_error.set(Some(message));
+    }
+    /// Drops the form, terminating the worker its script runs in. Handles last as long as the page
+    /// does, so this should be called on a handle that's about to be replaced by a new one.
+    pub fn close(&self) {
+        self.worker.set_value(None);
+    }
+    /// Lets the host know the user left the page without submitting the form, if they haven't
+    /// submitted it.
+    pub fn abandon(&self) {
+        if self.submission.with_untracked(Option::is_some) {
+            return;
+        }
+        let id = self
+            .snapshot
+            .with_untracked(|snapshot| snapshot.next.as_ref().map(|(id, _, _)| id.clone()));
+        self.emit(
+            "abandoned",
+            json!({ "questionId": id, "durationMs": self.elapsed() }),
+        );
+    }
+    /// Sends the given request to the form's worker, calling `on_reply` with its response. If the
+    /// form has been closed, this will do nothing.
+    fn send(&self, request: Request, on_reply: impl FnOnce(Response) + 'static) {
+        self.worker.with_value(|worker| {
+            if let Some(worker) = worker {
+                worker.send(&request, on_reply);
+            }
+        });
+    }
+    /// Lets the host know the user is being shown the next question, if there is one.
+    fn emit_viewed(&self) {
+        let id = self
+            .snapshot
+            .with_untracked(|snapshot| snapshot.next.as_ref().map(|(id, _, _)| id.clone()));
+        if let Some(id) = id {
+            self.emit("questionViewed", json!({ "questionId": id }));
+        }
+    }
+    /// Sends an event with the given type and details to the host's event hook, if there is one.
+    fn emit(&self, ty: &str, details: Value) {
+        self.on_event
+            .with_value(|on_event| emit_event(on_event.as_ref(), ty, details));
+    }
+    /// Gets how long it's been since the form was started, in milliseconds.
+    fn elapsed(&self) -> f64 {
+        js_sys::Date::now() - self.started
+    }
+}
@@ -0,0 +1,164 @@
+use crate::markdown::prompt_text;
+use birocrat::{Answer, Question, Transcript};
+use leptos::{component, view, window, CollectView, IntoView};
+use serde_json::Value;
+
+/// A read-only record of every question the user answered and how they answered it, styled so it
+/// can be printed (or saved as a PDF through the browser's print dialog).
+#[component]
+pub fn TranscriptView(transcript: Transcript) -> impl IntoView {
+    let entries = transcript
+        .entries
+        .into_iter()
+        // Information steps weren't really answered, so they don't belong on the record
+        .filter(|entry| !matches!(entry.question, Question::Info { .. }))
+        .map(|entry| {
+            let answer = answer_text(&entry.question, entry.answer);
+            view! {
+                <div class="break-inside-avoid">
+                    <dt class="font-semibold">{prompt_text(&entry.question)}</dt>
+                    <dd class="whitespace-pre-wrap">{answer}</dd>
+                </div>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="flex flex-col gap-4 print:text-black">
+            <button
+                class="self-start border rounded px-2 py-1 min-h-[44px] print:hidden"
+                on:click=|_| {
+                    // If printing isn't available, there's nothing useful we can do
+                    let _ = window().print();
+                }
+            >
+                "Print"
+            </button>
+            <dl class="flex flex-col gap-2">{entries}</dl>
+        </div>
+    }
+}
+
+/// Describes the given answer to the given question for people to read.
+pub fn answer_text(question: &Question, answer: Answer) -> String {
+    match (question, answer) {
+        // Secrets shouldn't end up on paper
+        (question, _) if question.is_secret() => "••••••••".to_string(),
+        (_, Answer::Secret(_)) => "••••••••".to_string(),
+        (_, Answer::Skipped) => "(skipped)".to_string(),
+        (_, Answer::Text(text)) => text,
+        (_, Answer::Options(selected)) if selected.is_empty() => "(none)".to_string(),
+        // Show the labels the user saw, rather than the values
+        (
+            Question::Select { options, .. } | Question::Rank { options, .. },
+            Answer::Options(selected),
+        ) => selected
+            .iter()
+            .map(|value| {
+                options
+                    .iter()
+                    .find(|option| option.value == *value)
+                    .map_or(value.as_str(), |option| option.label())
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Answer::Options(selected)) => selected.join(", "),
+        // Details go after the options they're for
+        (question, Answer::OptionsWithText(selected)) => selected
+            .into_iter()
+            .map(|(value, text)| {
+                let label = match question {
+                    Question::Select { options, .. } => options
+                        .iter()
+                        .find(|option| option.value == value)
+                        .map_or(value.clone(), |option| option.label().to_string()),
+                    _ => value,
+                };
+                match text {
+                    Some(text) => format!("{label} ({text})"),
+                    None => label,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        // Follow-ups go after the selection they're for
+        (
+            Question::Select {
+                followup: Some(followup),
+                ..
+            },
+            Answer::WithFollowUp {
+                answer,
+                followup: followup_answer,
+            },
+        ) => format!(
+            "{} ({}: {})",
+            answer_text(question, *answer),
+            prompt_text(&followup.question),
+            answer_text(&followup.question, *followup_answer)
+        ),
+        (_, Answer::WithFollowUp { answer, .. }) => answer_text(question, *answer),
+        // Show the label the user chose, rather than the point
+        (Question::Likert { labels, .. }, Answer::Rating(point)) => labels
+            .get(point as usize - 1)
+            .cloned()
+            .unwrap_or_else(|| point.to_string()),
+        (Question::Rating { max, .. }, Answer::Rating(rating)) => {
+            format!("{rating} out of {max}")
+        }
+        (_, Answer::Rating(rating)) => rating.to_string(),
+        (_, Answer::Range(value)) => value.to_string(),
+        (
+            Question::Decimal {
+                currency: Some(currency),
+                ..
+            },
+            Answer::Decimal(decimal),
+        ) => format!("{decimal} {currency}"),
+        (_, Answer::Decimal(decimal)) => decimal.to_string(),
+        (_, Answer::Color(color)) => color.to_string(),
+        (_, Answer::Acknowledged) => "Acknowledged".to_string(),
+        // Strings are shown as they are, and anything else as JSON
+        (_, Answer::Computed(Value::String(text))) => text,
+        (_, Answer::Computed(value)) => value.to_string(),
+        (_, Answer::Group(entries)) if entries.is_empty() => "(none)".to_string(),
+        // One entry per line, with its fields in the order the question gave them
+        (Question::Group { fields, .. }, Answer::Group(entries)) => entries
+            .iter()
+            .map(|entry| {
+                fields
+                    .iter()
+                    .filter_map(|field| {
+                        let value = entry.get(&field.id)?;
+                        Some(format!("{}: {value}", field.prompt))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        (_, Answer::Group(entries)) => format!("{} entries", entries.len()),
+        // One question per line, in the order they were on the page
+        (Question::Page { questions, .. }, Answer::Page(mut answers)) => questions
+            .iter()
+            .filter_map(|item| {
+                let answer = answers.remove(&item.id)?;
+                Some(format!(
+                    "{}: {}",
+                    prompt_text(&item.question),
+                    answer_text(&item.question, answer)
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        (_, Answer::Page(answers)) => format!("{} answers", answers.len()),
+        (_, Answer::Files(paths)) => paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Answer::Date(date)) => date.format("%-d %B %Y").to_string(),
+        (_, Answer::Time(time)) => time.format("%H:%M:%S").to_string(),
+        (_, Answer::DateTime(datetime)) => datetime.format("%-d %B %Y, %H:%M (UTC%:z)").to_string(),
+    }
+}
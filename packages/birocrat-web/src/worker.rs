@@ -0,0 +1,306 @@
+use crate::state::FormSnapshot;
+use birocrat::{error::Error, Answer, FormOptions, FormPoll, OwnedForm, Transcript};
+use fmterr::fmterr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{
+    Blob, BlobPropertyBag, DedicatedWorkerGlobalScope, ErrorEvent, Event, MessageEvent, Url,
+    Worker, WorkerOptions, WorkerType,
+};
+
+/// Something the page asks the worker running a form to do. The worker handles these one at a
+/// time, in the order they were sent, and replies to each with a [`Response`].
+#[derive(Serialize, Deserialize)]
+pub enum Request {
+    /// Builds a new form (replacing any old one) from the given script, parameters, seed, and
+    /// variants, filling in the given answers (see [`build_form`]).
+    Build {
+        script: String,
+        params: Value,
+        seed: u64,
+        variants: HashMap<String, String>,
+        answers: HashMap<String, Answer>,
+    },
+    /// Answers the question at the given index.
+    Answer { idx: usize, answer: Answer },
+    /// Works out what the script would do with one more likely answer to the next question.
+    Prefetch,
+    /// Finishes the form, if it's done.
+    Finish,
+    /// Gets suggestions for an answer to the question with the given ID, based on the text the
+    /// user has typed so far.
+    Suggest { question_id: String, text: String },
+}
+
+/// The worker's reply to a [`Request`].
+#[derive(Serialize, Deserialize)]
+pub enum Response {
+    /// The form was built, or the script failed to start.
+    Built(Result<FormSnapshot, String>),
+    /// The answer was processed, or it was of the wrong type for its question.
+    Answered(Result<Answered, String>),
+    /// How many answers the script was tried with (see [`birocrat::Form::prefetch`]).
+    Prefetched { num_tried: usize },
+    /// The form's output and a transcript of the user's answers, if it was done. Once the form
+    /// has been finished, it's gone, and will have to be built again to be changed.
+    Finished(Option<(Value, Transcript)>),
+    /// Suggestions for the answer, or the error the script gave trying to produce them.
+    Suggested(Result<Vec<String>, String>),
+}
+
+/// What happened when the form was given an answer.
+#[derive(Serialize, Deserialize)]
+pub struct Answered {
+    /// A snapshot of the form after the answer.
+    pub snapshot: FormSnapshot,
+    /// The ID of the question that was answered, if there was one at the given index.
+    pub id: Option<String>,
+    /// The error the script gave in rejecting the answer, if it did.
+    pub script_error: Option<String>,
+    /// The errors the script's review of the whole form gave for particular questions, keyed by
+    /// question ID, if it rejected the form.
+    pub review_errors: Option<HashMap<String, String>>,
+}
+
+thread_local! {
+    /// The form this worker is running. Workers run one form each.
+    static FORM: RefCell<Option<OwnedForm>> = const { RefCell::new(None) };
+}
+
+/// Starts handling requests in a worker spawned by [`FormWorker::spawn`]. Any messages that came
+/// in while this program was loading should be given here, in the order they came in, and they'll
+/// be handled before any new ones.
+#[wasm_bindgen]
+pub fn run_worker(queued: js_sys::Array) {
+    console_error_panic_hook::set_once();
+
+    let scope = js_sys::global().unchecked_into::<DedicatedWorkerGlobalScope>();
+    let respond = move |message: JsValue| {
+        // The page only ever sends us JSON it serialized itself
+        let request = serde_json::from_str(&message.as_string().unwrap()).unwrap();
+        let response = FORM.with(|form| handle(&mut form.borrow_mut(), request));
+        // Answers and outputs can always be serialized
+        scope
+            .post_message(&serde_json::to_string(&response).unwrap().into())
+            .unwrap();
+    };
+    queued.for_each(&mut |message, _, _| respond(message));
+
+    let on_message =
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| respond(event.data()));
+    js_sys::global()
+        .unchecked_into::<DedicatedWorkerGlobalScope>()
+        .set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // This has to last as long as the worker does
+    on_message.forget();
+}
+
+/// Handles a request against the given form.
+fn handle(form: &mut Option<OwnedForm>, request: Request) -> Response {
+    match request {
+        Request::Build {
+            script,
+            params,
+            seed,
+            variants,
+            answers,
+        } => {
+            // Free the old VM before we start a new one
+            *form = None;
+            let built = build_form(&script, &params, seed, variants, answers).map(|built| {
+                let snapshot = FormSnapshot::of(&built);
+                *form = Some(built);
+                snapshot
+            });
+            Response::Built(built.map_err(|err| fmterr(&err)))
+        }
+        Request::Answer { idx, answer } => {
+            let form = form
+                .as_mut()
+                .expect("attempted to answer question in submitted form");
+            Response::Answered(answer_form(form, idx, answer).map_err(|err| fmterr(&err)))
+        }
+        Request::Prefetch => Response::Prefetched {
+            num_tried: form.as_mut().map_or(0, |form| form.prefetch(1)),
+        },
+        Request::Finish => Response::Finished(form.take().and_then(|done| {
+            // We won't be able to get this once the form is consumed
+            let transcript = done.transcript();
+            match done.into_done() {
+                Ok(output) => Some((output, transcript)),
+                // The form wasn't done, so put it back
+                Err(unfinished) => {
+                    *form = Some(unfinished);
+                    None
+                }
+            }
+        })),
+        Request::Suggest { question_id, text } => Response::Suggested(match form {
+            Some(form) => form
+                .suggest(&question_id, &text)
+                .map_err(|err| fmterr(&err)),
+            // There's nothing to suggest for a form that's been submitted
+            None => Ok(Vec::new()),
+        }),
+    }
+}
+
+/// Answers the question at the given index in the given form.
+fn answer_form(form: &mut OwnedForm, idx: usize, answer: Answer) -> Result<Answered, Error> {
+    let id = form
+        .answers()
+        .nth(idx)
+        .map(|(id, _, _)| id)
+        .or_else(|| form.next_question_id())
+        .map(String::from);
+    let (script_error, review_errors) = match form.progress_with_answer(idx, answer)? {
+        FormPoll::Error(err) => (Some(err), None),
+        FormPoll::Rejected(field_errors) => (None, Some(field_errors)),
+        FormPoll::Question { .. } | FormPoll::Done(_) => (None, None),
+    };
+    Ok(Answered {
+        snapshot: FormSnapshot::of(form),
+        id,
+        script_error,
+        review_errors,
+    })
+}
+
+/// Creates a new form from the given script, parameters, seed, and variants assigned by the host,
+/// filling in any answers given ahead of time, keyed by question ID. Each of these will be used at
+/// most once, and we'll stop at the first one the script rejects, leaving it for the user.
+fn build_form(
+    script: &str,
+    params: &Value,
+    seed: u64,
+    variants: HashMap<String, String>,
+    mut preset_answers: HashMap<String, Answer>,
+) -> Result<OwnedForm, Error> {
+    let options = FormOptions {
+        seed: Some(seed),
+        variants,
+        ..FormOptions::default()
+    };
+    let mut form = OwnedForm::new_with_options(script, params, options)?;
+    while let Some(answer) = form
+        .next_question_id()
+        .and_then(|id| preset_answers.remove(id))
+    {
+        let idx = form.next_index();
+        // Answers of the wrong type are treated as rejections, because saved sessions might be
+        // from older versions of a script
+        if !matches!(
+            form.progress_with_answer(idx, answer),
+            Ok(FormPoll::Question { .. } | FormPoll::Done(_))
+        ) {
+            break;
+        }
+    }
+
+    Ok(form)
+}
+
+/// A handler for the worker's reply to a request.
+type ReplyHandler = Box<dyn FnOnce(Response)>;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The URL of the JS glue for this program, which workers import to load it themselves.
+    #[wasm_bindgen(thread_local_v2, js_namespace = ["import", "meta"], js_name = url)]
+    static MODULE_URL: String;
+}
+
+/// Waits for this program to load in a new worker, holding on to any messages that come in before
+/// then, and starts handling them with [`run_worker`].
+const WORKER_SCRIPT: &str = r#"
+const queued = [];
+self.onmessage = (event) => queued.push(event.data);
+const glue = await import(MODULE_URL);
+await glue.default();
+glue.run_worker(queued);
+"#;
+
+/// A worker running a form's script off the main thread, so the page stays responsive however
+/// long the script takes. Dropping this terminates the worker, freeing the VM the script runs in.
+pub struct FormWorker {
+    worker: Worker,
+    /// The URL of the worker's script, which we need to free when the worker is terminated.
+    script_url: String,
+    /// Handlers for the replies to the requests we've sent, in the order we sent them (which is
+    /// the order the worker replies in).
+    handlers: Rc<RefCell<VecDeque<ReplyHandler>>>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+}
+impl FormWorker {
+    /// Spawns a new worker, which will call `on_failure` with a description of the problem if it
+    /// fails (e.g. if the script panics, or this program can't be loaded in the worker). The
+    /// worker loads this program from the same place the page did, with its default settings.
+    pub fn spawn(on_failure: impl Fn(String) + 'static) -> Result<Self, JsValue> {
+        let script = WORKER_SCRIPT.replace(
+            "MODULE_URL",
+            // This is valid JS for a string
+            &Value::String(MODULE_URL.with(String::clone)).to_string(),
+        );
+        let options = BlobPropertyBag::new();
+        options.set_type("text/javascript");
+        let blob =
+            Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&script.into()), &options)?;
+        let script_url = Url::create_object_url_with_blob(&blob)?;
+        let options = WorkerOptions::new();
+        options.set_type(WorkerType::Module);
+        let worker = Worker::new_with_options(&script_url, &options)?;
+
+        let handlers = Rc::new(RefCell::new(VecDeque::<ReplyHandler>::new()));
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+            let handlers = handlers.clone();
+            move |event: MessageEvent| {
+                // The worker only ever sends us JSON it serialized itself
+                let response = serde_json::from_str(&event.data().as_string().unwrap()).unwrap();
+                // Handlers might send more requests, so we can't hold the queue while calling them
+                let handler = handlers.borrow_mut().pop_front();
+                if let Some(handler) = handler {
+                    handler(response);
+                }
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        let on_error = Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+            let message = match event.dyn_ref::<ErrorEvent>() {
+                Some(event) => event.message(),
+                None => "the form's worker failed to start".to_string(),
+            };
+            on_failure(message);
+        });
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            worker,
+            script_url,
+            handlers,
+            _on_message: on_message,
+            _on_error: on_error,
+        })
+    }
+    /// Sends the given request to the worker, calling `on_reply` with its response.
+    pub fn send(&self, request: &Request, on_reply: impl FnOnce(Response) + 'static) {
+        self.handlers.borrow_mut().push_back(Box::new(on_reply));
+        // Answers can always be serialized
+        let message = serde_json::to_string(request).unwrap();
+        // Strings can always be cloned into the worker
+        self.worker.post_message(&message.into()).unwrap();
+    }
+}
+impl Drop for FormWorker {
+    fn drop(&mut self) {
+        self.worker.terminate();
+        // This can only fail if the URL is invalid, and we made it ourselves
+        let _ = Url::revoke_object_url(&self.script_url);
+    }
+}
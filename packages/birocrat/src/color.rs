@@ -0,0 +1,72 @@
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A colour, which will come as a response to [`crate::Question::Color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// The red component, from 0 to 255.
+    pub red: u8,
+    /// The green component, from 0 to 255.
+    pub green: u8,
+    /// The blue component, from 0 to 255.
+    pub blue: u8,
+}
+impl Color {
+    /// Parses a colour from hex like `#ff8800` or `#f80` (the `#` is optional), or from RGB like
+    /// `rgb(255, 136, 0)`, ignoring case and surrounding whitespace. This returns `None` if the
+    /// text isn't a colour in one of these formats.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+        if let Some(components) = text
+            .strip_prefix("rgb(")
+            .and_then(|text| text.strip_suffix(')'))
+        {
+            let components = components
+                .split(',')
+                .map(|component| component.trim().parse::<u8>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            let [red, green, blue] = components[..] else {
+                return None;
+            };
+            return Some(Self { red, green, blue });
+        }
+
+        let hex = text.strip_prefix('#').unwrap_or(&text);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |digits: &str| u8::from_str_radix(digits, 16).ok();
+        match hex.len() {
+            // Each digit is doubled in short hex (e.g. `f80` is `ff8800`)
+            3 => Some(Self {
+                red: component(&hex[0..1].repeat(2))?,
+                green: component(&hex[1..2].repeat(2))?,
+                blue: component(&hex[2..3].repeat(2))?,
+            }),
+            6 => Some(Self {
+                red: component(&hex[0..2])?,
+                green: component(&hex[2..4])?,
+                blue: component(&hex[4..6])?,
+            }),
+            _ => None,
+        }
+    }
+}
+// These are serialized as hex, just like they're given to scripts
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).ok_or_else(|| D::Error::custom(format!("invalid colour '{text}'")))
+    }
+}
+/// Colours are displayed as lower-case hex, like `#ff8800`.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
@@ -0,0 +1,18 @@
+use crate::PromptFormat;
+use serde::{Deserialize, Serialize};
+
+/// The properties every type of [`crate::Question`] has, other than its prompt. Scripts give these
+/// the same way for every type of question.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct QuestionCommon {
+    /// Arbitrary tags the script gave the question (see [`crate::Question::tags`]).
+    pub tags: Vec<String>,
+    /// Whether or not answers to the question are locked once given (see
+    /// [`crate::Question::is_readonly`]).
+    pub readonly: bool,
+    /// Secondary text explaining the question, which interfaces should show under the prompt (see
+    /// [`crate::Question::help`]).
+    pub help: Option<String>,
+    /// How the prompt is written (see [`crate::Question::format`]).
+    pub format: PromptFormat,
+}
@@ -0,0 +1,103 @@
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// An exact decimal number, like an amount of money, which will come as a response to
+/// [`crate::Question::Decimal`]. This is stored as a whole number of units at some scale (e.g.
+/// `12.50` is 1250 units at a scale of 2), so, unlike a float, it never loses cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    /// The number with its decimal point removed.
+    units: i128,
+    /// How many of the digits of `units` come after the decimal point.
+    scale: u32,
+}
+impl Decimal {
+    /// Creates a new decimal of the given number of units at the given scale (e.g. `new(1250, 2)`
+    /// is `12.50`).
+    pub fn new(units: i128, scale: u32) -> Self {
+        Self { units, scale }
+    }
+    /// Gets the number with its decimal point removed (e.g. 1250 for `12.50`). For amounts of
+    /// money at the usual precision, this is the number of cents (or equivalent).
+    pub fn units(&self) -> i128 {
+        self.units
+    }
+    /// Gets how many digits come after the decimal point.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+    /// Parses a decimal from text like `12.5`, `-0.05`, or `1,234.50` (commas are ignored, so users
+    /// can group digits). The scale will be the number of digits given after the decimal point.
+    /// This returns `None` if the text isn't a number, or the number is too large.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().replace(',', "");
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let mut units: i128 = 0;
+        for digit in whole.chars().chain(fraction.chars()) {
+            units = units
+                .checked_mul(10)?
+                .checked_add(digit.to_digit(10)? as i128)?;
+        }
+        Some(Self {
+            units: if negative { -units } else { units },
+            scale: fraction.len().try_into().ok()?,
+        })
+    }
+    /// Converts this decimal to the given scale, adding zeros after the decimal point or removing
+    /// them. This returns `None` if that would change the number (i.e. there are more significant
+    /// digits after the decimal point than the scale allows), or it would be too large.
+    pub fn with_scale(&self, scale: u32) -> Option<Self> {
+        let units = if scale >= self.scale {
+            self.units
+                .checked_mul(10_i128.checked_pow(scale - self.scale)?)?
+        } else {
+            let divisor = 10_i128.checked_pow(self.scale - scale)?;
+            if self.units % divisor != 0 {
+                return None;
+            }
+            self.units / divisor
+        };
+
+        Some(Self { units, scale })
+    }
+}
+// These are serialized as text, because JSON numbers can't hold every `i128` exactly (and would
+// lose trailing zeros)
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).ok_or_else(|| D::Error::custom(format!("invalid decimal '{text}'")))
+    }
+}
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.units < 0 { "-" } else { "" };
+        let digits = self.units.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        // Make sure there's at least one digit before the decimal point
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{whole}.{fraction}")
+    }
+}
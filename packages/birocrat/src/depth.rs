@@ -0,0 +1,51 @@
+use crate::error::Error;
+use mlua::{Table, Value as LuaValue};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// The deepest values from a script (like its internal state, or the object it completes with) can
+/// be nested. Converting these to JSON recurses, so, without a limit, a deeply nested value from a
+/// buggy or malicious script could overflow the stack and crash the host. This is the same limit
+/// `serde_json` uses when parsing.
+pub const MAX_VALUE_DEPTH: usize = 128;
+
+/// Checks that the given value from a script is nested no more than [`MAX_VALUE_DEPTH`] levels
+/// deep. This doesn't recurse, so it's safe to call on any value, including tables that contain
+/// themselves (which will be treated as infinitely deep).
+pub(crate) fn check_depth(value: &LuaValue) -> Result<(), Error> {
+    let mut stack: Vec<(Table, usize)> = Vec::new();
+    if let LuaValue::Table(table) = value {
+        stack.push((table.clone(), 1));
+    }
+    // Tables can be shared between several places in a value, so we keep track of the deepest
+    // we've seen each one, and only look inside one again if we've found a deeper path to it
+    // (without this, a value that shares tables cleverly could take exponential time to check)
+    let mut deepest_seen = HashMap::new();
+
+    while let Some((table, depth)) = stack.pop() {
+        if depth > MAX_VALUE_DEPTH {
+            return Err(Error::ValueTooDeep {
+                max: MAX_VALUE_DEPTH,
+            });
+        }
+        match deepest_seen.entry(table.to_pointer()) {
+            Entry::Occupied(entry) if *entry.get() >= depth => continue,
+            Entry::Occupied(mut entry) => {
+                entry.insert(depth);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(depth);
+            }
+        }
+
+        for pair in table.pairs::<LuaValue, LuaValue>() {
+            let (key, value) = pair.map_err(|err| Error::InspectValueFailed { source: err })?;
+            for inner in [key, value] {
+                if let LuaValue::Table(inner) = inner {
+                    stack.push((inner, depth + 1));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
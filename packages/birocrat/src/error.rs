@@ -3,6 +3,11 @@ use thiserror::Error;
 /// Errors that can occur while operating a form.
 #[derive(Error, Debug)]
 pub enum Error {
+    #[error("failed to create environment for lua script in vm")]
+    CreateEnvironmentFailed {
+        #[source]
+        source: mlua::Error,
+    },
     #[error("failed to load lua script to drive form operation")]
     ScriptLoadFailed {
         #[source]
@@ -13,23 +18,70 @@ pub enum Error {
         #[source]
         source: mlua::Error,
     },
+    #[error("found invalid `Review` declared by driver script (expected function)")]
+    InvalidReviewFunction {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid `Suggest` declared by driver script (expected function or table)")]
+    InvalidSuggest {
+        #[source]
+        source: mlua::Error,
+    },
     #[error("failed to run driver function")]
     RunDriverFailed {
         #[source]
         source: mlua::Error,
     },
+    #[error("failed to run review function (it should return `nil` or a table of error messages keyed by question id)")]
+    RunReviewFailed {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("failed to run suggest function (it should return `nil` or an array of strings)")]
+    RunSuggestFailed {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid suggestions declared by driver script (expected array of strings)")]
+    InvalidSuggestions {
+        #[source]
+        source: mlua::Error,
+    },
 
     #[error("received invalid return value from driver script (expected array with status string and data)")]
     InvalidResult,
     #[error("found invalid state from driver function (expected `question`, `error`, or `done`)")]
     InvalidState { value: String },
-    #[error("failed to serialize intermediate driver script state")]
+    #[error(
+        "found value from driver script nested more than {max} levels deep (or containing itself)"
+    )]
+    ValueTooDeep { max: usize },
+    #[error("failed to inspect value from driver script")]
+    InspectValueFailed {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("failed to load intermediate driver script state back into lua vm")]
+    DeserializeStateFailed {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("failed to serialize intermediate driver script state (at `{path}`)")]
     SerializeStateFailed {
+        path: String,
         #[source]
         source: serde_json::Error,
     },
-    #[error("failed to serialize answers from completed driver script")]
+    #[error("failed to serialize answers from completed driver script (at `{path}`)")]
     SerializeAnswersFailed {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("failed to serialize value of computed-type question from script (at `{path}`)")]
+    SerializeComputedValueFailed {
+        path: String,
         #[source]
         source: serde_json::Error,
     },
@@ -54,10 +106,248 @@ pub enum Error {
     },
     #[error("received invalid question type from driver script: '{ty}'")]
     InvalidQuestionType { ty: String },
-    #[error("found invalid non-boolean value for property `multiple` in select-type question")]
+    #[error("found invalid value for property `tags` in question data from script (expected array of strings)")]
+    InvalidTagsProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid value for property `depends_on` in question data from script (expected array of question ids)")]
+    InvalidDependsOnProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error(
+        "found invalid non-boolean value for property `readonly` in question data from script"
+    )]
+    InvalidReadonlyProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error(
+        "found invalid non-boolean value for property `required` in question data from script"
+    )]
+    InvalidRequiredProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid non-string value for property `help` in question data from script")]
+    InvalidHelpProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid non-string value for property `format` in question data from script")]
+    InvalidFormatProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("unknown prompt format '{format}' in question data from script (expected 'plain' or 'markdown')")]
+    UnknownPromptFormat { format: String },
+    #[error(
+        "found invalid value for property `{property}` in date-type question (expected string)"
+    )]
+    InvalidDateProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid date '{value}' for property `{property}` in date-type question (expected format YYYY-MM-DD)")]
+    InvalidDate {
+        property: &'static str,
+        value: String,
+    },
+    #[error(
+        "found invalid value for property `{property}` in time-type question (expected string)"
+    )]
+    InvalidTimeProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid time '{value}' for property `{property}` in time-type question (expected format HH:MM or HH:MM:SS)")]
+    InvalidTime {
+        property: &'static str,
+        value: String,
+    },
+    #[error(
+        "found invalid value for property `{property}` in datetime-type question (expected string)"
+    )]
+    InvalidDateTimeProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid date and time '{value}' for property `{property}` in datetime-type question (expected RFC 3339 or YYYY-MM-DD HH:MM)")]
+    InvalidDateTime {
+        property: &'static str,
+        value: String,
+    },
+    #[error("found invalid timezone '{value}' in datetime-type question (expected an offset like +10:00)")]
+    InvalidTimezone { value: String },
+    #[error("found invalid non-boolean value for property `seconds` in time-type question")]
+    InvalidSecondsProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error(
+        "found invalid value for property `{property}` in range-type question (expected number)"
+    )]
+    InvalidRangeProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("range-type question must have both `min` and `max` properties")]
+    MissingRangeBounds,
+    #[error("found invalid bounds in range-type question (minimum {min} must be less than maximum {max}, both must be finite, and step {step:?} must be positive and finite)")]
+    InvalidRangeBounds {
+        min: f64,
+        max: f64,
+        step: Option<f64>,
+    },
+    #[error("found invalid default '{value}' in range-type question (expected number within the question's range)")]
+    InvalidRangeDefault { value: String },
+    #[error("failed to parse `region` property of phone-type question (expected string)")]
+    InvalidRegionProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found unknown region '{region}' in phone-type question (expected a two-letter country code like 'GB')")]
+    UnknownPhoneRegion { region: String },
+    #[error("found invalid default '{value}' in phone-type question (expected a phone number)")]
+    InvalidPhoneDefault { value: String },
+    #[error("found invalid default '{value}' in color-type question (expected hex or RGB color)")]
+    InvalidColorDefault { value: String },
+    #[error("found invalid value for property `{property}` in decimal-type question")]
+    InvalidDecimalProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid precision {precision} in decimal-type question (expected at most 18)")]
+    InvalidDecimalPrecision { precision: u32 },
+    #[error("found invalid currency '{currency}' in decimal-type question (expected three-letter ISO 4217 code)")]
+    InvalidCurrency { currency: String },
+    #[error("found invalid value '{value}' for property `{property}` in decimal-type question (expected number with no more decimal places than the question's precision)")]
+    InvalidDecimalValue {
+        property: &'static str,
+        value: String,
+    },
+    #[error("found invalid bounds in decimal-type question (minimum {min} must be no more than maximum {max})")]
+    InvalidDecimalRange { min: String, max: String },
+    #[error("found invalid value for property `labels` in likert-type question (expected array of strings)")]
+    InvalidLikertLabels {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found likert-type question with {count} labels (expected at least two)")]
+    TooFewLikertLabels { count: usize },
+    #[error("found invalid default '{value}' in likert-type question (expected one of the question's labels, or a point on its scale)")]
+    InvalidLikertDefault { value: String },
+    #[error("found invalid value for property `{property}` in rating-type question")]
+    InvalidRatingProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid range {min} to {max} in rating-type question (minimum must not be greater than maximum)")]
+    InvalidRatingRange { min: u32, max: u32 },
+    #[error("found invalid default '{value}' in rating-type question (expected whole number within the question's range)")]
+    InvalidRatingDefault { value: String },
+    #[error("found no, or failed to parse, fields in group-type question data from script")]
+    InvalidGroupFields {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid value for property `{property}` in group-type question (expected whole number)")]
+    InvalidGroupProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid bounds {min} to {max} in group-type question (minimum must not be greater than maximum)")]
+    InvalidGroupRange { min: usize, max: usize },
+    #[error("found no, or failed to parse, questions in page-type question data from script")]
+    InvalidPageQuestions {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found page-type question with no questions on it")]
+    EmptyPage,
+    #[error("found more than one question with id '{id}' on the same page")]
+    DuplicatePageQuestionId { id: String },
+    #[error("found page-type question on a page (pages can't be nested)")]
+    NestedPage,
+    #[error("found computed-type question on a page (computed steps must be on their own)")]
+    ComputedOnPage,
+    #[error(
+        "found invalid non-table value for property `followup` in select question data from script"
+    )]
+    InvalidFollowUpProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("expected array of option values for property `when` in follow-up question data from script")]
+    InvalidFollowUpWhen {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("follow-up in question data from script is brought up by '{value}', which is not one of the question's options")]
+    FollowUpWhenNotInOptions { value: String },
+    #[error("follow-up questions cannot be pages, information steps, computed steps, or select questions with their own follow-ups")]
+    InvalidFollowUpType,
+    #[error("found invalid value for property `{property}` in simple-type or multiline-type question (expected string)")]
+    InvalidPatternProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid value for property `{property}` in simple-type or multiline-type question (expected non-negative integer)")]
+    InvalidLengthProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid lengths {min} to {max} in simple-type or multiline-type question (minimum must not be greater than maximum)")]
+    InvalidLengthRange { min: usize, max: usize },
+    #[error("found invalid regular expression '{pattern}' in property `pattern` of question")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error("found invalid value for property `schemes` in url-type question (expected array of strings)")]
+    InvalidSchemesProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid value for property `extensions` in file-type question (expected array of strings)")]
+    InvalidExtensionsProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid value for property `{property}` in select-type question (expected non-negative integer)")]
+    InvalidSelectionCountProperty {
+        property: &'static str,
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid selection counts {min} to {max} in select-type question (minimum must not be greater than maximum)")]
+    InvalidSelectionCountRange { min: usize, max: usize },
+    #[error("found invalid non-boolean value for property `searchable` in select-type question")]
+    InvalidSearchableProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid non-boolean value for property `allow_other` in select-type question")]
+    InvalidAllowOtherProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("found invalid non-boolean value for property `multiple` in select-type or file-type question")]
     InvalidMultipleProperty,
     #[error(
-        "found no, or failed to parse, answer options in select-type question data from script"
+        "found no, or failed to parse, answer options in select- or rank-type question data from \
+        script"
     )]
     NoOptionsInQuestionData {
         #[source]
@@ -67,6 +357,35 @@ pub enum Error {
     FirstPollFailed { script_err: String },
     #[error("first poll of driver script completed form without asking a question")]
     FirstPollDone,
+    #[error("failed to serialize form state for saving")]
+    SerializeSavedStateFailed {
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error(
+        "saved form state is in an unsupported format (version {version}, expected {expected})"
+    )]
+    UnsupportedStateVersion { version: u64, expected: u64 },
+    #[error("failed to deserialize saved form state")]
+    DeserializeSavedStateFailed {
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("attempted to get first question when form has already been progressed")]
+    FormAlreadyProgressed,
+    #[error("gave up on question '{question_id}' after {errors} errors in a row")]
+    TooManyErrors { question_id: String, errors: usize },
+    #[error("attempted to answer a question in a form opened read-only")]
+    FormReadOnly,
+    #[error("attempted to get suggestions for question '{question_id}', which hasn't been asked")]
+    QuestionNotAsked { question_id: String },
+    #[error("attempted to change answer to question '{question_id}', which is readonly")]
+    AnswerLocked { question_id: String },
+    #[error("driver script rejected its own value for computed step '{question_id}': '{message}'")]
+    ComputedValueRejected {
+        question_id: String,
+        message: String,
+    },
     #[error("failed to allocate space in lua vm for table to hold answer")]
     AllocateAnswerTableFailed {
         #[source]
@@ -79,6 +398,42 @@ pub enum Error {
         #[source]
         source: mlua::Error,
     },
+    #[error("failed to parse `Meta` table declared by driver script")]
+    InvalidMeta {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("failed to parse `options_from` property of select-type question (expected string)")]
+    InvalidOptionsFromProperty {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error("select-type question had both `options` and `options_from`, only one is allowed")]
+    OptionsAndOptionsFrom,
+    #[error("invalid `options_from` path '{path}' (expected something like `param.countries`)")]
+    InvalidOptionsFromPath { path: String },
+    #[error("found nothing at `options_from` path '{path}' in form parameters")]
+    OptionsFromNotFound { path: String },
+    #[error("value at `options_from` path '{path}' in form parameters is not an array of strings")]
+    OptionsFromNotStrings { path: String },
     #[error("default suggested answer for select-type question not in provided options list")]
     DefaultNotInOptions { default: String },
+    #[error("failed to parse list of defaults for multiple select-type question")]
+    InvalidSelectDefaults {
+        #[source]
+        source: mlua::Error,
+    },
+    #[error(
+        "found list of defaults for select-type question that doesn't allow multiple selections"
+    )]
+    MultipleDefaultsForSingleSelect,
+    #[cfg(feature = "receipts")]
+    #[error("receipt signature is invalid, or the receipt has been tampered with")]
+    InvalidReceiptSignature {
+        #[source]
+        source: ed25519_dalek::SignatureError,
+    },
+    #[cfg(feature = "receipts")]
+    #[error("receipt is not for the given transcript")]
+    ReceiptTranscriptMismatch,
 }
@@ -0,0 +1,22 @@
+use crate::Question;
+use serde::{Deserialize, Serialize};
+
+/// A question asked along with a [`Question::Select`] when the user chooses certain options (e.g.
+/// "Please specify" after "Other"), which is answered in the same step, without another call to
+/// the script. Scripts give these just like a question on its own (with a type, some text, and so
+/// on), plus the options that bring them up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    /// The values of the options that bring up this question if the user chooses any of them.
+    pub when: Vec<String>,
+    /// The question itself, which can be any type other than a page, an information step, or a
+    /// select question with its own follow-up.
+    pub question: Box<Question>,
+}
+impl FollowUp {
+    /// Checks whether or not this question should be asked when the user has chosen the given
+    /// option values.
+    pub fn is_asked(&self, selected: &[String]) -> bool {
+        selected.iter().any(|value| self.when.contains(value))
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// How the prompt of a question is written, so interfaces know how to display it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PromptFormat {
+    /// Plain text, which should be shown exactly as it is.
+    #[default]
+    Plain,
+    /// Markdown, which interfaces should render (e.g. as HTML in the browser, or with basic
+    /// styling in a terminal). Interfaces that can't render it should still show it as it is,
+    /// since markdown is meant to read well as plain text.
+    Markdown,
+}
+impl PromptFormat {
+    /// Gets the format with the given name, as scripts give it in the `format` property of a
+    /// question.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(Self::Plain),
+            "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
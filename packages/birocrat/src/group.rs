@@ -0,0 +1,36 @@
+use mlua::{FromLua, Lua, Value as LuaValue};
+use serde::{Deserialize, Serialize};
+
+/// One of the fields in each entry of a [`crate::Question::Group`]. Scripts give these as tables
+/// like `{ id = "name", text = "Name", required = false, pattern = "..." }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupField {
+    /// The key of this field in each entry of an answer.
+    pub id: String,
+    /// The prompt for the field.
+    pub prompt: String,
+    /// Whether or not this field must be filled in for every entry (this defaults to `true`).
+    pub required: bool,
+    /// A regular expression that values of this field must match in full, if they're given.
+    pub pattern: Option<String>,
+    /// A message to show the user if a value of this field doesn't match the pattern.
+    pub pattern_hint: Option<String>,
+}
+impl<'lua> FromLua<'lua> for GroupField {
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> mlua::Result<Self> {
+        let LuaValue::Table(table) = value else {
+            return Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "GroupField",
+                message: Some("expected table".to_string()),
+            });
+        };
+        Ok(Self {
+            id: table.get("id")?,
+            prompt: table.get("text")?,
+            required: table.get::<_, Option<bool>>("required")?.unwrap_or(true),
+            pattern: table.get("pattern")?,
+            pattern_hint: table.get("pattern_hint")?,
+        })
+    }
+}
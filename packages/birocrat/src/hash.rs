@@ -0,0 +1,137 @@
+use crate::{Answer, Transcript};
+use chrono::SecondsFormat;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The version of the encoding hashes are computed over. This goes into every hash, so, if the
+/// encoding ever has to change, hashes from before and after the change can never collide.
+const HASH_VERSION: &str = "birocrat-hash-v1";
+
+/// Computes a stable hash of a form definition, i.e. a script and the parameters it's run with, as
+/// a lowercase hexadecimal SHA-256 digest. This can be stored alongside a form's output to check
+/// later that it came from a particular version of a form.
+///
+/// The parameters are hashed by their content, so the order of keys in objects doesn't matter,
+/// but the script is hashed exactly as given (even changes to whitespace will change the hash).
+pub fn form_hash(script: &str, parameters: &Value) -> String {
+    hash(&json!([HASH_VERSION, "form", script, parameters]))
+}
+
+impl Transcript {
+    /// Computes a stable hash of this transcript, as a lowercase hexadecimal SHA-256 digest. This
+    /// covers the ID of each question, the answer to it, and the locale it was given in (but not
+    /// the questions themselves, which are covered by [`form_hash`]), in the order they were
+    /// asked, so two submissions with the same answers will have the same hash. Answers are
+    /// hashed by their meaning, so, for example, dates are hashed the same way no matter how the
+    /// user typed them.
+    ///
+    /// Secret answers are included as they are, so callers that don't want them to affect the
+    /// hash should hash a redacted transcript instead (see [`crate::Form::redact`]).
+    pub fn hash(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| json!([entry.id, canonical_answer(&entry.answer), entry.locale]))
+            .collect::<Vec<_>>();
+        hash(&json!([HASH_VERSION, "transcript", entries]))
+    }
+}
+
+/// Converts the given answer into JSON, in the same shape as the tables scripts are given (see
+/// [`Answer::to_lua`]).
+fn canonical_answer(answer: &Answer) -> Value {
+    match answer {
+        Answer::Text(text) => json!({ "type": "text", "text": text }),
+        Answer::Secret(secret) => json!({ "type": "text", "text": secret.expose() }),
+        Answer::Options(selected) => json!({ "type": "options", "selected": selected }),
+        Answer::OptionsWithText(selected) => json!({
+            "type": "options",
+            "selected": selected.iter().map(|(option, _)| option).collect::<Vec<_>>(),
+            "details": selected
+                .iter()
+                .filter_map(|(option, text)| Some((option, text.as_ref()?)))
+                .collect::<BTreeMap<_, _>>(),
+        }),
+        Answer::Date(date) => json!({ "type": "date", "date": date.to_string() }),
+        Answer::Time(time) => {
+            json!({ "type": "time", "time": time.format("%H:%M:%S").to_string() })
+        }
+        Answer::DateTime(datetime) => json!({
+            "type": "datetime",
+            "datetime": datetime.to_rfc3339_opts(SecondsFormat::Secs, false),
+        }),
+        Answer::Files(paths) => json!({
+            "type": "files",
+            "files": paths.iter().map(|path| path.to_string_lossy()).collect::<Vec<_>>(),
+        }),
+        Answer::Rating(rating) => json!({ "type": "rating", "rating": rating }),
+        Answer::Range(value) => json!({ "type": "range", "value": value }),
+        Answer::Color(color) => json!({ "type": "color", "color": color.to_string() }),
+        Answer::Decimal(decimal) => json!({ "type": "decimal", "decimal": decimal.to_string() }),
+        Answer::Group(entries) => json!({ "type": "group", "entries": entries }),
+        Answer::Acknowledged => json!({ "type": "acknowledged" }),
+        Answer::Computed(value) => json!({ "type": "computed", "value": value }),
+        Answer::Skipped => json!({ "type": "skipped" }),
+        // These look like the selection, with the follow-up's answer alongside
+        Answer::WithFollowUp { answer, followup } => {
+            let mut canonical = canonical_answer(answer);
+            canonical["followup"] = canonical_answer(followup);
+            canonical
+        }
+        Answer::Page(answers) => json!({
+            "type": "page",
+            "answers": answers
+                .iter()
+                .map(|(id, answer)| (id.clone(), canonical_answer(answer)))
+                .collect::<serde_json::Map<_, _>>(),
+        }),
+    }
+}
+
+/// Hashes the canonical encoding of the given value.
+fn hash(value: &Value) -> String {
+    let mut encoded = String::new();
+    encode(value, &mut encoded);
+    let digest = Sha256::digest(encoded.as_bytes());
+
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        write!(hex, "{byte:02x}").unwrap();
+    }
+    hex
+}
+
+/// Writes the given value as compact JSON with the keys of every object sorted, so the same value
+/// is always encoded the same way, no matter how it was built (`serde_json` only sorts keys when
+/// its `preserve_order` feature is off, which another crate could turn on).
+pub(crate) fn encode(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                encode(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                encode(value, out);
+            }
+            out.push('}');
+        }
+        // Everything else has only one compact encoding
+        _ => out.push_str(&value.to_string()),
+    }
+}
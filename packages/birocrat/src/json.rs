@@ -0,0 +1,154 @@
+use crate::Answer;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+impl Answer {
+    /// Converts this answer into the plain JSON format interfaces use to prefill and save answers,
+    /// which [`Self::from_json`] reads back. Text answers, secrets, dates, times, colours, and
+    /// decimals are strings (decimals being kept exact), selections and files are arrays of
+    /// strings (with `[option, details]` pairs for options that need details), ratings and ranges
+    /// are numbers, group entries are arrays of objects, acknowledged information steps are
+    /// `true`, skipped questions are `null`, computed steps are their values, answers to pages are
+    /// objects of these keyed by the IDs of the questions on them, and selections with follow-ups
+    /// are objects with the selection as `answer` and the follow-up's answer as `followup`.
+    ///
+    /// This will expose secrets, so callers should leave them out first if the JSON is going
+    /// anywhere they shouldn't be.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Answer::Text(text) => Value::String(text.clone()),
+            Answer::Secret(secret) => Value::String(secret.expose().to_string()),
+            Answer::Options(selected) => {
+                Value::Array(selected.iter().cloned().map(Value::String).collect())
+            }
+            Answer::OptionsWithText(selected) => Value::Array(
+                selected
+                    .iter()
+                    .map(|(option, text)| match text {
+                        Some(text) => json!([option, text]),
+                        None => Value::String(option.clone()),
+                    })
+                    .collect(),
+            ),
+            Answer::Files(paths) => Value::Array(
+                paths
+                    .iter()
+                    .map(|path| Value::String(path.to_string_lossy().to_string()))
+                    .collect(),
+            ),
+            Answer::Rating(rating) => Value::from(*rating),
+            Answer::Range(value) => Value::from(*value),
+            Answer::Color(color) => Value::String(color.to_string()),
+            // Strings keep these exact
+            Answer::Decimal(decimal) => Value::String(decimal.to_string()),
+            Answer::Group(entries) => Value::Array(
+                entries
+                    .iter()
+                    .map(|entry| {
+                        Value::Object(
+                            entry
+                                .iter()
+                                .map(|(id, value)| (id.clone(), Value::String(value.clone())))
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            ),
+            Answer::Date(date) => Value::String(date.to_string()),
+            Answer::Time(time) => Value::String(time.format("%H:%M:%S").to_string()),
+            Answer::DateTime(datetime) => Value::String(datetime.to_rfc3339()),
+            Answer::Acknowledged => Value::Bool(true),
+            Answer::Skipped => Value::Null,
+            Answer::Computed(value) => value.clone(),
+            Answer::WithFollowUp { answer, followup } => {
+                json!({ "answer": answer.to_json(), "followup": followup.to_json() })
+            }
+            Answer::Page(answers) => Value::Object(
+                answers
+                    .iter()
+                    .map(|(id, answer)| (id.clone(), answer.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+    /// Reads an answer from the format [`Self::to_json`] produces, returning `None` if the value
+    /// isn't in that format. Everything that isn't a selection, a group, a page, or a follow-up
+    /// comes back as [`Answer::Text`] (numbers included), which the form will parse according to
+    /// the question it answers. Computed steps can't be read back, because the form works those
+    /// out again itself.
+    pub fn from_json(value: Value) -> Option<Self> {
+        match value {
+            Value::String(text) => Some(Answer::Text(text)),
+            // The form will parse numbers for ratings and ranges
+            Value::Number(number) => Some(Answer::Text(number.to_string())),
+            // Information steps are acknowledged with `true`
+            Value::Bool(true) => Some(Answer::Acknowledged),
+            // Optional questions are skipped with `null`
+            Value::Null => Some(Answer::Skipped),
+            // Selections with follow-ups are the only objects with just these keys
+            Value::Object(mut answers)
+                if answers.len() == 2
+                    && answers.contains_key("answer")
+                    && answers.contains_key("followup") =>
+            {
+                // We've just checked these are there
+                let answer = Self::from_json(answers.remove("answer").unwrap())?;
+                let followup = Self::from_json(answers.remove("followup").unwrap())?;
+                Some(Answer::WithFollowUp {
+                    answer: Box::new(answer),
+                    followup: Box::new(followup),
+                })
+            }
+            // Other objects are answers to pages, keyed by the IDs of the questions on them
+            Value::Object(answers) => answers
+                .into_iter()
+                .map(|(id, answer)| Some((id, Self::from_json(answer)?)))
+                .collect::<Option<HashMap<_, _>>>()
+                .map(Answer::Page),
+            // Arrays of objects are entries for group questions (an empty array will be taken as
+            // an empty selection)
+            Value::Array(entries)
+                if !entries.is_empty() && entries.iter().all(Value::is_object) =>
+            {
+                entries
+                    .into_iter()
+                    .map(|entry| match entry {
+                        Value::Object(fields) => fields
+                            .into_iter()
+                            .map(|(id, value)| match value {
+                                Value::String(value) => Some((id, value)),
+                                _ => None,
+                            })
+                            .collect::<Option<HashMap<_, _>>>(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(Answer::Group)
+            }
+            // Options that need details are given as `[option, details]` pairs
+            Value::Array(selected) => {
+                let selected = selected
+                    .into_iter()
+                    .map(|option| match option {
+                        Value::String(option) => Some((option, None)),
+                        Value::Array(pair) => match <[Value; 2]>::try_from(pair).ok()? {
+                            [Value::String(option), Value::String(text)] => {
+                                Some((option, Some(text)))
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                if selected.iter().any(|(_, text)| text.is_some()) {
+                    Some(Answer::OptionsWithText(selected))
+                } else {
+                    Some(Answer::Options(
+                        selected.into_iter().map(|(option, _)| option).collect(),
+                    ))
+                }
+            }
+            _ => None,
+        }
+    }
+}
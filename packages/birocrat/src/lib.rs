@@ -1,41 +1,115 @@
+mod color;
+mod common;
+mod decimal;
+mod depth;
 pub mod error;
+mod followup;
+mod format;
+mod group;
+mod hash;
+mod json;
+mod likert;
+mod meta;
+mod metrics;
+mod options;
+mod owned;
+mod page;
+mod phone;
+mod prefetch;
+mod random;
+#[cfg(feature = "receipts")]
+mod receipt;
+mod resume;
+mod script;
+mod secret;
+mod select;
+mod store;
+mod suggest;
+mod transcript;
+mod warning;
 
-use crate::error::Error;
+pub use color::Color;
+pub use common::QuestionCommon;
+pub use decimal::Decimal;
+pub use depth::MAX_VALUE_DEPTH;
+pub use followup::FollowUp;
+pub use format::PromptFormat;
+pub use group::GroupField;
+pub use hash::form_hash;
+pub use meta::{FormMeta, ParamMeta};
+pub use metrics::{Metrics, StepMetrics};
+pub use options::{fold_case, fold_case_and_whitespace, FormOptions};
+pub use owned::OwnedForm;
+pub use page::PageQuestion;
+pub use prefetch::mark_impure;
+#[cfg(feature = "receipts")]
+pub use receipt::Receipt;
+pub use script::Script;
+pub use secret::SecretText;
+pub use select::SelectOption;
+pub use store::{AnswerStore, HistoryEntry, MemoryStore};
+pub use transcript::{Transcript, TranscriptEntry, REDACTED, SECRET_TAG};
+pub use warning::Warning;
+
+use crate::{
+    depth::check_depth,
+    error::Error,
+    likert::{likert_point, DEFAULT_LIKERT_LABELS},
+    phone::normalize_phone,
+    prefetch::{Outcome, Prefetched, Prefetching},
+    random::ScriptApi,
+    suggest::Suggester,
+    transcript::DebugAnswer,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Timelike};
 use mlua::{Function, Lua, LuaSerdeExt, Table, Value as LuaValue};
-use serde::Serialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf, time::Instant};
+use url::Url;
 
 /// A form created and operated by Birocrat. This follows the engine pattern, whereby this may be
 /// used to "drive" an interface of any type.
-#[derive(Debug)]
-pub struct Form<'l> {
-    /// Answers to questions that have been presented at some stage. These are useless unless the
-    /// user goes back to change their answer to a previous question, in which case all later
-    /// question/answer states will be clobbered. As all questions have unique IDs, if the same
-    /// question is later asked, we can put up the same answer to the refiling program for
-    /// convenience, without having to manage multiple conflicting states of what the script might
-    /// have looked like in the past before the clobbering.
-    cached_answers: HashMap<String, Answer>,
-    /// The Lua virtual machine which stores the script driving this form. This is held by
-    /// reference and must be provided externally.
-    lua_vm: &'l Lua,
-    /// The main function in the Lua script that drives the form creation and operation.
-    driver_function: Function<'l>,
-    /// The state of the script at every stage, along with the question is was asking and the
-    /// internal ID of that question. This allows us to return to a previous state of the script
-    /// to, say, submit a different answer to a question previously asked.
+///
+/// The answers the form is given and the history of questions it asks are kept in an
+/// [`AnswerStore`], which is in memory by default.
+///
+/// The `Debug` output of a form only includes a redacted transcript of its answers (see
+/// [`Form::redact`]), so forms can be logged safely.
+pub struct Form<'l, S: AnswerStore = MemoryStore> {
+    /// Answers to questions that have been presented at some stage, and the state of the script at
+    /// every stage, along with the question it was asking and the internal ID of that question.
     ///
-    /// The indices in this array are used to index questions in the order which they were asked,
-    /// while the `String`s stored in each element are script-provided unique question identifiers.
-    /// These should not be confused!
+    /// Cached answers are useless unless the user goes back to change their answer to a previous
+    /// question, in which case all later question/answer states will be clobbered. As all
+    /// questions have unique IDs, if the same question is later asked, we can put up the same
+    /// answer to the refiling program for convenience, without having to manage multiple
+    /// conflicting states of what the script might have looked like in the past before the
+    /// clobbering.
+    ///
+    /// The history allows us to return to a previous state of the script to, say, submit a
+    /// different answer to a question previously asked. The indices in it are used to index
+    /// questions in the order which they were asked, while the IDs stored in each entry are
+    /// script-provided unique question identifiers. These should not be confused!
     ///
     /// Note that driver script states are stored as serialized values because otherwise Lua will
     /// be a little too efficient and override the values from under our noses when we call the
     /// driver script again! (I.e. they will all point to the same value in the VM.)
-    script_states: Vec<(String, Question, Value)>,
-    /// The state of the script in the next case. For all the states in `script_states`, there are
-    /// corresponding answers in `cached_answers`, while this state is the question which has not
+    store: S,
+    /// The Lua virtual machine which stores the script driving this form. This is held by
+    /// reference and must be provided externally.
+    lua_vm: &'l Lua,
+    /// The main function in the Lua script that drives the form creation and operation.
+    driver_function: Function<'l>,
+    /// The function in the Lua script that reviews all the user's answers together before the
+    /// form is completed, if the script has one.
+    review_function: Option<Function<'l>>,
+    /// Where the script's suggestions for answers come from, if it has any (see
+    /// [`Self::suggest`]).
+    suggester: Option<Suggester<'l>>,
+    /// The state of the script in the next case. For all the states in the history, there are
+    /// corresponding cached answers, while this state is the question which has not
     /// yet been answered. Alternately, it might be a completion state. By populating this for the
     /// next question whenever we're given the answer to another question, we can recreate the
     /// state list when a previous answer is changed and also determine if an error would occur and
@@ -46,16 +120,62 @@ pub struct Form<'l> {
     ///
     /// These are stored as a reference to a serialized object in the Lua VM.
     parameters: LuaValue<'l>,
+    /// The settings this form was created with.
+    options: FormOptions,
+    /// Mistakes in the script that have been worked around so far, because the form is in lenient
+    /// mode. Each of these is only recorded once, no matter how many times it happens.
+    warnings: Vec<Warning>,
+    /// The locale the user is currently answering in, if the host has set one. Each answer is
+    /// stored along with the locale it was given in.
+    locale: Option<String>,
+    /// The seed everything random in this form is derived from (see [`FormOptions::seed`]).
+    seed: u64,
+    /// The `birocrat` table the script is given, which is put in its environment before every
+    /// call.
+    script_api: ScriptApi<'l>,
+    /// The index of the last question the user answered, and how many errors in a row answering
+    /// it has produced, if the last answer produced an error.
+    error_streak: Option<(usize, usize)>,
+    /// The index of the question the user is currently on, which is the one the form last
+    /// returned from [`Self::progress_with_answer`] or [`Self::back`]. If the user is on the next
+    /// question (or the form is done), this is the same as [`Self::next_index`].
+    position: usize,
+    /// The results of calling the script ahead of time with possible answers to the question in
+    /// `next_state` (see [`Self::prefetch`]). These are cleared whenever `next_state` changes.
+    prefetched: Vec<Prefetched>,
+}
+impl<S: AnswerStore> std::fmt::Debug for Form<'_, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The store, script states, and output could all contain answers, so we leave them out
+        f.debug_struct("Form")
+            .field("answered", &self.redact().entries)
+            .field("next_question_id", &self.next_question_id())
+            .field("options", &self.options)
+            .field("warnings", &self.warnings)
+            .field("locale", &self.locale)
+            .finish_non_exhaustive()
+    }
 }
 impl<'l> Form<'l> {
-    /// Creates a new form from the given Lua script. All this does is loads the script.
+    /// Creates a new form from the given Lua script. All this does is loads the script (in an
+    /// environment of its own, so several forms can safely share the same VM; see [`Script`]) and
+    /// polls it for the first question.
     pub fn new<P: Serialize>(script: &str, parameters: P, lua_vm: &'l Lua) -> Result<Self, Error> {
+        Self::new_with_options(script, parameters, lua_vm, FormOptions::default())
+    }
+    /// Same as [`Self::new`], but this allows customising how the form treats its script.
+    pub fn new_with_options<P: Serialize>(
+        script: &str,
+        parameters: P,
+        lua_vm: &'l Lua,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
         // Register the parameters in the Lua VM
         let parameters = lua_vm
             .to_value(&parameters)
             .map_err(|err| Error::SerializeFormParamsFailed { source: err })?;
 
-        Self::new_with_lua_params(script, parameters, lua_vm)
+        Self::new_with_lua_params_and_options(script, parameters, lua_vm, options)
     }
     /// Same as [`Self::new`], but this takes parameters allocated within the Lua VM. In some
     /// cases, this can be more flexible if serialization can be skipped, or if a heterogeneous
@@ -66,31 +186,134 @@ impl<'l> Form<'l> {
         parameters: LuaValue<'l>,
         lua_vm: &'l Lua,
     ) -> Result<Self, Error> {
-        lua_vm
-            .load(script)
-            .exec()
-            .map_err(|err| Error::ScriptLoadFailed { source: err })?;
-        let driver_function: Function = lua_vm
-            .globals()
-            .get("Main")
-            .map_err(|err| Error::NoMainFunction { source: err })?;
+        Self::new_with_lua_params_and_options(script, parameters, lua_vm, FormOptions::default())
+    }
+    /// Same as [`Self::new_with_lua_params`], but this allows customising how the form treats its
+    /// script.
+    pub fn new_with_lua_params_and_options(
+        script: &str,
+        parameters: LuaValue<'l>,
+        lua_vm: &'l Lua,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
+        Self::new_with_lua_params_and_store(
+            script,
+            parameters,
+            lua_vm,
+            options,
+            MemoryStore::default(),
+        )
+    }
+    /// Creates a new form from the given loaded script. Unlike [`Self::new`], this doesn't execute
+    /// the top level of the script again, so it's much faster for creating many forms from the
+    /// same script.
+    pub fn from_script<P: Serialize>(
+        script: &Script<'l>,
+        parameters: P,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
+        Self::from_script_with_store(script, parameters, options, MemoryStore::default())
+    }
+}
+impl<'l, S: AnswerStore> Form<'l, S> {
+    /// Same as [`Self::new_with_options`], but this keeps the form's answers and history in the
+    /// given store, rather than in memory. The store should be empty.
+    pub fn new_with_store<P: Serialize>(
+        script: &str,
+        parameters: P,
+        lua_vm: &'l Lua,
+        options: FormOptions,
+        store: S,
+    ) -> Result<Self, Error> {
+        let parameters = lua_vm
+            .to_value(&parameters)
+            .map_err(|err| Error::SerializeFormParamsFailed { source: err })?;
+
+        Self::new_with_lua_params_and_store(script, parameters, lua_vm, options, store)
+    }
+    /// Same as [`Self::from_script`], but this keeps the form's answers and history in the given
+    /// store, rather than in memory. The store should be empty.
+    pub fn from_script_with_store<P: Serialize>(
+        script: &Script<'l>,
+        parameters: P,
+        options: FormOptions,
+        store: S,
+    ) -> Result<Self, Error> {
+        let parameters = script
+            .lua_vm
+            .to_value(&parameters)
+            .map_err(|err| Error::SerializeFormParamsFailed { source: err })?;
+
+        Self::start(script, parameters, options, store)
+    }
+    /// Same as [`Self::new_with_lua_params_and_options`], but this keeps the form's answers and
+    /// history in the given store, rather than in memory. The store should be empty.
+    pub fn new_with_lua_params_and_store(
+        script: &str,
+        parameters: LuaValue<'l>,
+        lua_vm: &'l Lua,
+        options: FormOptions,
+        store: S,
+    ) -> Result<Self, Error> {
+        let script = Script::load(script, lua_vm)?;
+        Self::start(&script, parameters, options, store)
+    }
+    /// Starts a new form from the given loaded script, polling it for the first question.
+    fn start(
+        script: &Script<'l>,
+        parameters: LuaValue<'l>,
+        options: FormOptions,
+        store: S,
+    ) -> Result<Self, Error> {
+        let lua_vm = script.lua_vm;
+        let driver_function = script.driver_function.clone();
+        let review_function = script.review_function.clone();
+        let suggester = script.suggester.clone();
+        let seed = options.seed.unwrap_or_else(random::new_seed);
+        let script_api =
+            ScriptApi::new(lua_vm, script.env.clone(), seed, options.variants.clone())?;
 
         // Get the first state (manually, because we don't have a `self` yet and because we need to
         // pass `nil` values, which should otherwise be impossible)
-        let first_state = Self::call_driver_fn(lua_vm, &driver_function, parameters.clone(), None)?
-            .map_err(|err| Error::FirstPollFailed {
-                script_err: err.to_string(),
-            })?;
+        let mut warnings = Vec::new();
+        let first_state = Self::call_driver_fn(
+            lua_vm,
+            &driver_function,
+            &script_api,
+            parameters.clone(),
+            None,
+            &options,
+            &mut warnings,
+        )?
+        .map_err(|err| Error::FirstPollFailed {
+            script_err: err.to_string(),
+        })?;
 
         if let ScriptState::Asking { .. } = first_state.0 {
-            Ok(Self {
-                cached_answers: HashMap::new(),
+            let mut form = Self {
+                store,
                 lua_vm,
                 driver_function,
-                script_states: Vec::new(),
+                review_function,
+                suggester,
                 next_state: first_state,
                 parameters,
-            })
+                options,
+                warnings,
+                locale: None,
+                seed,
+                script_api,
+                error_streak: None,
+                position: 0,
+                prefetched: Vec::new(),
+            };
+            // If the script starts with computed steps, the first question is the one after them
+            form.compute()?;
+            if let ScriptState::Done(_) = form.next_state.0 {
+                return Err(Error::FirstPollDone);
+            }
+            form.position = form.next_index();
+            Ok(form)
         } else {
             // This isn't a form...
             Err(Error::FirstPollDone)
@@ -98,18 +321,28 @@ impl<'l> Form<'l> {
     }
     /// Gets the first question in the form. This should be called directly after [`Self::new`].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This will panic if it's called when any other questions have been asked or any answers
-    /// provided.
-    pub fn first_question(&self) -> &Question {
-        if !self.script_states.is_empty() || !self.cached_answers.is_empty() {
-            panic!("attempted to get first question when form has already been progressed")
+    /// This will return [`Error::FormAlreadyProgressed`] if it's called when any questions have
+    /// been answered (other than computed steps, which the form answers itself).
+    pub fn first_question(&self) -> Result<&Question, Error> {
+        let progressed = (0..self.store.history_len()).any(|idx| {
+            !matches!(
+                self.store.history_entry(idx),
+                Some(HistoryEntry {
+                    question: Question::Computed { .. },
+                    ..
+                })
+            )
+        });
+        if progressed {
+            return Err(Error::FormAlreadyProgressed);
         }
 
         match &self.next_state.0 {
-            ScriptState::Asking { question, .. } => question,
-            _ => unreachable!(),
+            ScriptState::Asking { question, .. } => Ok(question),
+            // We can only be done if a question has been answered
+            ScriptState::Done(_) => Err(Error::FormAlreadyProgressed),
         }
     }
 
@@ -120,13 +353,57 @@ impl<'l> Form<'l> {
     /// If there is no next question (i.e. the form is done), this will return `None`.
     pub fn next_question(&self) -> Option<(&Question, Option<&Answer>)> {
         match &self.next_state.0 {
-            ScriptState::Asking { question, id } => {
-                let answer = self.cached_answers.get(id);
+            ScriptState::Asking { question, id, .. } => {
+                let answer = self.store.answer(id);
                 Some((question, answer))
             }
             _ => None,
         }
     }
+    /// Gets the unique ID the script gave to the next question, if there is one. This is useful
+    /// for matching questions against answers provided from elsewhere (e.g. a file).
+    pub fn next_question_id(&self) -> Option<&str> {
+        match &self.next_state.0 {
+            ScriptState::Asking { id, .. } => Some(id),
+            _ => None,
+        }
+    }
+    /// Gets the index that should be passed to [`Self::progress_with_answer`] to answer the next
+    /// question. This is simply the number of questions that have been answered so far.
+    pub fn next_index(&self) -> usize {
+        self.store.history_len()
+    }
+    /// Gets the IDs of all questions that have been answered so far, in the order they were asked,
+    /// along with the questions themselves and their answers. Any answers cached from questions
+    /// that were later clobbered will not be included.
+    pub fn answers(&self) -> impl Iterator<Item = (&str, &Question, &Answer)> {
+        (0..self.store.history_len()).filter_map(|idx| {
+            let entry = self.store.history_entry(idx)?;
+            let answer = self.store.answer(&entry.id)?;
+            Some((entry.id.as_str(), &entry.question, answer))
+        })
+    }
+
+    /// Same as [`Self::answers`], but this only includes questions with the given tag. This is
+    /// useful for treating classes of questions differently when reviewing or exporting answers.
+    pub fn history_filtered<'a>(
+        &'a self,
+        tag: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Question, &'a Answer)> {
+        self.answers()
+            .filter(move |(_, question, _)| question.has_tag(tag))
+    }
+
+    /// Gets the store holding the form's answers and history.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+    /// Gets the mistakes in the script that have been worked around so far, in the order they were
+    /// first found. This will always be empty unless the form was created in lenient mode (see
+    /// [`FormOptions::lenient`]).
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
 
     /// Gets the question at the given index. This will return a cached answer as well if the user
     /// has answered this question before. This should be used exclusively for getting past
@@ -136,128 +413,525 @@ impl<'l> Form<'l> {
     /// This will never poll the driver script.
     // NOTE: The `idx` here is completely different from the internal question IDs!
     pub fn get_question(&mut self, idx: usize) -> Option<(&Question, Option<&Answer>)> {
-        let (id, question, _inner) = self.script_states.get(idx)?;
+        let entry = self.store.history_entry(idx)?;
         // See if there's a cached answer for this question (by its ID)
-        let answer = self.cached_answers.get(id);
-        Some((question, answer))
+        let answer = self.store.answer(&entry.id);
+        Some((&entry.question, answer))
     }
     /// Progresses the form by providing an answer for the question with the given index. If this
     /// is the latest question, which has not yet been answered, this will poll the Lua script for
-    /// the next question. However, if this provides an answer to a previous question (different
-    /// or not!), all subsequent questions the script has generated will be removed from the
-    /// internal state (e.g. if you have 10 questions, re-answer index 5, and then request index 7,
-    /// you'll get `None`), and the script will be polled for the new next question after the one
-    /// answered with this function. This may be the same question (in which case the answer will
-    /// be cached), but it may be completely different!
+    /// the next question. However, if this provides a different answer to a previous question,
+    /// all subsequent questions the script has generated will be removed from the internal state
+    /// (e.g. if you have 10 questions, re-answer index 5, and then request index 7, you'll get
+    /// `None`), and the script will be polled for the new next question after the one answered
+    /// with this function. This may be the same question (in which case the answer will be
+    /// cached), but it may be completely different!
+    ///
+    /// If the answer to a previous question is the same as the one it was given before (as
+    /// decided by [`FormOptions::answer_eq`]), nothing will be changed, and the question after it
+    /// will be returned.
     ///
     /// If the script returns an error (i.e. [`FormPoll::Error`]), no changes will be made to the
-    /// internal state of the form (i.e. no clobbering, no answer caching).
+    /// internal state of the form (i.e. no clobbering, no answer caching). The same goes for when
+    /// this answer would complete the form, but the script's `Review` function rejects some of the
+    /// user's answers (i.e. [`FormPoll::Rejected`]).
     ///
     /// Attempting to answer an out-of-range ID when the form has already been completed will
     /// short-circuit to return the script's completed object.
     ///
     /// This will return a hard `Err(_)` if the answer is of an incorrect type relative to the
     /// question (e.g. multiple options when only one was allowed, options when text was required).
+    ///
+    /// Some questions (like [`Question::Date`]) accept answers as text, which will be parsed and
+    /// validated before the script is polled. If this fails, [`FormPoll::Error`] will be returned
+    /// with a message for the user, as if the script had returned it.
+    ///
+    /// The answer will be given in the form's current locale (see [`Self::set_locale`]).
+    ///
+    /// If the form was created with [`FormOptions::max_errors`], and the same question has
+    /// produced more than that many [`FormPoll::Error`]s in a row, this will return
+    /// [`Error::TooManyErrors`] instead of the last one.
+    ///
+    /// If the form was opened with [`FormOptions::read_only`], this will always return
+    /// [`Error::FormReadOnly`], and the form won't be changed at all.
     pub fn progress_with_answer(
         &mut self,
         question_idx: usize,
         answer: Answer,
     ) -> Result<FormPoll<'_>, Error> {
-        // Get the script-internal state at whatever point in the question history we're at
-        let (question_id, question, inner_state, should_clobber) = if let Some((
-            question_id,
-            question,
-            inner_state,
-        )) =
-            self.script_states.get(question_idx)
-        {
-            (question_id, question, inner_state, true)
-        } else {
-            match &self.next_state {
-                // There's a question, we can use its details
-                (ScriptState::Asking { id, question }, inner_state) => {
-                    (id, question, inner_state, false)
-                }
-                // If we're already done, short-circuit
-                (ScriptState::Done(_), _) => return Ok(FormPoll::Done),
-            }
+        if self.options.read_only {
+            return Err(Error::FormReadOnly);
+        }
+        let progress = self.progress_in_locale(question_idx, answer, self.locale.clone())?;
+        self.position = match progress {
+            // The user should stay on the question they just answered (which will be the next
+            // question if they gave an index past it)
+            Progress::Error(_) | Progress::Rejected(_) => question_idx.min(self.next_index()),
+            Progress::At(idx) => idx,
+            Progress::Done => self.next_index(),
         };
-
-        // Check the answer
-        match question {
-            Question::Simple { .. } | Question::Multiline { .. } => {
-                if !matches!(answer, Answer::Text(_)) {
-                    return Err(Error::InvalidAnswerType {
-                        expected: "text for simple/multiline question",
-                    });
-                }
+        if let Progress::Error(_) = progress {
+            let errors = match self.error_streak {
+                Some((idx, errors)) if idx == question_idx => errors + 1,
+                _ => 1,
+            };
+            self.error_streak = Some((question_idx, errors));
+            if self.options.max_errors.is_some_and(|max| errors > max) {
+                // The count starts again in case the host lets the user keep trying
+                self.error_streak = None;
+                let question_id = match self.store.history_entry(question_idx) {
+                    Some(entry) => entry.id.clone(),
+                    // This must be the next question, because only questions can error
+                    None => self.next_question_id().unwrap_or_default().to_string(),
+                };
+                return Err(Error::TooManyErrors {
+                    question_id,
+                    errors,
+                });
             }
-            Question::Select {
-                options, multiple, ..
-            } => {
-                if let Answer::Options(ref selected) = answer {
-                    if !*multiple && selected.len() > 1 {
-                        return Err(Error::InvalidAnswerType {
-                            expected: "single option for non-multiple select question",
-                        });
-                    }
-                    if !selected.iter().all(|s| options.contains(s)) {
-                        return Err(Error::InvalidAnswerType {
-                            expected: "all options to be valid",
-                        });
+        } else {
+            self.error_streak = None;
+        }
+
+        Ok(match progress {
+            Progress::Error(err) => FormPoll::Error(err),
+            Progress::Rejected(field_errors) => FormPoll::Rejected(field_errors),
+            Progress::At(idx) => self.poll_at(idx),
+            // There's no question at the next index, so this will give the output
+            Progress::Done => self.poll_at(self.next_index()),
+        })
+    }
+    /// Moves the user back to the question before the one they're currently on (see
+    /// [`Self::current_index`]), returning it along with the answer they gave it. Computed steps
+    /// are skipped over, because the user never saw them. This can be used to implement a
+    /// "Previous" button, and the answer can then be changed by passing [`Self::current_index`] to
+    /// [`Self::progress_with_answer`] as usual.
+    ///
+    /// If the user is already on the first question, this will return `None`, and nothing will be
+    /// changed.
+    ///
+    /// This will never poll the driver script.
+    pub fn back(&mut self) -> Option<(&Question, Option<&Answer>)> {
+        let idx = (0..self.position).rev().find(|&idx| {
+            !matches!(
+                self.store.history_entry(idx),
+                Some(HistoryEntry {
+                    question: Question::Computed { .. },
+                    ..
+                })
+            )
+        })?;
+        self.position = idx;
+        self.get_question(idx)
+    }
+    /// Gets the index of the question the user is currently on, which is the one most recently
+    /// returned from [`Self::progress_with_answer`] or [`Self::back`]. Until the user goes back,
+    /// this will be the same as [`Self::next_index`].
+    pub fn current_index(&self) -> usize {
+        self.position
+    }
+    /// Sets the locale the user is answering in (e.g. `en-GB`), or clears it. Every answer given
+    /// after this will be stored along with this locale, and the script will be given it as the
+    /// `locale` property of the answer, so it can interpret localised text correctly. Answers
+    /// already given keep the locale they were given in, so the user can switch locales part-way
+    /// through a form.
+    pub fn set_locale(&mut self, locale: Option<String>) {
+        self.locale = locale;
+    }
+    /// Gets the locale the user is currently answering in, if there is one.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+    /// Gets the seed everything random in this form is derived from. Creating the form again with
+    /// this seed (see [`FormOptions::seed`]) and giving it the same answers will replay it exactly,
+    /// even if the script randomises its questions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Gets the variant of each experiment the script has been given so far with
+    /// `birocrat.variant`, keyed by experiment, so they can be recorded along with the user's
+    /// answers for analysis. This includes any experiments the script asked about while working
+    /// out questions the user ended up not being asked (e.g. because they changed an earlier
+    /// answer).
+    pub fn variants(&self) -> HashMap<String, String> {
+        self.script_api.assigned()
+    }
+    /// Gets the locale the cached answer to the question with the given ID was given in, if it
+    /// was given in one.
+    pub fn answer_locale(&self, id: &str) -> Option<&str> {
+        self.store.answer_locale(id)
+    }
+    /// Same as [`Self::progress_with_answer`], but this gives the answer in the given locale,
+    /// rather than the form's current one.
+    fn progress_in_locale(
+        &mut self,
+        question_idx: usize,
+        answer: Answer,
+        locale: Option<String>,
+    ) -> Result<Progress, Error> {
+        // Get the script-internal state at whatever point in the question history we're at
+        let (question_id, question, inner_state, should_clobber) =
+            if let Some(entry) = self.store.history_entry(question_idx) {
+                (&entry.id, &entry.question, &entry.state, true)
+            } else {
+                match &self.next_state {
+                    // There's a question, we can use its details
+                    (ScriptState::Asking { id, question, .. }, inner_state) => {
+                        (id, question, inner_state, false)
                     }
-                } else {
-                    return Err(Error::InvalidAnswerType {
-                        expected: "options for select question",
-                    });
+                    // If we're already done, short-circuit
+                    (ScriptState::Done(_), _) => return Ok(Progress::Done),
                 }
-            }
+            };
+
+        // Check the answer
+        let answer = match check_answer(question, answer, &self.options)? {
+            Ok(answer) => answer,
+            Err(message) => return Ok(Progress::Error(message)),
+        };
+
+        // Readonly questions can't have their answers changed once given, even if the question
+        // is being asked again after an earlier answer changed
+        let answer_eq = self.options.answer_eq.unwrap_or(Answer::eq);
+        if question.is_readonly()
+            && self
+                .store
+                .answer(question_id)
+                .is_some_and(|cached| !answer_eq(cached, &answer))
+        {
+            return Err(Error::AnswerLocked {
+                question_id: question_id.clone(),
+            });
+        }
+
+        // If this is the same answer the question was given before (as far as the host is
+        // concerned), in the same locale, the script would just produce the same states again, so
+        // we can keep everything after this question
+        if should_clobber
+            && self.store.answer_locale(question_id) == locale.as_deref()
+            && self
+                .store
+                .answer(question_id)
+                .is_some_and(|cached| answer_eq(cached, &answer))
+        {
+            return Ok(Progress::At(question_idx + 1));
         }
 
+        // We'll need to change the store later, so we can't hold onto anything from it
+        let question_id = question_id.clone();
+        let inner_state = inner_state.clone();
+
         // Poll the driver script for a new state (if we get an error from this, we won't clobber)
-        let next_state = self.get_script_state(inner_state, &answer)?;
+        let mut warnings = Vec::new();
+        // If we've already worked out what the script would do with this answer, we can use that
+        let prefetched = if should_clobber {
+            None
+        } else {
+            self.take_prefetched(&answer, locale.as_deref())
+        };
+        let next_state = match prefetched {
+            Some((next_state, prefetched_warnings)) => {
+                warnings = prefetched_warnings;
+                next_state
+            }
+            None => {
+                self.get_script_state(inner_state, &answer, locale.as_deref(), &mut warnings)?
+            }
+        };
         match next_state {
             Ok((new_state, new_inner_state)) => {
+                // If the script ended up in exactly the same state as it did with the old answer,
+                // everything after this question will come out the same too, so we can keep it
+                let unchanged = should_clobber
+                    && self.is_state_at(question_idx + 1, &new_state, &new_inner_state);
+                // If this answer would complete the form, the script might want to review all the
+                // answers together first (excluding any we're about to clobber)
+                let completes = if unchanged {
+                    matches!(self.next_state.0, ScriptState::Done(_))
+                } else {
+                    matches!(new_state, ScriptState::Done(_))
+                };
+                if completes {
+                    let answers = if unchanged {
+                        self.answers()
+                            .enumerate()
+                            .map(|(idx, (id, _, old_answer))| {
+                                if idx == question_idx {
+                                    (id, &answer, locale.as_deref())
+                                } else {
+                                    (id, old_answer, self.store.answer_locale(id))
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    } else {
+                        let num_kept = if should_clobber {
+                            question_idx
+                        } else {
+                            self.store.history_len()
+                        };
+                        self.answers()
+                            .take(num_kept)
+                            .map(|(id, _, answer)| (id, answer, self.store.answer_locale(id)))
+                            .chain(std::iter::once((
+                                question_id.as_str(),
+                                &answer,
+                                locale.as_deref(),
+                            )))
+                            .collect()
+                    };
+                    let field_errors = self.review(answers.into_iter())?;
+                    if !field_errors.is_empty() {
+                        return Ok(Progress::Rejected(field_errors));
+                    }
+                }
+
                 // This answer worked, cache it
-                self.cached_answers.insert(question_id.clone(), answer);
+                self.store.set_answer(question_id.clone(), answer);
+                self.store.set_answer_locale(question_id.clone(), locale);
+                for warning in warnings {
+                    if !self.warnings.contains(&warning) {
+                        self.warnings.push(warning);
+                    }
+                }
 
-                if should_clobber {
+                if unchanged {
+                    // Everything after this question can stay as it is
+                    return Ok(Progress::At(question_idx + 1));
+                } else if should_clobber {
                     // We're changing an answer, so we should get rid of additional questions (they
                     // might have changed). Keep the question we're answering though (`.truncate()`
                     // works by length).
-                    self.script_states.truncate(question_idx + 1);
+                    self.store.truncate_history(question_idx + 1);
                     // We can also clobber `next_state`
                     self.next_state = (new_state, new_inner_state);
+                    self.prefetched.clear();
+                    // Any questions after this that don't depend on it can be answered again
+                    // straight away
+                    self.replay(&question_id)?;
                 } else {
                     // We've answered the question in `next_state` (which we confirmed above is a
-                    // question), put it into `script_states`
+                    // question), put it into the history
                     let old_next_state =
                         std::mem::replace(&mut self.next_state, (new_state, new_inner_state));
-                    match old_next_state {
-                        (ScriptState::Asking { id, question }, old_inner_state) => {
-                            self.script_states.push((id, question, old_inner_state))
-                        }
-                        _ => unreachable!(),
-                    };
+                    self.prefetched.clear();
+                    if let (ScriptState::Asking { id, question, .. }, old_inner_state) =
+                        old_next_state
+                    {
+                        self.store.push_history(HistoryEntry {
+                            id,
+                            question,
+                            state: old_inner_state,
+                        });
+                    }
+                }
+                // If the script has worked out the answer to what comes next itself, give it that
+                // (the script's review of the form might reject it though, if it completes it)
+                if let Progress::Rejected(field_errors) = self.compute()? {
+                    return Ok(Progress::Rejected(field_errors));
                 }
 
                 // Regardless of the above, we have the right thing in `next_state` now
-                match &self.next_state.0 {
-                    ScriptState::Asking { question, id } => Ok(FormPoll::Question {
-                        question,
-                        answer: self.cached_answers.get(id),
-                    }),
-                    ScriptState::Done(_) => Ok(FormPoll::Done),
-                }
+                Ok(Progress::At(self.next_index()))
             }
             // We have an error from the script, which indicates this answer is invalid. We won't
             // clobber subsequent states if this was an old question or change anything else at all
             // about the form, we'll let the user decide what to do.
-            Err(script_err) => Ok(FormPoll::Error(script_err)),
+            Err(script_err) => Ok(Progress::Error(script_err)),
+        }
+    }
+    /// Calls the script ahead of time with some possible answers to the next question, so that
+    /// answering it with one of them later won't need to wait for the script. Interfaces can call
+    /// this when they'd otherwise be idle (e.g. while the user reads the question), to make slow
+    /// scripts feel instant.
+    ///
+    /// Currently, this only works for select-type questions that allow a single option, and will
+    /// call the script with each option in turn, in the form's current locale. At most `limit`
+    /// options will be tried in each call, so this can be spread out, and this returns how many
+    /// were tried (once there are none left, it will return `0`). Nothing else about the form is
+    /// changed, and these results are thrown away as soon as the next question changes.
+    ///
+    /// If the script calls an impure host function while this is happening (see
+    /// [`mark_impure`]), or produces a hard error, the result will be thrown away, and the script
+    /// will be called as usual when that answer is actually given.
+    pub fn prefetch(&mut self, limit: usize) -> usize {
+        let (
+            ScriptState::Asking {
+                question:
+                    Question::Select {
+                        options,
+                        multiple: false,
+                        followup,
+                        ..
+                    },
+                ..
+            },
+            inner_state,
+        ) = &self.next_state
+        else {
+            return 0;
+        };
+        let answers = options
+            .iter()
+            // Options that need text or bring up a follow-up can't be answered in advance
+            .filter(|option| {
+                !option.specify
+                    && !followup
+                        .as_ref()
+                        .is_some_and(|followup| followup.when.contains(&option.value))
+            })
+            .map(|option| Answer::Options(vec![option.value.clone()]))
+            .filter(|answer| {
+                !self.prefetched.iter().any(|prefetched| {
+                    &prefetched.answer == answer && prefetched.locale == self.locale
+                })
+            })
+            .take(limit)
+            .collect::<Vec<_>>();
+        let inner_state = inner_state.clone();
+
+        let num_tried = answers.len();
+        for answer in answers {
+            let assigned = self.script_api.assigned();
+            self.lua_vm.set_app_data(Prefetching { impure: false });
+            let mut warnings = Vec::new();
+            let next_state = self.get_script_state(
+                inner_state.clone(),
+                &answer,
+                self.locale.as_deref(),
+                &mut warnings,
+            );
+            let impure = self
+                .lua_vm
+                .remove_app_data::<Prefetching>()
+                .is_some_and(|prefetching| prefetching.impure);
+            let assigned = self.script_api.rewind_assigned(assigned);
+            let outcome = match next_state {
+                Ok(next_state) if !impure => Some((next_state, warnings)),
+                _ => None,
+            };
+            self.prefetched.push(Prefetched {
+                answer,
+                locale: self.locale.clone(),
+                outcome,
+                assigned,
+            });
+        }
+
+        num_tried
+    }
+    /// Takes the result of calling the script ahead of time with the given answer to the question
+    /// in `next_state` in the given locale, if there is one that can be used, recording any
+    /// variants the script was given in that call.
+    fn take_prefetched(&mut self, answer: &Answer, locale: Option<&str>) -> Option<Outcome> {
+        let idx = self.prefetched.iter().position(|prefetched| {
+            &prefetched.answer == answer
+                && prefetched.locale.as_deref() == locale
+                && prefetched.outcome.is_some()
+        })?;
+        let prefetched = self.prefetched.swap_remove(idx);
+        self.script_api.restore_assigned(prefetched.assigned);
+        prefetched.outcome
+    }
+    /// Checks whether or not the script was in the given state at the given index in the history
+    /// (or in `next_state`, if the index is past the end of the history).
+    fn is_state_at(&self, idx: usize, state: &ScriptState, inner_state: &Value) -> bool {
+        match self.store.history_entry(idx) {
+            Some(entry) => {
+                let ScriptState::Asking { id, question, .. } = state else {
+                    return false;
+                };
+                *id == entry.id && *question == entry.question && *inner_state == entry.state
+            }
+            None => self.next_state.0 == *state && self.next_state.1 == *inner_state,
+        }
+    }
+    /// Answers the questions after the one with the given ID, whose answer has just changed, with
+    /// their cached answers, for as long as the script has declared that they don't depend on it.
+    /// This stops at the first question that does (or that doesn't declare its dependencies, or
+    /// doesn't have a cached answer, or whose cached answer is no longer accepted), which will be
+    /// left as the next question for the user.
+    ///
+    /// This saves the user from having to confirm every answer after one they changed early on in
+    /// a long form.
+    fn replay(&mut self, changed_id: &str) -> Result<(), Error> {
+        loop {
+            // Computed steps are worked out again from scratch, rather than from cached answers
+            if let ScriptState::Asking {
+                question: Question::Computed { .. },
+                ..
+            } = &self.next_state.0
+            {
+                match self.compute()? {
+                    Progress::At(_) | Progress::Done => continue,
+                    Progress::Error(_) | Progress::Rejected(_) => return Ok(()),
+                }
+            }
+            let ScriptState::Asking {
+                id,
+                depends_on: Some(depends_on),
+                ..
+            } = &self.next_state.0
+            else {
+                return Ok(());
+            };
+            if depends_on.iter().any(|dep| dep == changed_id) {
+                return Ok(());
+            }
+            let Some(answer) = self.store.answer(id).cloned() else {
+                return Ok(());
+            };
+            // This should be interpreted in the locale it was originally given in
+            let locale = self.store.answer_locale(id).map(String::from);
+            match self.progress_in_locale(self.next_index(), answer, locale)? {
+                Progress::At(_) | Progress::Done => {}
+                Progress::Error(_) | Progress::Rejected(_) => return Ok(()),
+            }
+        }
+    }
+    /// Answers the next question with its own value if it's a computed step (see
+    /// [`Question::Computed`]), which will then answer any computed steps straight after it in the
+    /// same way. If the next question isn't a computed step, this does nothing.
+    ///
+    /// The script rejecting its own value is a mistake in the script, so that's a hard error.
+    fn compute(&mut self) -> Result<Progress, Error> {
+        let ScriptState::Asking {
+            id,
+            question: Question::Computed { value, .. },
+            ..
+        } = &self.next_state.0
+        else {
+            return Ok(Progress::At(self.next_index()));
+        };
+        let question_id = id.clone();
+        let answer = Answer::Computed(value.clone());
+        // Computed values don't depend on the user's locale
+        match self.progress_in_locale(self.next_index(), answer, None)? {
+            Progress::Error(message) => Err(Error::ComputedValueRejected {
+                question_id,
+                message,
+            }),
+            progress => Ok(progress),
+        }
+    }
+    /// Gets the question at the given index in the history as a poll, along with any cached
+    /// answer for it. If the index is past the end of the history, this will be the next question,
+    /// or the completion state.
+    fn poll_at(&self, idx: usize) -> FormPoll<'_> {
+        let (id, question) = match self.store.history_entry(idx) {
+            Some(entry) => (&entry.id, &entry.question),
+            None => match &self.next_state.0 {
+                ScriptState::Asking { id, question, .. } => (id, question),
+                ScriptState::Done(output) => return FormPoll::Done(output),
+            },
+        };
+        FormPoll::Question {
+            question,
+            answer: self.store.answer(id),
         }
     }
     /// If the form has been completed, returns the final object the driver script returned,
     /// serialized for convenience as JSON.
+    #[allow(clippy::result_large_err)]
     pub fn into_done(self) -> Result<serde_json::Value, Self> {
         match self.next_state {
             (ScriptState::Done(obj), _) => Ok(obj),
@@ -269,22 +943,59 @@ impl<'l> Form<'l> {
     /// script. This method does not modify the internal `next_state` or any other properties.
     ///
     /// This returns a nested `Result` because the execution may succeed but the script itself may
-    /// return a string error message.
+    /// return a string error message. Any mistakes in the script that were worked around will be
+    /// added to `warnings`.
     fn get_script_state(
         &self,
-        inner_state: &Value,
+        inner_state: Value,
         answer: &Answer,
+        locale: Option<&str>,
+        warnings: &mut Vec<Warning>,
     ) -> Result<Result<(ScriptState, Value), String>, Error> {
         Self::call_driver_fn(
             self.lua_vm,
             &self.driver_function,
+            &self.script_api,
             // Cheap clone of a Lua reference
             self.parameters.clone(),
-            // PERF: Way of avoiding this clone?
-            Some((inner_state.clone(), answer)),
+            Some((inner_state, answer, locale)),
+            &self.options,
+            warnings,
         )
     }
 
+    /// Calls the script's `Review` function, if it has one, with the given answers (keyed by
+    /// question ID, and with the locales they were given in), returning any errors it gave for
+    /// particular questions, keyed by question ID. If there's no `Review` function, or it accepts
+    /// the answers, this will be empty.
+    fn review<'a>(
+        &self,
+        answers: impl Iterator<Item = (&'a str, &'a Answer, Option<&'a str>)>,
+    ) -> Result<HashMap<String, String>, Error> {
+        let Some(review_function) = &self.review_function else {
+            return Ok(HashMap::new());
+        };
+
+        let answers_table = self
+            .lua_vm
+            .create_table()
+            .map_err(|err| Error::AllocateAnswerTableFailed { source: err })?;
+        for (id, answer, locale) in answers {
+            let answer = answer
+                .to_lua_in_locale(self.lua_vm, locale)
+                .map_err(|err| Error::AllocateAnswerTableFailed { source: err })?;
+            answers_table
+                .set(id, answer)
+                .map_err(|err| Error::AllocateAnswerTableFailed { source: err })?;
+        }
+
+        self.script_api.install()?;
+        let field_errors: Option<HashMap<String, String>> = review_function
+            .call((answers_table, self.parameters.clone()))
+            .map_err(|err| Error::RunReviewFailed { source: err })?;
+        Ok(field_errors.unwrap_or_default())
+    }
+
     /// Calls the raw driver function with the given optional state and answer (if one is provided,
     /// both must be). This is used internally, and only directly when getting the first state,
     /// when `None` must be provided. For all subsequent calls, [`Self::get_script_state`] should
@@ -292,44 +1003,73 @@ impl<'l> Form<'l> {
     fn call_driver_fn(
         lua_vm: &'l Lua,
         driver_function: &Function<'l>,
+        script_api: &ScriptApi<'l>,
         parameters: LuaValue<'l>,
-        inner_state_and_answer: Option<(Value, &Answer)>,
+        inner_state_and_answer: Option<(Value, &Answer, Option<&str>)>,
+        options: &FormOptions,
+        warnings: &mut Vec<Warning>,
     ) -> Result<Result<(ScriptState, Value), String>, Error> {
         // Convert the answer provided into a Lua table, or, if nothing was provided, call with
         // nils
-        let (inner_state, answer) = if let Some((inner_state, answer)) = inner_state_and_answer {
-            (
-                lua_vm.to_value(&inner_state).unwrap(),
-                LuaValue::Table(
-                    answer
-                        .to_lua(lua_vm)
-                        .map_err(|err| Error::AllocateAnswerTableFailed { source: err })?,
-                ),
-            )
-        } else {
-            (LuaValue::Nil, LuaValue::Nil)
-        };
+        let (inner_state, answer) =
+            if let Some((inner_state, answer, locale)) = inner_state_and_answer {
+                (
+                    lua_vm
+                        .to_value(&inner_state)
+                        .map_err(|err| Error::DeserializeStateFailed { source: err })?,
+                    LuaValue::Table(
+                        answer
+                            .to_lua_in_locale(lua_vm, locale)
+                            .map_err(|err| Error::AllocateAnswerTableFailed { source: err })?,
+                    ),
+                )
+            } else {
+                (LuaValue::Nil, LuaValue::Nil)
+            };
 
+        script_api.install()?;
+        // We only take measurements if someone's going to record them, and calls made ahead of
+        // time aren't steps the user took
+        let metrics = options
+            .metrics
+            .as_ref()
+            .filter(|_| lua_vm.app_data_ref::<Prefetching>().is_none());
+        let started = metrics.map(|_| Instant::now());
         let ret_table: Table = driver_function
-            .call((inner_state, answer, parameters))
+            .call((inner_state, answer, parameters.clone()))
             .map_err(|err| Error::RunDriverFailed { source: err })?;
+        let script_time = started.map(|started| started.elapsed());
         let state: String = ret_table.get(1).map_err(|_| Error::InvalidResult)?;
         let props: LuaValue = ret_table.get(2).map_err(|_| Error::InvalidResult)?;
         let inner_state: LuaValue = ret_table.get(3).map_err(|_| Error::InvalidResult)?;
-        // Serialize the inner state as an intermediate value
-        let inner_state = serde_json::to_value(inner_state)
-            .map_err(|err| Error::SerializeStateFailed { source: err })?;
+        // Serialize the inner state as an intermediate value (making sure it won't overflow the
+        // stack first)
+        let started = metrics.map(|_| Instant::now());
+        check_depth(&inner_state)?;
+        let inner_state = to_json_with_path(&inner_state)
+            .map_err(|(path, err)| Error::SerializeStateFailed { path, source: err })?;
+        if let (Some(metrics), Some(script_time), Some(started)) = (metrics, script_time, started) {
+            metrics.record_step(StepMetrics {
+                script_time,
+                serialization_time: started.elapsed(),
+                state_size: serde_json::to_vec(&inner_state).map_or(0, |json| json.len()),
+                vm_memory: lua_vm.used_memory(),
+            });
+        }
 
         // We get the raw script state as a double-result, one is handled above and the other is
         // for script errors, but if that didn't occur we should implant the internal state too
-        let script_state = ScriptState::from_lua(&state, props)?;
+        let script_state = ScriptState::from_lua(&state, props, &parameters, options, warnings)?;
         // NOTE: If we have a done state, `inner_state` will be null.
         Ok(script_state.map(|state| (state, inner_state)))
     }
 }
 
 /// The possible results when polling the form. This is returned when a question is answered.
-#[derive(PartialEq, Eq, Debug)]
+///
+/// The `Debug` output of this redacts the previous answer to a secret question (see
+/// [`Question::is_secret`]).
+#[derive(PartialEq)]
 pub enum FormPoll<'a> {
     /// There is a new question to ask.
     Question {
@@ -342,8 +1082,47 @@ pub enum FormPoll<'a> {
     /// to the question before the one being requested now, but it could also be to do with
     /// generating the next question.
     Error(String),
-    /// The form is complete, and an object is available to be processed. [`Form::into_done`]
-    /// should be used to extract the return object from the driver script.
+    /// The answer would have completed the form, but the script's `Review` function, which checks
+    /// all the user's answers together, rejected some of them. This contains the errors it gave,
+    /// keyed by the IDs of the questions they're for, which may include the question just answered.
+    /// The answer was not accepted, so the user should change their answers to the questions with
+    /// errors, and then answer the question just asked again.
+    Rejected(HashMap<String, String>),
+    /// The form is complete, and this is the object the driver script returned, serialized as
+    /// JSON. The form isn't consumed, so this can be shown to the user as a summary while they
+    /// can still go back and change their answers, and [`Form::into_done`] should be used to take
+    /// the object once they're finished.
+    Done(&'a Value),
+}
+
+impl std::fmt::Debug for FormPoll<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormPoll::Question { question, answer } => f
+                .debug_struct("Question")
+                .field("question", question)
+                .field(
+                    "answer",
+                    &answer.map(|answer| DebugAnswer::new(question, answer)),
+                )
+                .finish(),
+            FormPoll::Error(message) => f.debug_tuple("Error").field(message).finish(),
+            FormPoll::Rejected(errors) => f.debug_tuple("Rejected").field(errors).finish(),
+            FormPoll::Done(output) => f.debug_tuple("Done").field(output).finish(),
+        }
+    }
+}
+
+/// The outcome of answering a question. This is the same as a [`FormPoll`], except that it doesn't
+/// borrow the form, so the form can still be updated after answering.
+enum Progress {
+    /// The answer produced an error for the user.
+    Error(String),
+    /// The script's review rejected some answers.
+    Rejected(HashMap<String, String>),
+    /// The answer was accepted, and the form should be polled at this index.
+    At(usize),
+    /// The form was already complete.
     Done,
 }
 
@@ -353,7 +1132,9 @@ pub enum FormPoll<'a> {
 ///
 /// This should be stored in each case along with an arbitrary [`Value`] from the script, which
 /// constitutes its internal state. This only represents the state we observe.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+// Only a handful of these are alive at once, so boxing questions wouldn't save anything
+#[allow(clippy::large_enum_variant)]
 enum ScriptState {
     /// The script is in a valid state, and wishes to ask the given question.
     Asking {
@@ -362,6 +1143,11 @@ enum ScriptState {
         id: String,
         /// The question to ask.
         question: Question,
+        /// The IDs of the questions whose answers this question depends on, if the script
+        /// declared them. If the answer to an earlier question changes, but not to any of these,
+        /// the cached answer to this question can be given again automatically (see
+        /// [`Form::replay`]).
+        depends_on: Option<Vec<String>>,
     },
     /// All questions have been asked and answered, and the script has returned an object
     /// created from them. This object is serialized as JSON for simplicity.
@@ -370,10 +1156,18 @@ enum ScriptState {
 impl ScriptState {
     /// Creates an internal representation of the state of the script from the given Lua
     /// components. The first is a string indicator of the state variant (i.e. `question`, `error`,
-    /// or `done`), and the second a series of properties for that variant.
+    /// or `done`), and the second a series of properties for that variant. The form's parameters
+    /// are needed to resolve any options a question takes from them.
     ///
-    /// If the script returned an error, this will return `Ok(Err(err))`.
-    fn from_lua(state: &str, props: LuaValue) -> Result<Result<Self, String>, Error> {
+    /// If the script returned an error, this will return `Ok(Err(err))`. In lenient mode, any
+    /// mistakes in the script that were worked around will be added to `warnings`.
+    fn from_lua(
+        state: &str,
+        props: LuaValue,
+        parameters: &LuaValue,
+        form_options: &FormOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Result<Self, String>, Error> {
         match state {
             "question" => {
                 // We have a question to ask, which will be provided as an ID, a question type, a
@@ -382,66 +1176,16 @@ impl ScriptState {
                 let id: String = question_table
                     .get("id")
                     .map_err(|err| Error::NoIdInQuestionData { source: err })?;
-                let question_type: String = question_table
-                    .get("type")
-                    .map_err(|err| Error::NoTypeInQuestionData { source: err })?;
-                let question_body: String = question_table
-                    .get("text")
-                    .map_err(|err| Error::NoBodyInQuestionData { source: err })?;
-                let suggested_answer: Option<String> =
-                    question_table.get("default").unwrap_or(None);
-
-                // The remaining options we extract are type-dependent
-                let question = match question_type.as_str() {
-                    "simple" => Question::Simple {
-                        prompt: question_body,
-                        default: suggested_answer,
-                    },
-                    "multiline" => Question::Multiline {
-                        prompt: question_body,
-                        default: suggested_answer,
-                    },
-                    "select" => {
-                        // If `multiple` isn't present, we'll default to `false`, reasonably. That
-                        // means we can't parse it when we get it though
-                        let multiple = question_table
-                            .get("multiple")
-                            .unwrap_or(LuaValue::Boolean(false));
-                        let multiple = if multiple.is_nil() {
-                            false
-                        } else {
-                            multiple
-                                .as_boolean()
-                                .ok_or(Error::InvalidMultipleProperty)?
-                        };
-
-                        let options: Vec<String> = question_table
-                            .get("options")
-                            .map_err(|err| Error::NoOptionsInQuestionData { source: err })?;
-
-                        // Make sure any default is one of the options
-                        if let Some(default) = &suggested_answer {
-                            if !options.contains(&default) {
-                                return Err(Error::DefaultNotInOptions {
-                                    default: default.clone(),
-                                })?;
-                            }
-                        }
-
-                        Question::Select {
-                            prompt: question_body,
-                            default: suggested_answer,
-                            options,
-                            multiple,
-                        }
-                    }
-                    _ => {
-                        return Err(Error::InvalidQuestionType {
-                            ty: question_type.to_string(),
-                        })
-                    }
-                };
-                Ok(Ok(ScriptState::Asking { question, id }))
+                let depends_on: Option<Vec<String>> = question_table
+                    .get("depends_on")
+                    .map_err(|err| Error::InvalidDependsOnProperty { source: err })?;
+                let question =
+                    Self::parse_question(&id, question_table, parameters, form_options, warnings)?;
+                Ok(Ok(ScriptState::Asking {
+                    question,
+                    id,
+                    depends_on,
+                }))
             }
             "error" => {
                 // We have a string error message
@@ -450,8 +1194,9 @@ impl ScriptState {
             }
             "done" => {
                 // We have the final result, parse it into a `serde_json` object and return
-                let result = serde_json::to_value(&props)
-                    .map_err(|err| Error::SerializeAnswersFailed { source: err })?;
+                check_depth(&props)?;
+                let result = to_json_with_path(&props)
+                    .map_err(|(path, err)| Error::SerializeAnswersFailed { path, source: err })?;
                 Ok(Ok(ScriptState::Done(result)))
             }
             _ => Err(Error::InvalidState {
@@ -459,57 +1204,1450 @@ impl ScriptState {
             }),
         }
     }
-}
+    /// Parses the properties of a question from the given table (which the script returned), for
+    /// the question with the given ID. In lenient mode, any mistakes in the script that were
+    /// worked around will be added to `warnings`.
+    fn parse_question(
+        id: &str,
+        question_table: &Table,
+        parameters: &LuaValue,
+        form_options: &FormOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Question, Error> {
+        let question_type: String = question_table
+            .get("type")
+            .map_err(|err| Error::NoTypeInQuestionData { source: err })?;
+        let question_body: String = question_table
+            .get("text")
+            .map_err(|err| Error::NoBodyInQuestionData { source: err })?;
+        let suggested_answer: Option<String> = question_table.get("default").unwrap_or(None);
+        let tags: Option<Vec<String>> = question_table
+            .get("tags")
+            .map_err(|err| Error::InvalidTagsProperty { source: err })?;
+        let tags = tags.unwrap_or_default();
+        let readonly: Option<bool> = question_table
+            .get("readonly")
+            .map_err(|err| Error::InvalidReadonlyProperty { source: err })?;
+        let readonly = readonly.unwrap_or(false);
+        let help: Option<String> = question_table
+            .get("help")
+            .map_err(|err| Error::InvalidHelpProperty { source: err })?;
+        let format: Option<String> = question_table
+            .get("format")
+            .map_err(|err| Error::InvalidFormatProperty { source: err })?;
+        let format = match format {
+            Some(format) => {
+                PromptFormat::from_name(&format).ok_or(Error::UnknownPromptFormat { format })?
+            }
+            None => PromptFormat::Plain,
+        };
+        let common = QuestionCommon {
+            tags,
+            readonly,
+            help,
+            format,
+        };
+        // Information and computed steps can't be skipped, so they ignore this
+        let required: Option<bool> = question_table
+            .get("required")
+            .map_err(|err| Error::InvalidRequiredProperty { source: err })?;
+        let required = required.unwrap_or(true);
 
-/// The different types of questions that can be asked. These are fairly generic, as Kylie knows
-/// nothing about the contents of boxes. This allows significant flexibility, and delegates
-/// complexity to box handlers.
-#[derive(Debug, PartialEq, Eq)]
-pub enum Question {
-    /// A simple question that requires a single-line answer. This would correspond in HTML to a
-    /// single `<input>`.
-    Simple {
-        /// The prompt for the question.
-        prompt: String,
-        /// A default suggested answer.
-        default: Option<String>,
-    },
-    /// A simple question that requires a multiline answer. This would correspond in HTML to a
-    /// `<textarea>`.
-    Multiline {
-        /// The prompt for the question.
-        prompt: String,
-        /// A default suggested answer.
-        default: Option<String>,
-    },
-    /// A question where the user can select their answer from a list.
-    Select {
-        /// The question being asked.
-        prompt: String,
-        /// A default suggested answer. This is guaranteed to be one of the options.
-        default: Option<String>,
-        /// A list of options the user can take.
-        options: Vec<String>,
-        /// Whether or not the user can select multiple options. Further validation like ensuring
-        /// the user has selected fewer than *n* answers is left to the box.
-        multiple: bool,
-    },
-}
+        // The remaining options we extract are type-dependent
+        let question = match question_type.as_str() {
+            "simple" | "multiline" => {
+                let get_string = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<String>>(property)
+                        .map_err(|err| Error::InvalidPatternProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let pattern = get_string("pattern")?;
+                let pattern_hint = get_string("pattern_hint")?;
+                // Catch bad patterns now, rather than when the user answers
+                if let Some(pattern) = &pattern {
+                    whole_match_regex(pattern)?;
+                }
+                let get_length = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<usize>>(property)
+                        .map_err(|err| Error::InvalidLengthProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let (min_length, max_length) =
+                    (get_length("min_length")?, get_length("max_length")?);
+                if let (Some(min), Some(max)) = (min_length, max_length) {
+                    if min > max {
+                        return Err(Error::InvalidLengthRange { min, max });
+                    }
+                }
 
-/// The user's answer to a question. This contains no information about the question it answers.
-#[derive(Debug, PartialEq, Eq)]
-pub enum Answer {
-    /// A textual answer. This will come to either [`Question::Simple`] or [`Question::Multiline`].
-    Text(String),
-    /// An answer in terms of a series of given options. These are *guaranteed* to be valid with
-    /// respect to the options offered in the relevant question, and will come as a response to
-    /// [`Question::Select`].
+                if question_type == "simple" {
+                    Question::Simple {
+                        prompt: question_body,
+                        default: suggested_answer,
+                        pattern,
+                        pattern_hint,
+                        min_length,
+                        max_length,
+                        required,
+                        common,
+                    }
+                } else {
+                    Question::Multiline {
+                        prompt: question_body,
+                        default: suggested_answer,
+                        pattern,
+                        pattern_hint,
+                        min_length,
+                        max_length,
+                        required,
+                        common,
+                    }
+                }
+            }
+            "email" => Question::Email {
+                prompt: question_body,
+                default: suggested_answer,
+                required,
+                common,
+            },
+            "phone" => {
+                let region: Option<String> = question_table
+                    .get("region")
+                    .map_err(|err| Error::InvalidRegionProperty { source: err })?;
+                let region = region.map(|region| region.to_uppercase());
+                if let Some(region) = &region {
+                    if phone::calling_code(region).is_none() {
+                        return Err(Error::UnknownPhoneRegion {
+                            region: region.clone(),
+                        });
+                    }
+                }
+                let default = suggested_answer
+                    .map(|value| {
+                        normalize_phone(&value, region.as_deref())
+                            .map_err(|_| Error::InvalidPhoneDefault { value })
+                    })
+                    .transpose()?;
+
+                Question::Phone {
+                    prompt: question_body,
+                    default,
+                    region,
+                    required,
+                    common,
+                }
+            }
+            "url" => {
+                let schemes: Option<Vec<String>> = question_table
+                    .get("schemes")
+                    .map_err(|err| Error::InvalidSchemesProperty { source: err })?;
+                // Scripts can give schemes with or without the colon
+                let schemes = schemes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|scheme| scheme.trim_end_matches(':').to_lowercase())
+                    .collect();
+
+                Question::Url {
+                    prompt: question_body,
+                    default: suggested_answer,
+                    schemes,
+                    required,
+                    common,
+                }
+            }
+            // Suggesting a default for a secret would defeat the point, so we ignore it
+            "secret" => Question::Secret {
+                prompt: question_body,
+                required,
+                common,
+            },
+            "date" => {
+                let get_date = |property: &'static str| {
+                    let value: Option<String> =
+                        question_table
+                            .get(property)
+                            .map_err(|err| Error::InvalidDateProperty {
+                                property,
+                                source: err,
+                            })?;
+                    value
+                        .map(|value| {
+                            parse_date(&value).ok_or(Error::InvalidDate { property, value })
+                        })
+                        .transpose()
+                };
+                let default = suggested_answer
+                    .map(|value| {
+                        parse_date(&value).ok_or(Error::InvalidDate {
+                            property: "default",
+                            value,
+                        })
+                    })
+                    .transpose()?;
+
+                Question::Date {
+                    prompt: question_body,
+                    default,
+                    min: get_date("min")?,
+                    max: get_date("max")?,
+                    required,
+                    common,
+                }
+            }
+            "time" => {
+                let seconds: Option<bool> = question_table
+                    .get("seconds")
+                    .map_err(|err| Error::InvalidSecondsProperty { source: err })?;
+                // Scripts can always give seconds, even if users can't
+                let get_time = |property: &'static str| {
+                    let value: Option<String> =
+                        question_table
+                            .get(property)
+                            .map_err(|err| Error::InvalidTimeProperty {
+                                property,
+                                source: err,
+                            })?;
+                    value
+                        .map(|value| {
+                            parse_time(&value, true).ok_or(Error::InvalidTime { property, value })
+                        })
+                        .transpose()
+                };
+                let default = suggested_answer
+                    .map(|value| {
+                        parse_time(&value, true).ok_or(Error::InvalidTime {
+                            property: "default",
+                            value,
+                        })
+                    })
+                    .transpose()?;
+
+                Question::Time {
+                    prompt: question_body,
+                    default,
+                    min: get_time("min")?,
+                    max: get_time("max")?,
+                    seconds: seconds.unwrap_or(false),
+                    required,
+                    common,
+                }
+            }
+            "datetime" => {
+                let timezone: Option<String> = question_table.get("timezone").map_err(|err| {
+                    Error::InvalidDateTimeProperty {
+                        property: "timezone",
+                        source: err,
+                    }
+                })?;
+                let timezone = timezone
+                    .map(|value| {
+                        value
+                            .parse::<FixedOffset>()
+                            .map_err(|_| Error::InvalidTimezone { value })
+                    })
+                    .transpose()?;
+                let get_datetime = |property: &'static str| {
+                    let value: Option<String> = question_table.get(property).map_err(|err| {
+                        Error::InvalidDateTimeProperty {
+                            property,
+                            source: err,
+                        }
+                    })?;
+                    value
+                        .map(|value| {
+                            parse_datetime(&value, timezone)
+                                .ok_or(Error::InvalidDateTime { property, value })
+                        })
+                        .transpose()
+                };
+
+                Question::DateTime {
+                    prompt: question_body,
+                    default: suggested_answer
+                        .map(|value| {
+                            parse_datetime(&value, timezone).ok_or(Error::InvalidDateTime {
+                                property: "default",
+                                value,
+                            })
+                        })
+                        .transpose()?,
+                    min: get_datetime("min")?,
+                    max: get_datetime("max")?,
+                    timezone,
+                    required,
+                    common,
+                }
+            }
+            "select" => {
+                // If `multiple` isn't present, we'll default to `false`, reasonably. That
+                // means we can't parse it when we get it though
+                let multiple = question_table
+                    .get("multiple")
+                    .unwrap_or(LuaValue::Boolean(false));
+                let multiple = if multiple.is_nil() {
+                    false
+                } else {
+                    multiple
+                        .as_boolean()
+                        .ok_or(Error::InvalidMultipleProperty)?
+                };
+
+                // Long lists (like countries) can come straight from the parameters, rather than
+                // being copied into the script
+                let options_from: Option<String> = question_table
+                    .get("options_from")
+                    .map_err(|err| Error::InvalidOptionsFromProperty { source: err })?;
+                let options: Vec<SelectOption> = match options_from {
+                    Some(_) if question_table.contains_key("options").unwrap_or(false) => {
+                        return Err(Error::OptionsAndOptionsFrom)
+                    }
+                    Some(path) => options_from_params(parameters, &path)?
+                        .into_iter()
+                        .map(SelectOption::from)
+                        .collect(),
+                    None => question_table
+                        .get("options")
+                        .map_err(|err| Error::NoOptionsInQuestionData { source: err })?,
+                };
+                let allow_other: Option<bool> = question_table
+                    .get("allow_other")
+                    .map_err(|err| Error::InvalidAllowOtherProperty { source: err })?;
+                let allow_other = allow_other.unwrap_or(false);
+                let searchable: Option<bool> = question_table
+                    .get("searchable")
+                    .map_err(|err| Error::InvalidSearchableProperty { source: err })?;
+                let searchable = searchable.unwrap_or(false);
+                let get_count = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<usize>>(property)
+                        .map_err(|err| Error::InvalidSelectionCountProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let (min_selected, max_selected) =
+                    (get_count("min_selected")?, get_count("max_selected")?);
+                if let (Some(min), Some(max)) = (min_selected, max_selected) {
+                    if min > max {
+                        return Err(Error::InvalidSelectionCountRange { min, max });
+                    }
+                }
+
+                // Multiple selections can start with several options ticked, so their default
+                // can be a list
+                let defaults: Vec<String> = match question_table.get::<_, LuaValue>("default") {
+                    Ok(LuaValue::Table(_)) if multiple => question_table
+                        .get("default")
+                        .map_err(|err| Error::InvalidSelectDefaults { source: err })?,
+                    Ok(LuaValue::Table(_)) => return Err(Error::MultipleDefaultsForSingleSelect),
+                    _ => suggested_answer.into_iter().collect(),
+                };
+                // Make sure every default is one of the options (unless we're being lenient,
+                // in which case we can just drop the ones that aren't, or the user can give one
+                // value of their own)
+                let mut has_other = false;
+                let mut checked_defaults = Vec::new();
+                for default in defaults {
+                    let is_option = options.iter().any(|option| option.value == default);
+                    if is_option || (allow_other && !has_other) {
+                        has_other |= !is_option;
+                        checked_defaults.push(default);
+                        continue;
+                    }
+                    if !form_options.lenient {
+                        return Err(Error::DefaultNotInOptions { default });
+                    }
+                    warnings.push(Warning::DefaultNotInOptions {
+                        question_id: id.to_string(),
+                        default,
+                    });
+                }
+
+                // A follow-up is given just like a question on its own, plus the options that
+                // bring it up
+                let followup: Option<Table> = question_table
+                    .get("followup")
+                    .map_err(|err| Error::InvalidFollowUpProperty { source: err })?;
+                let followup = match followup {
+                    Some(table) => {
+                        let when: Vec<String> = table
+                            .get("when")
+                            .map_err(|err| Error::InvalidFollowUpWhen { source: err })?;
+                        if let Some(value) = when
+                            .iter()
+                            .find(|value| !options.iter().any(|option| &&option.value == value))
+                        {
+                            return Err(Error::FollowUpWhenNotInOptions {
+                                value: value.clone(),
+                            });
+                        }
+                        let question =
+                            Self::parse_question(id, &table, parameters, form_options, warnings)?;
+                        if matches!(
+                            question,
+                            Question::Page { .. }
+                                | Question::Info { .. }
+                                | Question::Computed { .. }
+                                | Question::Select {
+                                    followup: Some(_),
+                                    ..
+                                }
+                        ) {
+                            return Err(Error::InvalidFollowUpType);
+                        }
+                        Some(FollowUp {
+                            when,
+                            question: Box::new(question),
+                        })
+                    }
+                    None => None,
+                };
+
+                Question::Select {
+                    prompt: question_body,
+                    defaults: checked_defaults,
+                    options,
+                    multiple,
+                    allow_other,
+                    searchable,
+                    min_selected,
+                    max_selected,
+                    followup,
+                    required,
+                    common,
+                }
+            }
+            // There's no default for these, because interfaces start with the options in
+            // the order the script gave them
+            "rank" => {
+                let options: Vec<SelectOption> = question_table
+                    .get("options")
+                    .map_err(|err| Error::NoOptionsInQuestionData { source: err })?;
+
+                Question::Rank {
+                    prompt: question_body,
+                    options,
+                    required,
+                    common,
+                }
+            }
+            // Each question on a page is given just like a question on its own, but they're all
+            // answered together
+            "page" => {
+                let tables: Vec<Table> = question_table
+                    .get("questions")
+                    .map_err(|err| Error::InvalidPageQuestions { source: err })?;
+                if tables.is_empty() {
+                    return Err(Error::EmptyPage);
+                }
+                let mut questions: Vec<PageQuestion> = Vec::new();
+                for table in tables {
+                    let id: String = table
+                        .get("id")
+                        .map_err(|err| Error::NoIdInQuestionData { source: err })?;
+                    if questions.iter().any(|item| item.id == id) {
+                        return Err(Error::DuplicatePageQuestionId { id });
+                    }
+                    let question =
+                        Self::parse_question(&id, &table, parameters, form_options, warnings)?;
+                    match question {
+                        Question::Page { .. } => return Err(Error::NestedPage),
+                        Question::Computed { .. } => return Err(Error::ComputedOnPage),
+                        _ => {}
+                    }
+                    questions.push(PageQuestion { id, question });
+                }
+
+                Question::Page {
+                    prompt: question_body,
+                    questions,
+                    required,
+                    common,
+                }
+            }
+            "info" => Question::Info {
+                prompt: question_body,
+                common,
+            },
+            "computed" => {
+                let value: LuaValue = question_table.get("value").unwrap_or(LuaValue::Nil);
+                check_depth(&value)?;
+                let value = to_json_with_path(&value).map_err(|(path, err)| {
+                    Error::SerializeComputedValueFailed { path, source: err }
+                })?;
+
+                Question::Computed {
+                    prompt: question_body,
+                    value,
+                    common,
+                }
+            }
+            // These don't have defaults either, because they start with no entries
+            "group" => {
+                let fields: Vec<GroupField> = question_table
+                    .get("fields")
+                    .map_err(|err| Error::InvalidGroupFields { source: err })?;
+                // Catch bad patterns now, rather than when the user answers
+                for pattern in fields.iter().filter_map(|field| field.pattern.as_ref()) {
+                    whole_match_regex(pattern)?;
+                }
+                let get_bound = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<usize>>(property)
+                        .map_err(|err| Error::InvalidGroupProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let (min, max) = (get_bound("min")?, get_bound("max")?);
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(Error::InvalidGroupRange { min, max });
+                    }
+                }
+
+                Question::Group {
+                    prompt: question_body,
+                    fields,
+                    min,
+                    max,
+                    required,
+                    common,
+                }
+            }
+            "range" => {
+                let get_number = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<f64>>(property)
+                        .map_err(|err| Error::InvalidRangeProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let (Some(min), Some(max)) = (get_number("min")?, get_number("max")?) else {
+                    return Err(Error::MissingRangeBounds);
+                };
+                let step = get_number("step")?;
+                // NaN compares false with everything, so it has to be ruled out separately
+                if !min.is_finite()
+                    || !max.is_finite()
+                    || min >= max
+                    || step.is_some_and(|step| !step.is_finite() || step <= 0.0)
+                {
+                    return Err(Error::InvalidRangeBounds { min, max, step });
+                }
+                let default = suggested_answer
+                    .map(|value| {
+                        value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|number| range_error(*number, min, max, step).is_none())
+                            .ok_or(Error::InvalidRangeDefault { value })
+                    })
+                    .transpose()?;
+
+                Question::Range {
+                    prompt: question_body,
+                    default,
+                    min,
+                    max,
+                    step,
+                    required,
+                    common,
+                }
+            }
+            "color" => {
+                let default = suggested_answer
+                    .map(|value| Color::parse(&value).ok_or(Error::InvalidColorDefault { value }))
+                    .transpose()?;
+
+                Question::Color {
+                    prompt: question_body,
+                    default,
+                    required,
+                    common,
+                }
+            }
+            "decimal" => {
+                let precision: Option<u32> = question_table.get("precision").map_err(|err| {
+                    Error::InvalidDecimalProperty {
+                        property: "precision",
+                        source: err,
+                    }
+                })?;
+                let precision = precision.unwrap_or(2);
+                if precision > MAX_DECIMAL_PRECISION {
+                    return Err(Error::InvalidDecimalPrecision { precision });
+                }
+                let currency: Option<String> = question_table.get("currency").map_err(|err| {
+                    Error::InvalidDecimalProperty {
+                        property: "currency",
+                        source: err,
+                    }
+                })?;
+                // Currency codes are three letters (from ISO 4217), which we'll keep in upper case
+                let currency = currency
+                    .map(|currency| {
+                        if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic())
+                        {
+                            Ok(currency.to_ascii_uppercase())
+                        } else {
+                            Err(Error::InvalidCurrency { currency })
+                        }
+                    })
+                    .transpose()?;
+                // Scripts should give these as strings, because Lua numbers are floats (but
+                // numbers that happen to be exact will work)
+                let parse = |property: &'static str, value: String| {
+                    Decimal::parse(&value)
+                        .and_then(|decimal| decimal.with_scale(precision))
+                        .ok_or(Error::InvalidDecimalValue { property, value })
+                };
+                let get_bound = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<String>>(property)
+                        .map_err(|err| Error::InvalidDecimalProperty {
+                            property,
+                            source: err,
+                        })?
+                        .map(|value| parse(property, value))
+                        .transpose()
+                };
+                let (min, max) = (get_bound("min")?, get_bound("max")?);
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min.units() > max.units() {
+                        return Err(Error::InvalidDecimalRange {
+                            min: min.to_string(),
+                            max: max.to_string(),
+                        });
+                    }
+                }
+                let default = suggested_answer
+                    .map(|value| parse("default", value))
+                    .transpose()?;
+
+                Question::Decimal {
+                    prompt: question_body,
+                    default,
+                    precision,
+                    currency,
+                    min,
+                    max,
+                    required,
+                    common,
+                }
+            }
+            // Without labels, this is the usual five-point agreement scale
+            "likert" => {
+                let labels: Option<Vec<String>> = question_table
+                    .get("labels")
+                    .map_err(|err| Error::InvalidLikertLabels { source: err })?;
+                let labels = labels.unwrap_or_else(|| {
+                    DEFAULT_LIKERT_LABELS
+                        .iter()
+                        .map(|label| label.to_string())
+                        .collect()
+                });
+                if labels.len() < 2 {
+                    return Err(Error::TooFewLikertLabels {
+                        count: labels.len(),
+                    });
+                }
+                let default = suggested_answer
+                    .map(|value| {
+                        likert_point(&labels, &value).ok_or(Error::InvalidLikertDefault { value })
+                    })
+                    .transpose()?;
+
+                Question::Likert {
+                    prompt: question_body,
+                    default,
+                    labels,
+                    required,
+                    common,
+                }
+            }
+            "rating" => {
+                let get_bound = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<u32>>(property)
+                        .map_err(|err| Error::InvalidRatingProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let min = get_bound("min")?.unwrap_or(1);
+                let max = get_bound("max")?.unwrap_or(5);
+                if min > max {
+                    return Err(Error::InvalidRatingRange { min, max });
+                }
+                let get_label = |property: &'static str| {
+                    question_table
+                        .get::<_, Option<String>>(property)
+                        .map_err(|err| Error::InvalidRatingProperty {
+                            property,
+                            source: err,
+                        })
+                };
+                let default = suggested_answer
+                    .map(|value| {
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|rating| (min..=max).contains(rating))
+                            .ok_or(Error::InvalidRatingDefault { value })
+                    })
+                    .transpose()?;
+
+                Question::Rating {
+                    prompt: question_body,
+                    default,
+                    min,
+                    max,
+                    min_label: get_label("min_label")?,
+                    max_label: get_label("max_label")?,
+                    required,
+                    common,
+                }
+            }
+            "file" => {
+                let multiple: Option<bool> = question_table
+                    .get("multiple")
+                    .map_err(|_| Error::InvalidMultipleProperty)?;
+                let extensions: Option<Vec<String>> = question_table
+                    .get("extensions")
+                    .map_err(|err| Error::InvalidExtensionsProperty { source: err })?;
+                // Scripts can give extensions with or without the dot
+                let extensions = extensions
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                    .collect();
+
+                Question::File {
+                    prompt: question_body,
+                    extensions,
+                    multiple: multiple.unwrap_or(false),
+                    required,
+                    common,
+                }
+            }
+            _ => {
+                return Err(Error::InvalidQuestionType {
+                    ty: question_type.to_string(),
+                })
+            }
+        };
+
+        Ok(question)
+    }
+}
+
+/// The different types of questions that can be asked. These are fairly generic, as Kylie knows
+/// nothing about the contents of boxes. This allows significant flexibility, and delegates
+/// complexity to box handlers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Question {
+    /// A simple question that requires a single-line answer. This would correspond in HTML to a
+    /// single `<input>`.
+    Simple {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<String>,
+        /// A regular expression the whole answer must match, which the engine will check before
+        /// the script sees it.
+        pattern: Option<String>,
+        /// A message to show the user if their answer doesn't match the pattern (e.g. "Please
+        /// enter a postcode like SW1A 1AA.").
+        pattern_hint: Option<String>,
+        /// The fewest characters the answer can have, which the engine will check before the
+        /// script sees it.
+        min_length: Option<usize>,
+        /// The most characters the answer can have, which the engine will likewise check.
+        max_length: Option<usize>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A simple question that requires a multiline answer. This would correspond in HTML to a
+    /// `<textarea>`.
+    Multiline {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<String>,
+        /// A regular expression the whole answer must match, which the engine will check before
+        /// the script sees it.
+        pattern: Option<String>,
+        /// A message to show the user if their answer doesn't match the pattern.
+        pattern_hint: Option<String>,
+        /// The fewest characters the answer can have, which the engine will check before the
+        /// script sees it.
+        min_length: Option<usize>,
+        /// The most characters the answer can have, which the engine will likewise check.
+        max_length: Option<usize>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires an email address as an answer, which the engine will check the
+    /// syntax of before the script sees it. This would correspond in HTML to an
+    /// `<input type="email">`.
+    Email {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<String>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a phone number as an answer, which the engine will normalise to
+    /// E.164 format (e.g. `+442079460958`) before the script sees it. This would correspond in HTML
+    /// to an `<input type="tel">`.
+    Phone {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer, in E.164 format.
+        default: Option<String>,
+        /// The region numbers are assumed to be in if the user doesn't give a country code, as an
+        /// ISO 3166-1 alpha-2 code in uppercase (e.g. `GB`). If this is `None`, the user must
+        /// always give a country code.
+        region: Option<String>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires an absolute URL as an answer, which the engine will check before
+    /// the script sees it. This would correspond in HTML to an `<input type="url">`.
+    Url {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<String>,
+        /// The schemes the URL can have (e.g. `https`), in lowercase. If this is empty, any scheme
+        /// is allowed.
+        schemes: Vec<String>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a single-line answer which should be kept secret, like a password
+    /// or an access token. Interfaces should mask the user's input. This would correspond in HTML
+    /// to an `<input type="password">`.
+    Secret {
+        /// The prompt for the question.
+        prompt: String,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question where the user can select their answer from a list.
+    Select {
+        /// The question being asked.
+        prompt: String,
+        /// The options that should be selected to start with. Each of these is guaranteed to be
+        /// the value of one of the options (except for at most one if the user can give their own
+        /// value), and there will be at most one unless `multiple` is set.
+        defaults: Vec<String>,
+        /// A list of options the user can take. Answers should contain the values of these.
+        options: Vec<SelectOption>,
+        /// Whether or not the user can select multiple options.
+        multiple: bool,
+        /// Whether or not the user can give a value of their own, which isn't one of the options
+        /// (like an "Other" option with a text box). Answers can then contain one value that isn't
+        /// an option's value (see [`Question::is_other`]).
+        allow_other: bool,
+        /// Whether or not interfaces should let the user search the options (e.g. with a
+        /// combobox), because there are a lot of them. This is only a hint, and doesn't change how
+        /// answers are validated.
+        searchable: bool,
+        /// The fewest options the user must select (including their own value, if they give
+        /// one), which the engine will check before the script sees the answer.
+        min_selected: Option<usize>,
+        /// The most options the user can select, which the engine will likewise check.
+        max_selected: Option<usize>,
+        /// A question to ask in the same step if the user chooses certain options, in which case
+        /// the answer will be [`Answer::WithFollowUp`].
+        followup: Option<FollowUp>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question where the user puts a list of options in order of priority.
+    Rank {
+        /// The question being asked.
+        prompt: String,
+        /// The options the user must put in order, in the order they should initially be shown.
+        /// Answers should contain the values of all of these, most important first.
+        options: Vec<SelectOption>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// Not really a question, but a step that just shows the user some information (e.g. an
+    /// explanation between stages of a form), which they acknowledge to continue. The only valid
+    /// answer to this is [`Answer::Acknowledged`].
+    Info {
+        /// The information to show the user.
+        prompt: String,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// Not really a question either, but a step whose answer the script has worked out itself
+    /// (e.g. a score, or something looked up from earlier answers), so that it's recorded in the
+    /// history alongside the user's answers. The form answers these with
+    /// [`Answer::Computed`] as soon as the script asks them, so interfaces will generally only
+    /// see them among the questions already answered.
+    Computed {
+        /// A description of the value (e.g. `Risk score`).
+        prompt: String,
+        /// The value the script worked out.
+        value: Value,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// Several questions that should be answered together (e.g. the lines of an address), which
+    /// interfaces should show on one page. The only valid answer to this is [`Answer::Page`].
+    Page {
+        /// A heading for the page.
+        prompt: String,
+        /// The questions on the page, in order.
+        questions: Vec<PageQuestion>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question where the user adds any number of entries, each of which has the same fields
+    /// (e.g. the name and age of each of their dependents).
+    Group {
+        /// The question being asked.
+        prompt: String,
+        /// The fields in each entry.
+        fields: Vec<GroupField>,
+        /// The fewest entries the user can give, if there's a minimum.
+        min: Option<usize>,
+        /// The most entries the user can give, if there's a maximum.
+        max: Option<usize>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a date as an answer. This would correspond in HTML to an
+    /// `<input type="date">`. Dates are given by scripts in the format `YYYY-MM-DD`.
+    Date {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<NaiveDate>,
+        /// The earliest date the user can give, if there is one.
+        min: Option<NaiveDate>,
+        /// The latest date the user can give, if there is one.
+        max: Option<NaiveDate>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a time of day as an answer. This would correspond in HTML to an
+    /// `<input type="time">`. Times are given by scripts in the format `HH:MM` or `HH:MM:SS`.
+    Time {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<NaiveTime>,
+        /// The earliest time the user can give, if there is one.
+        min: Option<NaiveTime>,
+        /// The latest time the user can give, if there is one.
+        max: Option<NaiveTime>,
+        /// Whether or not the user can give seconds as well as hours and minutes.
+        seconds: bool,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a number within a range as an answer, which would usually be shown
+    /// as a slider. This would correspond in HTML to an `<input type="range">`.
+    Range {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<f64>,
+        /// The lowest number the user can give.
+        min: f64,
+        /// The highest number the user can give.
+        max: f64,
+        /// The increments the user's answer must be in (counting from `min`), if there are any.
+        step: Option<f64>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a colour as an answer. This would correspond in HTML to an
+    /// `<input type="color">`. The only valid answer to this is [`Answer::Color`], which
+    /// interfaces can also give as [`Answer::Text`] in hex (e.g. `#ff8800`) or RGB (e.g.
+    /// `rgb(255, 136, 0)`), which will be parsed into it.
+    Color {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<Color>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires an exact decimal number as an answer, like an amount of money.
+    /// The only valid answer to this is [`Answer::Decimal`], which interfaces can also give as
+    /// [`Answer::Text`] (e.g. `1,234.50`), which will be parsed into it.
+    Decimal {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<Decimal>,
+        /// How many digits the user can give after the decimal point. Answers will always have
+        /// exactly this many (e.g. `12.5` will become `12.50` if this is 2).
+        precision: u32,
+        /// The currency the number is an amount of, as an upper-case ISO 4217 code (e.g. `AUD`),
+        /// if it's an amount of money.
+        currency: Option<String>,
+        /// The lowest number the user can give.
+        min: Option<Decimal>,
+        /// The highest number the user can give.
+        max: Option<Decimal>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a rating on a numeric scale as an answer (e.g. 1 to 5 stars, or 0
+    /// to 10 with labelled ends).
+    Rating {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<u32>,
+        /// The lowest rating the user can give.
+        min: u32,
+        /// The highest rating the user can give.
+        max: u32,
+        /// A label for the lowest end of the scale (e.g. "Not at all likely"), if there is one.
+        min_label: Option<String>,
+        /// A label for the highest end of the scale (e.g. "Extremely likely"), if there is one.
+        max_label: Option<String>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question where the user chooses a point on a scale with a label for every point (e.g.
+    /// "Strongly disagree" to "Strongly agree"), which interfaces should lay out horizontally, as
+    /// is conventional in surveys. The only valid answer to this is [`Answer::Rating`], with the
+    /// point chosen (starting from 1), though interfaces can also answer with text, which can be
+    /// one of the labels, or the number of the point.
+    Likert {
+        /// The prompt for the question (usually a statement the user agrees or disagrees with).
+        prompt: String,
+        /// A default suggested answer, as a point on the scale.
+        default: Option<u32>,
+        /// The label for each point on the scale, in order. There are always at least two.
+        labels: Vec<String>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires one or more files as an answer. This would correspond in HTML to an
+    /// `<input type="file">`. Interfaces are responsible for making sure the files exist.
+    File {
+        /// The prompt for the question.
+        prompt: String,
+        /// The extensions files must have (lowercase, without the leading dot). If this is empty,
+        /// any file can be given.
+        extensions: Vec<String>,
+        /// Whether or not the user can give multiple files.
+        multiple: bool,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+    /// A question that requires a date and time as an answer. This would correspond in HTML to an
+    /// `<input type="datetime-local">`. These are given by scripts in RFC 3339 format, or as
+    /// `YYYY-MM-DD HH:MM` in the question's timezone.
+    DateTime {
+        /// The prompt for the question.
+        prompt: String,
+        /// A default suggested answer.
+        default: Option<DateTime<FixedOffset>>,
+        /// The earliest date and time the user can give, if there is one.
+        min: Option<DateTime<FixedOffset>>,
+        /// The latest date and time the user can give, if there is one.
+        max: Option<DateTime<FixedOffset>>,
+        /// The timezone the user is expected to answer in, if the script gave one. Answers without
+        /// an offset will be taken to be in this timezone (or UTC if there isn't one), and all
+        /// answers will be converted to it.
+        #[serde(with = "crate::resume::optional_offset")]
+        timezone: Option<FixedOffset>,
+        /// Whether or not the user has to answer this question, rather than skipping it (see
+        /// [`Question::is_required`]).
+        required: bool,
+        /// The properties every question has, like its tags (see [`QuestionCommon`]).
+        #[serde(flatten)]
+        common: QuestionCommon,
+    },
+}
+impl Question {
+    /// Gets the prompt of this question, whatever type of question it is.
+    pub fn prompt(&self) -> &str {
+        match self {
+            Question::Simple { prompt, .. }
+            | Question::Multiline { prompt, .. }
+            | Question::Email { prompt, .. }
+            | Question::Phone { prompt, .. }
+            | Question::Url { prompt, .. }
+            | Question::Secret { prompt, .. }
+            | Question::Select { prompt, .. }
+            | Question::Rank { prompt, .. }
+            | Question::Group { prompt, .. }
+            | Question::Info { prompt, .. }
+            | Question::Computed { prompt, .. }
+            | Question::Page { prompt, .. }
+            | Question::Date { prompt, .. }
+            | Question::Time { prompt, .. }
+            | Question::DateTime { prompt, .. }
+            | Question::File { prompt, .. }
+            | Question::Rating { prompt, .. }
+            | Question::Likert { prompt, .. }
+            | Question::Range { prompt, .. }
+            | Question::Decimal { prompt, .. }
+            | Question::Color { prompt, .. } => prompt,
+        }
+    }
+    /// Gets the properties this question has whatever type of question it is (other than its
+    /// prompt).
+    pub fn common(&self) -> &QuestionCommon {
+        match self {
+            Question::Simple { common, .. }
+            | Question::Multiline { common, .. }
+            | Question::Email { common, .. }
+            | Question::Phone { common, .. }
+            | Question::Url { common, .. }
+            | Question::Secret { common, .. }
+            | Question::Select { common, .. }
+            | Question::Rank { common, .. }
+            | Question::Group { common, .. }
+            | Question::Info { common, .. }
+            | Question::Computed { common, .. }
+            | Question::Page { common, .. }
+            | Question::Date { common, .. }
+            | Question::Time { common, .. }
+            | Question::DateTime { common, .. }
+            | Question::File { common, .. }
+            | Question::Rating { common, .. }
+            | Question::Likert { common, .. }
+            | Question::Range { common, .. }
+            | Question::Decimal { common, .. }
+            | Question::Color { common, .. } => common,
+        }
+    }
+    /// Gets the tags the script gave this question, whatever type of question it is. These are
+    /// arbitrary strings that classify questions, so interfaces can treat some differently (e.g.
+    /// redacting the answers to all questions tagged `pii`).
+    pub fn tags(&self) -> &[String] {
+        &self.common().tags
+    }
+    /// Gets the help text the script gave this question, whatever type of question it is. This is
+    /// secondary text explaining the question (e.g. why it's being asked, or where to find the
+    /// answer), which interfaces should show under the prompt, less prominently.
+    pub fn help(&self) -> Option<&str> {
+        self.common().help.as_deref()
+    }
+    /// Checks whether or not the user has to answer this question. Questions are required unless
+    /// the script says otherwise, and ones that aren't can be answered with [`Answer::Skipped`].
+    /// Information and computed steps are always required.
+    pub fn is_required(&self) -> bool {
+        match self {
+            // There's nothing to skip in these
+            Question::Info { .. } | Question::Computed { .. } => true,
+            Question::Simple { required, .. }
+            | Question::Multiline { required, .. }
+            | Question::Email { required, .. }
+            | Question::Phone { required, .. }
+            | Question::Url { required, .. }
+            | Question::Secret { required, .. }
+            | Question::Select { required, .. }
+            | Question::Rank { required, .. }
+            | Question::Group { required, .. }
+            | Question::Page { required, .. }
+            | Question::Date { required, .. }
+            | Question::Time { required, .. }
+            | Question::DateTime { required, .. }
+            | Question::File { required, .. }
+            | Question::Rating { required, .. }
+            | Question::Likert { required, .. }
+            | Question::Range { required, .. }
+            | Question::Decimal { required, .. }
+            | Question::Color { required, .. } => *required,
+        }
+    }
+    /// Gets how the prompt of this question is written, whatever type of question it is. Scripts
+    /// can give `format = "markdown"` to have interfaces render their prompts as markdown, which
+    /// is useful for emphasis, lists, and links (e.g. in information steps).
+    pub fn format(&self) -> PromptFormat {
+        self.common().format
+    }
+    /// Checks whether or not this question has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().iter().any(|t| t == tag)
+    }
+    /// Checks whether or not the given selected value in an answer to this question is the user's
+    /// own value, rather than one of the question's options. This can only be the case for
+    /// select-type questions that allow other values.
+    pub fn is_other(&self, value: &str) -> bool {
+        match self {
+            Question::Select {
+                options,
+                allow_other: true,
+                ..
+            } => !options.iter().any(|option| option.value == value),
+            _ => false,
+        }
+    }
+    /// Checks whether or not answers to this question are sensitive, meaning they should be kept
+    /// out of logs. This is the case for [`Question::Secret`], any question tagged with
+    /// [`SECRET_TAG`], any page with a secret question on it, and any select question with a
+    /// secret follow-up.
+    pub fn is_secret(&self) -> bool {
+        match self {
+            Question::Secret { .. } => true,
+            Question::Page { questions, .. } => {
+                self.has_tag(SECRET_TAG) || questions.iter().any(|item| item.question.is_secret())
+            }
+            Question::Select {
+                followup: Some(followup),
+                ..
+            } => self.has_tag(SECRET_TAG) || followup.question.is_secret(),
+            _ => self.has_tag(SECRET_TAG),
+        }
+    }
+    /// Checks whether or not answers to this question are locked once given (e.g. for legally
+    /// significant consents). Once a readonly question has been answered, the form will refuse
+    /// to change that answer with [`Error::AnswerLocked`], so interfaces should show it as locked.
+    pub fn is_readonly(&self) -> bool {
+        self.common().readonly
+    }
+}
+
+/// The user's answer to a question. This contains no information about the question it answers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Answer {
+    /// A textual answer. This will come to [`Question::Simple`], [`Question::Multiline`],
+    /// [`Question::Email`], [`Question::Phone`], or [`Question::Url`].
+    Text(String),
+    /// A secret textual answer, which will come as a response to [`Question::Secret`]. Interfaces
+    /// can also answer these questions with [`Answer::Text`], which will be wrapped into this, so
+    /// the answer will be redacted in `Debug` output from then on.
+    Secret(SecretText),
+    /// An answer in terms of a series of given options. These are *guaranteed* to be valid with
+    /// respect to the options offered in the relevant question, and will come as a response to
+    /// [`Question::Select`] or [`Question::Rank`] (in which case they'll be in the user's order).
     Options(Vec<String>),
+    /// An answer to a [`Question::Select`] where some of the options chosen need text to go with
+    /// them (see [`SelectOption::specify`]). Each selection is given with its text, which must be
+    /// there for exactly those options that need it (and will be trimmed). Selections that don't
+    /// include any such options will be given as [`Answer::Options`] instead, and interfaces can
+    /// answer with either.
+    OptionsWithText(Vec<(String, Option<String>)>),
+    /// An answer to a [`Question::Select`] whose follow-up question was asked (see
+    /// [`FollowUp`]), along with the answer to that. The selection is an [`Answer::Options`] or
+    /// [`Answer::OptionsWithText`], and interfaces should only answer with this if the options
+    /// chosen bring up the follow-up. If the follow-up isn't required, it can be answered with
+    /// [`Answer::Skipped`] (or left out, by answering with just the selection).
+    WithFollowUp {
+        /// The selection.
+        answer: Box<Answer>,
+        /// The answer to the follow-up question.
+        followup: Box<Answer>,
+    },
+    /// A date, which will come as a response to [`Question::Date`]. Interfaces can also answer
+    /// these questions with [`Answer::Text`] in the format `YYYY-MM-DD`, which will be parsed into
+    /// this.
+    Date(NaiveDate),
+    /// A time of day, which will come as a response to [`Question::Time`]. Interfaces can also
+    /// answer these questions with [`Answer::Text`] in the format `HH:MM` (or `HH:MM:SS`, if the
+    /// question allows seconds), which will be parsed into this.
+    Time(NaiveTime),
+    /// A date and time, which will come as a response to [`Question::DateTime`]. Interfaces can
+    /// also answer these questions with [`Answer::Text`] in RFC 3339 format, or as
+    /// `YYYY-MM-DD HH:MM` in the question's timezone, which will be parsed into this.
+    DateTime(DateTime<FixedOffset>),
+    /// Paths to files, which will come as a response to [`Question::File`]. Interfaces that can
+    /// only give text or lists of text (e.g. answer files) can also answer these questions with
+    /// [`Answer::Text`] for a single path or [`Answer::Options`] for several, which will be
+    /// converted into this.
+    Files(Vec<PathBuf>),
+    /// A rating, which will come as a response to [`Question::Rating`], or a point on the scale of
+    /// a [`Question::Likert`] (starting from 1). Interfaces can also answer these questions with
+    /// [`Answer::Text`] containing the number (or one of the labels of a Likert scale), which will
+    /// be parsed into this.
+    Rating(u32),
+    /// A number, which will come as a response to [`Question::Range`]. Interfaces can also answer
+    /// these questions with [`Answer::Text`] containing the number, which will be parsed into this.
+    Range(f64),
+    /// An exact decimal number, which will come as a response to [`Question::Decimal`], at the
+    /// question's precision. Interfaces can also answer these questions with [`Answer::Text`]
+    /// containing the number, which will be parsed into this.
+    Decimal(Decimal),
+    /// A colour, which will come as a response to [`Question::Color`]. Interfaces can also answer
+    /// these questions with [`Answer::Text`] in hex or RGB, which will be parsed into this.
+    Color(Color),
+    /// A list of entries, which will come as a response to [`Question::Group`]. Each entry maps
+    /// the IDs of the question's fields to their values, and fields that weren't filled in can be
+    /// left out.
+    Group(Vec<HashMap<String, String>>),
+    /// An acknowledgement that the user has seen the information in a [`Question::Info`].
+    Acknowledged,
+    /// The value of a [`Question::Computed`], which the form gives itself. This must be exactly
+    /// the value the script gave.
+    Computed(Value),
+    /// Answers to each of the questions on a [`Question::Page`], keyed by their IDs. There must be
+    /// an answer to every question on the page.
+    Page(HashMap<String, Answer>),
+    /// No answer at all, because the user chose to skip the question. This can answer any question
+    /// that isn't required (see [`Question::is_required`]).
+    Skipped,
 }
 impl Answer {
-    /// Converts this answer into a Lua-friendly representation. This will produce a Lua table of
-    /// the form `{ type = "text", text = "..." }` or `{ type = "options", selected = { ... } }`,
-    /// depending on the type of question this is in answer to.
+    /// Checks whether or not this answer is the same as the given one, ignoring case in text
+    /// answers. Selections must be exactly the same. This can be used for
+    /// [`FormOptions::answer_eq`].
+    pub fn eq_ignore_case(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a.to_lowercase() == b.to_lowercase(),
+            (
+                Answer::WithFollowUp {
+                    answer: a,
+                    followup: a_followup,
+                },
+                Answer::WithFollowUp {
+                    answer: b,
+                    followup: b_followup,
+                },
+            ) => a == b && a_followup.eq_ignore_case(b_followup),
+            (Answer::Page(a), Answer::Page(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(id, a)| b.get(id).is_some_and(|b| a.eq_ignore_case(b)))
+            }
+            _ => self == other,
+        }
+    }
+    /// Checks whether or not this answer is the same as the given one, ignoring leading and
+    /// trailing whitespace in text answers, and treating any run of whitespace within them as a
+    /// single space. Selections must be exactly the same. This can be used for
+    /// [`FormOptions::answer_eq`].
+    pub fn eq_ignore_whitespace(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a.split_whitespace().eq(b.split_whitespace()),
+            (
+                Answer::WithFollowUp {
+                    answer: a,
+                    followup: a_followup,
+                },
+                Answer::WithFollowUp {
+                    answer: b,
+                    followup: b_followup,
+                },
+            ) => a == b && a_followup.eq_ignore_whitespace(b_followup),
+            (Answer::Page(a), Answer::Page(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(id, a)| b.get(id).is_some_and(|b| a.eq_ignore_whitespace(b)))
+            }
+            _ => self == other,
+        }
+    }
+    /// Converts this answer into a Lua-friendly representation, a table whose `type` depends on the
+    /// type of question this is in answer to:
+    ///
+    /// - `{ type = "text", text = "..." }` (secrets are given to scripts as text too)
+    /// - `{ type = "options", selected = { ... } }`, with `details` keyed by option for options
+    ///   that need them
+    /// - `{ type = "date", date = "YYYY-MM-DD" }`
+    /// - `{ type = "time", time = "HH:MM:SS", hour = ..., minute = ..., second = ... }`
+    /// - `{ type = "datetime", datetime = "..." }` (in RFC 3339 format)
+    /// - `{ type = "files", files = { ... } }`
+    /// - `{ type = "rating", rating = ... }`
+    /// - `{ type = "range", value = ... }`
+    /// - `{ type = "decimal", decimal = "..." }` (as a string, so it stays exact)
+    /// - `{ type = "color", color = "#rrggbb", red = ..., green = ..., blue = ... }`
+    /// - `{ type = "group", entries = { ... } }` (with a table of field values for each entry)
+    /// - `{ type = "acknowledged" }`
+    /// - `{ type = "computed", value = ... }`
+    /// - `{ type = "skipped" }`
+    /// - `{ type = "page", answers = { ... } }` (with an answer table like these for each
+    ///   question)
+    ///
+    /// Selections with follow-ups also have the follow-up's answer table as `followup`.
     ///
     /// # Errors
     ///
@@ -523,12 +2661,921 @@ impl Answer {
                 answer_table.set("type", "text")?;
                 answer_table.set("text", text.as_str())?;
             }
+            Answer::Secret(secret) => {
+                answer_table.set("type", "text")?;
+                answer_table.set("text", secret.expose())?;
+            }
             Answer::Options(options) => {
                 answer_table.set("type", "options")?;
                 answer_table.set("selected", options.clone())?;
             }
+            // These look just like other selections to scripts, with the details keyed by option
+            Answer::OptionsWithText(selected) => {
+                answer_table.set("type", "options")?;
+                answer_table.set(
+                    "selected",
+                    selected
+                        .iter()
+                        .map(|(option, _)| option.as_str())
+                        .collect::<Vec<_>>(),
+                )?;
+                let details_table = lua_vm.create_table()?;
+                for (option, text) in selected {
+                    if let Some(text) = text {
+                        details_table.set(option.as_str(), text.as_str())?;
+                    }
+                }
+                answer_table.set("details", details_table)?;
+            }
+            // These look just like the selection, with the follow-up's answer table alongside
+            Answer::WithFollowUp { answer, followup } => {
+                let answer_table = answer.to_lua(lua_vm)?;
+                answer_table.set("followup", followup.to_lua(lua_vm)?)?;
+                return Ok(answer_table);
+            }
+            Answer::Date(date) => {
+                answer_table.set("type", "date")?;
+                answer_table.set("date", date.to_string())?;
+            }
+            Answer::Time(time) => {
+                answer_table.set("type", "time")?;
+                answer_table.set("time", time.format("%H:%M:%S").to_string())?;
+                answer_table.set("hour", time.hour())?;
+                answer_table.set("minute", time.minute())?;
+                answer_table.set("second", time.second())?;
+            }
+            Answer::Range(value) => {
+                answer_table.set("type", "range")?;
+                answer_table.set("value", *value)?;
+            }
+            Answer::Color(color) => {
+                answer_table.set("type", "color")?;
+                answer_table.set("color", color.to_string())?;
+                answer_table.set("red", color.red)?;
+                answer_table.set("green", color.green)?;
+                answer_table.set("blue", color.blue)?;
+            }
+            // This is given as a string, so scripts don't lose precision by accident
+            Answer::Decimal(decimal) => {
+                answer_table.set("type", "decimal")?;
+                answer_table.set("decimal", decimal.to_string())?;
+            }
+            Answer::Group(entries) => {
+                answer_table.set("type", "group")?;
+                answer_table.set("entries", entries.clone())?;
+            }
+            Answer::Acknowledged => {
+                answer_table.set("type", "acknowledged")?;
+            }
+            Answer::Computed(value) => {
+                answer_table.set("type", "computed")?;
+                answer_table.set("value", lua_vm.to_value(value)?)?;
+            }
+            Answer::Skipped => {
+                answer_table.set("type", "skipped")?;
+            }
+            Answer::Page(answers) => {
+                answer_table.set("type", "page")?;
+                let answers_table = lua_vm.create_table()?;
+                for (id, answer) in answers {
+                    answers_table.set(id.as_str(), answer.to_lua(lua_vm)?)?;
+                }
+                answer_table.set("answers", answers_table)?;
+            }
+            Answer::Rating(rating) => {
+                answer_table.set("type", "rating")?;
+                answer_table.set("rating", *rating)?;
+            }
+            Answer::Files(paths) => {
+                answer_table.set("type", "files")?;
+                answer_table.set(
+                    "files",
+                    paths
+                        .iter()
+                        .map(|path| path.to_string_lossy())
+                        .collect::<Vec<_>>(),
+                )?;
+            }
+            Answer::DateTime(datetime) => {
+                answer_table.set("type", "datetime")?;
+                answer_table.set(
+                    "datetime",
+                    datetime.to_rfc3339_opts(SecondsFormat::Secs, false),
+                )?;
+            }
         };
 
         Ok(answer_table)
     }
+    /// Same as [`Self::to_lua`], but this also sets the `locale` property of the table to the
+    /// locale the answer was given in, if there was one.
+    fn to_lua_in_locale<'l>(
+        &self,
+        lua_vm: &'l Lua,
+        locale: Option<&str>,
+    ) -> Result<Table<'l>, mlua::Error> {
+        let answer_table = self.to_lua(lua_vm)?;
+        answer_table.set("locale", locale)?;
+
+        Ok(answer_table)
+    }
+}
+
+/// Checks the given answer to the given question, returning it in the form the script should see
+/// (e.g. with dates parsed). If the answer is the wrong type for the question, this will return an
+/// error, but if the user just needs to fix it, this will return `Ok(Err(message))`.
+fn check_answer(
+    question: &Question,
+    mut answer: Answer,
+    form_options: &FormOptions,
+) -> Result<Result<Answer, String>, Error> {
+    // Any question that isn't required can be skipped, and there's nothing else to check then
+    if answer == Answer::Skipped {
+        return if question.is_required() {
+            Err(Error::InvalidAnswerType {
+                expected: "an answer (not a skip) for required question",
+            })
+        } else {
+            Ok(Ok(answer))
+        };
+    }
+    match question {
+        Question::Simple {
+            pattern,
+            pattern_hint,
+            min_length,
+            max_length,
+            ..
+        }
+        | Question::Multiline {
+            pattern,
+            pattern_hint,
+            min_length,
+            max_length,
+            ..
+        } => {
+            let Answer::Text(text) = &answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "text for simple/multiline question",
+                });
+            };
+            // Lengths are in characters, which is what users will count
+            let length = text.chars().count();
+            if let Some(min) = min_length.filter(|min| length < *min) {
+                return Ok(Err(format!(
+                    "Please enter at least {min} characters (you've entered {length})."
+                )));
+            }
+            if let Some(max) = max_length.filter(|max| length > *max) {
+                return Ok(Err(format!(
+                    "Please enter at most {max} characters (you've entered {length})."
+                )));
+            }
+            if let Some(pattern) = pattern {
+                if !whole_match_regex(pattern)?.is_match(text) {
+                    return Ok(Err(pattern_hint.clone().unwrap_or_else(|| {
+                        "Please enter an answer in the expected format.".to_string()
+                    })));
+                }
+            }
+        }
+        Question::Email { .. } => {
+            let Answer::Text(text) = &answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "text for email question",
+                });
+            };
+            let address = text.trim();
+            if !is_valid_email(address) {
+                return Ok(Err("Please enter a valid email address.".to_string()));
+            }
+            answer = Answer::Text(address.to_string());
+        }
+        Question::Phone { region, .. } => {
+            let Answer::Text(text) = &answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "text for phone question",
+                });
+            };
+            match normalize_phone(text, region.as_deref()) {
+                Ok(number) => answer = Answer::Text(number),
+                Err(msg) => return Ok(Err(msg)),
+            }
+        }
+        Question::Url { schemes, .. } => {
+            let Answer::Text(text) = &answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "text for url question",
+                });
+            };
+            let text = text.trim();
+            // This will fail for relative URLs, because there's nothing to resolve them against
+            let Ok(url) = Url::parse(text) else {
+                return Ok(Err(
+                    "Please enter a full URL, like https://example.com.".to_string()
+                ));
+            };
+            if !schemes.is_empty() && !schemes.iter().any(|scheme| scheme == url.scheme()) {
+                return Ok(Err(format!(
+                    "Please enter a URL using one of these schemes: {}.",
+                    schemes.join(", ")
+                )));
+            }
+            answer = Answer::Text(text.to_string());
+        }
+        // Make sure secrets won't be leaked by anyone debugging the form
+        Question::Secret { .. } => match answer {
+            Answer::Secret(_) => {}
+            Answer::Text(text) => answer = Answer::Secret(SecretText::from(text)),
+            _ => {
+                return Err(Error::InvalidAnswerType {
+                    expected: "secret or text for secret question",
+                })
+            }
+        },
+        Question::Select {
+            options,
+            multiple,
+            allow_other,
+            min_selected,
+            max_selected,
+            followup,
+            ..
+        } => {
+            // The answer to any follow-up is checked once we know whether it was asked
+            let (selection, followup_answer) = match answer {
+                Answer::WithFollowUp { answer, followup } => (*answer, Some(*followup)),
+                answer => (answer, None),
+            };
+            // Options that need text come with it, and others can come with nothing
+            let (selected, texts): (Vec<String>, Vec<Option<String>>) = match selection {
+                Answer::Options(selected) => {
+                    let texts = vec![None; selected.len()];
+                    (selected, texts)
+                }
+                Answer::OptionsWithText(selected) => selected.into_iter().unzip(),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "options for select question",
+                    })
+                }
+            };
+            if !*multiple && selected.len() > 1 {
+                return Err(Error::InvalidAnswerType {
+                    expected: "single option for non-multiple select question",
+                });
+            }
+            // Match each selection to an option, using the host's normalisation if there's no
+            // exact match, so the script only ever sees the options it gave (and anything else
+            // must be the user's own value)
+            let mut others = Vec::new();
+            let canonical = selected
+                .iter()
+                .map(
+                    |s| match match_option(options, s, form_options.option_normalize) {
+                        Some(option) => Some(option.value.clone()),
+                        None if *allow_other => {
+                            let other = s.trim().to_string();
+                            others.push(other.clone());
+                            Some(other)
+                        }
+                        None => None,
+                    },
+                )
+                .collect::<Option<Vec<_>>>();
+            if others.len() > 1 {
+                return Err(Error::InvalidAnswerType {
+                    expected: "at most one other value for select question",
+                });
+            }
+            let Some(canonical) = canonical else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "all options to be valid",
+                });
+            };
+            // Differently written selections of the same option would otherwise count twice
+            if canonical
+                .iter()
+                .enumerate()
+                .any(|(idx, value)| canonical[..idx].contains(value))
+            {
+                return Err(Error::InvalidAnswerType {
+                    expected: "each option at most once for select question",
+                });
+            }
+            if others.iter().any(|other| other.is_empty()) {
+                return Ok(Err("Please fill in your own answer.".to_string()));
+            }
+            if let Some(min) = min_selected.filter(|min| canonical.len() < *min) {
+                return Ok(Err(match min {
+                    1 => "Please choose at least 1 option.".to_string(),
+                    min => format!("Please choose at least {min} options."),
+                }));
+            }
+            if let Some(max) = max_selected.filter(|max| canonical.len() > *max) {
+                return Ok(Err(match max {
+                    1 => "Please choose at most 1 option.".to_string(),
+                    max => format!("Please choose at most {max} options."),
+                }));
+            }
+
+            // Now make sure there's text for exactly the options that need it
+            let mut needs_text = false;
+            let mut with_text = Vec::new();
+            for (value, text) in canonical.iter().zip(texts) {
+                let text = text.map(|text| text.trim().to_string());
+                match options.iter().find(|option| &option.value == value) {
+                    Some(option) if option.specify => {
+                        needs_text = true;
+                        if text.as_deref().is_none_or(str::is_empty) {
+                            return Ok(Err(format!(
+                                "Please give some details for \"{}\".",
+                                option.label()
+                            )));
+                        }
+                    }
+                    _ if text.is_some() => {
+                        return Err(Error::InvalidAnswerType {
+                            expected: "text only for select options that need it",
+                        })
+                    }
+                    _ => {}
+                }
+                with_text.push((value.clone(), text));
+            }
+            let selection = if needs_text {
+                Answer::OptionsWithText(with_text)
+            } else {
+                Answer::Options(canonical.clone())
+            };
+            answer = match (followup, followup_answer) {
+                (Some(followup), followup_answer) if followup.is_asked(&canonical) => {
+                    let followup_answer = match followup_answer {
+                        Some(followup_answer) => followup_answer,
+                        None if followup.question.is_required() => {
+                            return Ok(Err(format!(
+                                "Please answer \"{}\".",
+                                followup.question.prompt()
+                            )))
+                        }
+                        None => Answer::Skipped,
+                    };
+                    match check_answer(&followup.question, followup_answer, form_options)? {
+                        Ok(followup_answer) => Answer::WithFollowUp {
+                            answer: Box::new(selection),
+                            followup: Box::new(followup_answer),
+                        },
+                        Err(message) => {
+                            return Ok(Err(format!("{}: {message}", followup.question.prompt())))
+                        }
+                    }
+                }
+                (_, Some(_)) => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "follow-up answer only for options that bring it up",
+                    })
+                }
+                (_, None) => selection,
+            };
+        }
+        Question::Rank { options, .. } => {
+            let Answer::Options(ref ranked) = answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "options for rank question",
+                });
+            };
+            // As with selections, the script should only ever see the options it gave
+            let canonical = ranked
+                .iter()
+                .map(|s| {
+                    match_option(options, s, form_options.option_normalize)
+                        .map(|option| option.value.clone())
+                })
+                .collect::<Option<Vec<_>>>();
+            // The user must have put every option somewhere in the order
+            match canonical {
+                Some(canonical)
+                    if canonical.len() == options.len()
+                        && options
+                            .iter()
+                            .all(|option| canonical.contains(&option.value)) =>
+                {
+                    answer = Answer::Options(canonical)
+                }
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "every option exactly once for rank question",
+                    })
+                }
+            }
+        }
+        Question::Group {
+            fields, min, max, ..
+        } => {
+            let Answer::Group(entries) = &answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "entries for group question",
+                });
+            };
+            if entries
+                .iter()
+                .flat_map(|entry| entry.keys())
+                .any(|key| !fields.iter().any(|field| field.id == *key))
+            {
+                return Err(Error::InvalidAnswerType {
+                    expected: "only declared fields in group entries",
+                });
+            }
+            if let Some(min) = min.filter(|min| entries.len() < *min) {
+                return Ok(Err(format!("Please add at least {min} entries.")));
+            }
+            if let Some(max) = max.filter(|max| entries.len() > *max) {
+                return Ok(Err(format!("Please add at most {max} entries.")));
+            }
+            for (idx, entry) in entries.iter().enumerate() {
+                for field in fields {
+                    let value = entry.get(&field.id).map(String::as_str).unwrap_or("");
+                    if value.is_empty() {
+                        if field.required {
+                            return Ok(Err(format!(
+                                "Please fill in {} for entry {}.",
+                                field.prompt,
+                                idx + 1
+                            )));
+                        }
+                        continue;
+                    }
+                    if let Some(pattern) = &field.pattern {
+                        if !whole_match_regex(pattern)?.is_match(value) {
+                            return Ok(Err(field.pattern_hint.clone().unwrap_or_else(|| {
+                                format!(
+                                    "Please enter {} for entry {} in the expected format.",
+                                    field.prompt,
+                                    idx + 1
+                                )
+                            })));
+                        }
+                    }
+                }
+            }
+        }
+        Question::Page { questions, .. } => {
+            let Answer::Page(mut answers) = answer else {
+                return Err(Error::InvalidAnswerType {
+                    expected: "answers for page",
+                });
+            };
+            if answers.len() != questions.len()
+                || !questions.iter().all(|item| answers.contains_key(&item.id))
+            {
+                return Err(Error::InvalidAnswerType {
+                    expected: "an answer to every question on page",
+                });
+            }
+            // The user sees the first problem on the page, with the question it's about
+            let mut checked = HashMap::new();
+            for item in questions {
+                // We've checked every question has an answer
+                let item_answer = answers.remove(&item.id).unwrap();
+                match check_answer(&item.question, item_answer, form_options)? {
+                    Ok(item_answer) => {
+                        checked.insert(item.id.clone(), item_answer);
+                    }
+                    Err(message) => {
+                        return Ok(Err(format!("{}: {message}", item.question.prompt())))
+                    }
+                }
+            }
+            answer = Answer::Page(checked);
+        }
+        Question::Info { .. } => {
+            if answer != Answer::Acknowledged {
+                return Err(Error::InvalidAnswerType {
+                    expected: "acknowledgement for info step",
+                });
+            }
+        }
+        Question::Computed { value, .. } => {
+            if answer != Answer::Computed(value.clone()) {
+                return Err(Error::InvalidAnswerType {
+                    expected: "script's own value for computed step",
+                });
+            }
+        }
+        Question::Date { min, max, .. } => {
+            let date = match &answer {
+                Answer::Date(date) => *date,
+                Answer::Text(text) => match parse_date(text) {
+                    Some(date) => date,
+                    None => {
+                        return Ok(Err(
+                            "Please enter a date in the format YYYY-MM-DD.".to_string()
+                        ))
+                    }
+                },
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "date or text for date question",
+                    })
+                }
+            };
+            if let Some(min) = min.filter(|min| date < *min) {
+                return Ok(Err(format!("Please enter a date on or after {min}.")));
+            }
+            if let Some(max) = max.filter(|max| date > *max) {
+                return Ok(Err(format!("Please enter a date on or before {max}.")));
+            }
+            answer = Answer::Date(date);
+        }
+        Question::Time {
+            min, max, seconds, ..
+        } => {
+            let time = match &answer {
+                Answer::Time(time) => *time,
+                Answer::Text(text) => match parse_time(text, *seconds) {
+                    Some(time) => time,
+                    None => {
+                        let format = if *seconds {
+                            "HH:MM or HH:MM:SS"
+                        } else {
+                            "HH:MM"
+                        };
+                        return Ok(Err(format!("Please enter a time in the format {format}.")));
+                    }
+                },
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "time or text for time question",
+                    })
+                }
+            };
+            if let Some(min) = min.filter(|min| time < *min) {
+                return Ok(Err(format!("Please enter a time no earlier than {min}.")));
+            }
+            if let Some(max) = max.filter(|max| time > *max) {
+                return Ok(Err(format!("Please enter a time no later than {max}.")));
+            }
+            answer = Answer::Time(time);
+        }
+        Question::Range { min, max, step, .. } => {
+            let value = match &answer {
+                Answer::Range(value) => Some(*value),
+                Answer::Text(text) => text.trim().parse().ok(),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "number or text for range question",
+                    })
+                }
+            };
+            let Some(value) = value.filter(|value: &f64| value.is_finite()) else {
+                return Ok(Err("Please enter a number.".to_string()));
+            };
+            if let Some(err) = range_error(value, *min, *max, *step) {
+                return Ok(Err(err));
+            }
+            answer = Answer::Range(value);
+        }
+        Question::Color { .. } => {
+            let color = match &answer {
+                Answer::Color(color) => Some(*color),
+                Answer::Text(text) => Color::parse(text),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "color or text for color question",
+                    })
+                }
+            };
+            let Some(color) = color else {
+                return Ok(Err(
+                    "Please enter a colour in hex (like #ff8800) or RGB (like rgb(255, 136, 0))."
+                        .to_string(),
+                ));
+            };
+            answer = Answer::Color(color);
+        }
+        Question::Decimal {
+            precision,
+            currency,
+            min,
+            max,
+            ..
+        } => {
+            let decimal = match &answer {
+                Answer::Decimal(decimal) => Some(*decimal),
+                Answer::Text(text) => Decimal::parse(text),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "decimal or text for decimal question",
+                    })
+                }
+            };
+            let Some(decimal) = decimal else {
+                return Ok(Err("Please enter a number.".to_string()));
+            };
+            let Some(decimal) = decimal.with_scale(*precision) else {
+                return Ok(Err(match precision {
+                    0 => "Please enter a whole number.".to_string(),
+                    1 => "Please enter a number with at most 1 decimal place.".to_string(),
+                    _ => format!("Please enter a number with at most {precision} decimal places."),
+                }));
+            };
+            let amount = |decimal: Decimal| match currency {
+                Some(currency) => format!("{decimal} {currency}"),
+                None => decimal.to_string(),
+            };
+            if let Some(min) = min.filter(|min| decimal.units() < min.units()) {
+                return Ok(Err(format!("Please enter at least {}.", amount(min))));
+            }
+            if let Some(max) = max.filter(|max| decimal.units() > max.units()) {
+                return Ok(Err(format!("Please enter at most {}.", amount(max))));
+            }
+            answer = Answer::Decimal(decimal);
+        }
+        Question::Rating { min, max, .. } => {
+            let rating = match &answer {
+                Answer::Rating(rating) => Some(*rating),
+                Answer::Text(text) => text.trim().parse().ok(),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "rating or text for rating question",
+                    })
+                }
+            };
+            match rating.filter(|rating| (*min..=*max).contains(rating)) {
+                Some(rating) => answer = Answer::Rating(rating),
+                None => return Ok(Err(format!("Please give a rating from {min} to {max}."))),
+            }
+        }
+        Question::Likert { labels, .. } => {
+            let point = match &answer {
+                Answer::Rating(point) => {
+                    Some(*point).filter(|point| (1..=labels.len()).contains(&(*point as usize)))
+                }
+                Answer::Text(text) => likert_point(labels, text),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "rating or text for likert question",
+                    })
+                }
+            };
+            match point {
+                Some(point) => answer = Answer::Rating(point),
+                None => return Ok(Err(format!("Please choose one of: {}.", labels.join(", ")))),
+            }
+        }
+        Question::File {
+            extensions,
+            multiple,
+            ..
+        } => {
+            let paths = match answer {
+                Answer::Files(paths) => paths,
+                Answer::Text(path) => vec![PathBuf::from(path)],
+                Answer::Options(paths) => paths.into_iter().map(PathBuf::from).collect(),
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "files, text, or options for file question",
+                    })
+                }
+            };
+            if !*multiple && paths.len() > 1 {
+                return Err(Error::InvalidAnswerType {
+                    expected: "single file for non-multiple file question",
+                });
+            }
+            if paths.is_empty() {
+                return Ok(Err("Please choose a file.".to_string()));
+            }
+            let has_valid_extension = |path: &PathBuf| {
+                path.extension()
+                    .is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+            };
+            if !extensions.is_empty() && !paths.iter().all(has_valid_extension) {
+                let extensions = extensions
+                    .iter()
+                    .map(|ext| format!(".{ext}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Ok(Err(format!(
+                    "Please choose files of these types: {extensions}."
+                )));
+            }
+            answer = Answer::Files(paths);
+        }
+        Question::DateTime {
+            min, max, timezone, ..
+        } => {
+            let datetime = match &answer {
+                Answer::DateTime(datetime) => *datetime,
+                Answer::Text(text) => match parse_datetime(text, *timezone) {
+                    Some(datetime) => datetime,
+                    None => {
+                        return Ok(Err(
+                            "Please enter a date and time in the format YYYY-MM-DD HH:MM."
+                                .to_string(),
+                        ))
+                    }
+                },
+                _ => {
+                    return Err(Error::InvalidAnswerType {
+                        expected: "datetime or text for datetime question",
+                    })
+                }
+            };
+            // Keep everything in the question's timezone so the script doesn't have to
+            let datetime = match timezone {
+                Some(timezone) => datetime.with_timezone(timezone),
+                None => datetime,
+            };
+            if let Some(min) = min.filter(|min| datetime < *min) {
+                return Ok(Err(format!(
+                    "Please enter a date and time no earlier than {}.",
+                    min.format("%Y-%m-%d %H:%M %:z")
+                )));
+            }
+            if let Some(max) = max.filter(|max| datetime > *max) {
+                return Ok(Err(format!(
+                    "Please enter a date and time no later than {}.",
+                    max.format("%Y-%m-%d %H:%M %:z")
+                )));
+            }
+            answer = Answer::DateTime(datetime);
+        }
+    }
+
+    Ok(Ok(answer))
+}
+
+/// Finds the option the given selection refers to, using the given normalisation if there's no
+/// exact match.
+fn match_option<'o>(
+    options: &'o [SelectOption],
+    selection: &str,
+    normalize: Option<fn(&str) -> String>,
+) -> Option<&'o SelectOption> {
+    if let Some(option) = options.iter().find(|option| option.value == selection) {
+        return Some(option);
+    }
+    let normalize = normalize?;
+    let normalized = normalize(selection);
+    options
+        .iter()
+        .find(|option| normalize(&option.value) == normalized)
+}
+
+/// Gets the options at the given path in the form's parameters (like `param.countries`), which
+/// should be an array of strings.
+fn options_from_params(parameters: &LuaValue, path: &str) -> Result<Vec<String>, Error> {
+    let invalid_path = || Error::InvalidOptionsFromPath {
+        path: path.to_string(),
+    };
+    let mut keys = path.split('.');
+    if keys.next() != Some("param") {
+        return Err(invalid_path());
+    }
+    let mut value = parameters.clone();
+    let mut has_key = false;
+    for key in keys {
+        if key.is_empty() {
+            return Err(invalid_path());
+        }
+        has_key = true;
+        value = match value {
+            LuaValue::Table(table) => table.raw_get(key).unwrap_or(LuaValue::Nil),
+            _ => LuaValue::Nil,
+        };
+        if value.is_nil() {
+            return Err(Error::OptionsFromNotFound {
+                path: path.to_string(),
+            });
+        }
+    }
+    if !has_key {
+        return Err(invalid_path());
+    }
+
+    // Every entry must be a string, and there mustn't be any keys other than the indices, or
+    // this isn't really an array
+    let not_strings = || Error::OptionsFromNotStrings {
+        path: path.to_string(),
+    };
+    let LuaValue::Table(table) = value else {
+        return Err(not_strings());
+    };
+    let len = table.raw_len();
+    if table.clone().pairs::<LuaValue, LuaValue>().count() != len {
+        return Err(not_strings());
+    }
+    (1..=len)
+        .map(|idx| match table.raw_get(idx) {
+            Ok(LuaValue::String(option)) => option
+                .to_str()
+                .map(ToString::to_string)
+                .map_err(|_| not_strings()),
+            _ => Err(not_strings()),
+        })
+        .collect()
+}
+
+/// Parses a date in the format `YYYY-MM-DD`, ignoring any surrounding whitespace.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()
+}
+
+/// Parses a time of day in the format `HH:MM` or `HH:MM:SS`, ignoring any surrounding whitespace.
+/// Unless `seconds` is set, only times on the minute will be accepted (so times we've formatted
+/// with seconds can always be parsed again).
+fn parse_time(text: &str, seconds: bool) -> Option<NaiveTime> {
+    let text = text.trim();
+    NaiveTime::parse_from_str(text, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S"))
+        .ok()
+        .filter(|time| seconds || time.second() == 0)
+}
+
+/// Parses a date and time in RFC 3339 format, or as `YYYY-MM-DD HH:MM` (with optional seconds,
+/// and optionally a `T` instead of the space) in the given timezone (or UTC if there isn't one),
+/// ignoring any surrounding whitespace.
+fn parse_datetime(text: &str, timezone: Option<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime);
+    }
+    let timezone = timezone.unwrap_or(FixedOffset::east_opt(0).unwrap());
+    [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ]
+    .into_iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    // Fixed offsets never have ambiguous times
+    .and_then(|datetime| datetime.and_local_timezone(timezone).single())
+}
+
+/// The most digits decimal questions can have after the decimal point, which leaves room for at
+/// least 20 before it.
+const MAX_DECIMAL_PRECISION: u32 = 18;
+
+/// Checks whether or not the given number is valid for a range question with the given bounds and
+/// step, returning a message for the user if it isn't.
+fn range_error(value: f64, min: f64, max: f64, step: Option<f64>) -> Option<String> {
+    if value < min || value > max {
+        return Some(format!("Please choose a number from {min} to {max}."));
+    }
+    // Allow for a little floating point error in the number of steps
+    if let Some(step) = step {
+        let steps = (value - min) / step;
+        if (steps - steps.round()).abs() > 1e-9 {
+            return Some(format!(
+                "Please choose a number in steps of {step}, starting from {min}."
+            ));
+        }
+    }
+
+    None
+}
+
+/// Checks whether or not the given text is a syntactically valid email address. This is
+/// deliberately looser than the RFCs (e.g. quoted local parts aren't allowed), because it only
+/// needs to catch typos: the only real test of an address is sending something to it.
+fn is_valid_email(address: &str) -> bool {
+    let Some((local, domain)) = address.rsplit_once('@') else {
+        return false;
+    };
+    let is_atom_char = |c: char| c.is_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+
+    !local.is_empty()
+        && local.len() <= 64
+        && local
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(is_atom_char))
+        // Addresses at bare hostnames are technically valid, but almost always a mistake
+        && domain.contains('.')
+        && domain.len() <= 253
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+/// Serializes the given value to JSON, returning the path within it to whatever couldn't be
+/// serialized if that fails (e.g. `answers.pets[2]`), so script authors can find the culprit (which
+/// is usually a function).
+fn to_json_with_path(value: &impl Serialize) -> Result<Value, (String, serde_json::Error)> {
+    serde_path_to_error::serialize(value, serde_json::value::Serializer)
+        .map_err(|err| (err.path().to_string(), err.into_inner()))
+}
+
+/// Compiles the given pattern from a script into a regular expression that only matches whole
+/// answers, rather than any part of them.
+fn whole_match_regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(&format!("^(?:{pattern})$")).map_err(|err| Error::InvalidPattern {
+        pattern: pattern.to_string(),
+        source: err,
+    })
 }
@@ -0,0 +1,25 @@
+/// The labels a [`crate::Question::Likert`] has if the script doesn't give any, which make up the
+/// conventional five-point agreement scale.
+pub const DEFAULT_LIKERT_LABELS: [&str; 5] = [
+    "Strongly disagree",
+    "Disagree",
+    "Neither agree nor disagree",
+    "Agree",
+    "Strongly agree",
+];
+
+/// Works out which point on a Likert scale with the given labels some text refers to, which can be
+/// one of the labels (ignoring case and surrounding whitespace), or the number of the point
+/// (starting from 1). This returns `None` if the text is neither, or the number isn't on the
+/// scale.
+pub fn likert_point(labels: &[String], text: &str) -> Option<u32> {
+    let text = text.trim();
+    let point = match labels
+        .iter()
+        .position(|label| label.to_lowercase() == text.to_lowercase())
+    {
+        Some(idx) => idx as u32 + 1,
+        None => text.parse().ok()?,
+    };
+    (1..=labels.len() as u32).contains(&point).then_some(point)
+}
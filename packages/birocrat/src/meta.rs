@@ -0,0 +1,75 @@
+use crate::{error::Error, Script};
+use mlua::{Lua, Table};
+use std::collections::HashMap;
+
+/// Metadata a driver script can declare about its form in a global `Meta` table. This allows
+/// interfaces to find out about a form (e.g. what parameters it takes) without running it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormMeta {
+    /// A description of what the form is for.
+    pub description: Option<String>,
+    /// The parameters the form takes, in the order the script declared them.
+    pub params: Vec<ParamMeta>,
+    /// Human-readable labels for the fields of the form's output and for its questions, keyed by
+    /// dotted paths into the output (e.g. `service.name`) or by question ID.
+    pub labels: HashMap<String, String>,
+}
+impl FormMeta {
+    /// Loads the given script into the given Lua VM and extracts its metadata, if it declares any.
+    /// This executes the top level of the script (in an environment of its own, like
+    /// [`Script::load`]), but will not call its `Main` function.
+    pub fn from_script(script: &str, lua_vm: &Lua) -> Result<Option<Self>, Error> {
+        Script::load(script, lua_vm)?.meta()
+    }
+    /// Parses metadata from the given `Meta` table.
+    pub(crate) fn from_lua(table: Table) -> Result<Self, Error> {
+        let description: Option<String> = table
+            .get("description")
+            .map_err(|err| Error::InvalidMeta { source: err })?;
+        let params: Option<Vec<Table>> = table
+            .get("params")
+            .map_err(|err| Error::InvalidMeta { source: err })?;
+
+        let params = params
+            .unwrap_or_default()
+            .into_iter()
+            .map(|param| {
+                let name: String = param
+                    .get("name")
+                    .map_err(|err| Error::InvalidMeta { source: err })?;
+                let description: Option<String> = param
+                    .get("description")
+                    .map_err(|err| Error::InvalidMeta { source: err })?;
+                let required: Option<bool> = param
+                    .get("required")
+                    .map_err(|err| Error::InvalidMeta { source: err })?;
+
+                Ok(ParamMeta {
+                    name,
+                    description,
+                    required: required.unwrap_or(false),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let labels: Option<HashMap<String, String>> = table
+            .get("labels")
+            .map_err(|err| Error::InvalidMeta { source: err })?;
+
+        Ok(Self {
+            description,
+            params,
+            labels: labels.unwrap_or_default(),
+        })
+    }
+}
+
+/// Metadata about a single parameter a form takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamMeta {
+    /// The name of the parameter (i.e. its key in the parameters table).
+    pub name: String,
+    /// A description of what the parameter is for.
+    pub description: Option<String>,
+    /// Whether or not the form needs this parameter to work.
+    pub required: bool,
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// Something that records measurements of a form's script as it runs, for capacity planning (e.g.
+/// on a server hosting many forms at once). Hosts can implement this to feed their own metrics
+/// system, and give it to forms through [`crate::FormOptions::metrics`]. Forms without it won't
+/// take any measurements at all.
+///
+/// This is shared between forms, which might be on different threads, so implementations will
+/// usually aggregate measurements with atomics or a lock.
+pub trait Metrics: Send + Sync {
+    /// Records the measurements from a single call to the script.
+    fn record_step(&self, step: StepMetrics);
+}
+
+/// Measurements from a single call to a form's script (e.g. to get the question after an answer).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepMetrics {
+    /// How long the script took to run.
+    pub script_time: Duration,
+    /// How long it took to serialize the state the script returned, which the form stores for
+    /// every question.
+    pub serialization_time: Duration,
+    /// The size of the state the script returned, in bytes of JSON.
+    pub state_size: usize,
+    /// How much memory the Lua VM was using once the script returned, in bytes. This covers
+    /// everything in the VM, including any other forms sharing it.
+    pub vm_memory: usize,
+}
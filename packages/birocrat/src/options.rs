@@ -0,0 +1,95 @@
+use crate::{Answer, Metrics};
+use std::{collections::HashMap, sync::Arc};
+
+/// Settings that control how a [`crate::Form`] treats its driver script and answers. The defaults
+/// are strict, so any mistake in a script is a hard error.
+#[derive(Clone, Default)]
+pub struct FormOptions {
+    /// Whether or not to tolerate mistakes in the script that don't stop the user from filling in
+    /// the form. Rather than failing, the form will work around these and record a
+    /// [`crate::Warning`] for each, which can be retrieved with [`crate::Form::warnings`]. This is
+    /// useful in production, where one typo in a rarely-used branch of a script shouldn't stop
+    /// anyone from using the form.
+    ///
+    /// Currently, this only covers select-type questions whose default isn't one of their options,
+    /// for which the default will be dropped.
+    pub lenient: bool,
+    /// A function that decides whether or not a new answer to a question that's already been
+    /// answered is the same as the old one. If it is, the questions after it will be kept, rather
+    /// than being clobbered and regenerated by the script. By default, answers must be exactly the
+    /// same, but hosts might want to ignore trivial differences (e.g. with
+    /// [`Answer::eq_ignore_case`] or [`Answer::eq_ignore_whitespace`]).
+    ///
+    /// When answers are found to be the same, the old answer will be kept, because that's the one
+    /// the script saw.
+    pub answer_eq: Option<fn(&Answer, &Answer) -> bool>,
+    /// A function that normalises selections in answers to select-type questions, so they can be
+    /// matched against the question's options even if they aren't exactly the same (e.g. with
+    /// [`fold_case`] or [`fold_case_and_whitespace`]). A selection that isn't exactly one of the
+    /// options will be matched to the first option that normalises to the same thing as it, and
+    /// the script will be given that option instead. By default, selections must match options
+    /// exactly.
+    ///
+    /// This is most useful when users type their selections, rather than choosing them (e.g. in
+    /// answer files for the CLI).
+    pub option_normalize: Option<fn(&str) -> String>,
+    /// The most errors the user can get in a row answering the same question before
+    /// [`crate::Form::progress_with_answer`] gives up and returns
+    /// [`crate::error::Error::TooManyErrors`], so the host can stop the user from getting stuck
+    /// (e.g. by resetting the form). By default, there's no limit.
+    pub max_errors: Option<usize>,
+    /// Where to record measurements of the script each time it's called (see [`Metrics`]). By
+    /// default, nothing is measured, which costs nothing.
+    ///
+    /// Measuring uses [`std::time::Instant`], which isn't available on `wasm32-unknown-unknown`,
+    /// so this shouldn't be set in the browser.
+    pub metrics: Option<Arc<dyn Metrics>>,
+    /// The seed for everything random the script does with `birocrat.random` (see
+    /// [`crate::Form::seed`]). Hosts resuming a session should give the seed it started with, so
+    /// the script makes the same choices it did before. By default, a new seed is generated, but
+    /// that can't be done on `wasm32-unknown-unknown`, so a seed should always be given in the
+    /// browser.
+    pub seed: Option<u64>,
+    /// Variants of experiments the host has assigned this form to, keyed by experiment, which
+    /// the script will be given when it asks for them with `birocrat.variant` (see
+    /// [`crate::Form::variants`]). Experiments that aren't assigned here get a variant chosen
+    /// from the seed.
+    pub variants: HashMap<String, String>,
+    /// Whether or not the form should refuse to be changed, so its history, answers, and
+    /// transcript can be inspected with no risk of changing them (e.g. when a support team looks
+    /// at a session restored with [`crate::Form::resume_with_options`]). When this is set,
+    /// [`crate::Form::progress_with_answer`] will always return
+    /// [`crate::error::Error::FormReadOnly`].
+    pub read_only: bool,
+}
+impl std::fmt::Debug for FormOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FormOptions")
+            .field("lenient", &self.lenient)
+            .field("answer_eq", &self.answer_eq)
+            .field("option_normalize", &self.option_normalize)
+            .field("max_errors", &self.max_errors)
+            .field("metrics", &self.metrics.is_some())
+            .field("seed", &self.seed)
+            .field("variants", &self.variants)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+/// Normalises a selection by ignoring its case. This can be used for
+/// [`FormOptions::option_normalize`].
+pub fn fold_case(option: &str) -> String {
+    option.to_lowercase()
+}
+
+/// Normalises a selection by ignoring its case, leading and trailing whitespace, and treating any
+/// run of whitespace within it as a single space. This can be used for
+/// [`FormOptions::option_normalize`].
+pub fn fold_case_and_whitespace(option: &str) -> String {
+    option
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
@@ -0,0 +1,140 @@
+use crate::{
+    error::Error, Answer, AnswerStore, Form, FormOptions, FormPoll, MemoryStore, Question,
+    ScriptState,
+};
+use mlua::Lua;
+use serde::Serialize;
+use serde_json::Value;
+use std::{mem::ManuallyDrop, ops::Deref, ptr::NonNull};
+
+/// A [`Form`] that creates and owns the Lua VM its script runs in, rather than borrowing one, so
+/// it has no lifetime, and can be stored anywhere (e.g. in a map of sessions) and moved around
+/// freely. Each of these has a whole VM to itself, so creating many forms from the same script is
+/// much cheaper with a shared VM and a [`crate::Script`]. Like the VM, these can't be sent between
+/// threads.
+///
+/// This dereferences to the form it owns, so all the methods of [`Form`] that take `&self` can be
+/// used on it directly, and those that take `&mut self` are repeated here. (The form can't be
+/// borrowed mutably, because it could then be swapped with one from a VM that'll be freed first.)
+pub struct OwnedForm<S: AnswerStore = MemoryStore> {
+    /// The form itself, which borrows the VM below, so it must always be dropped first.
+    form: ManuallyDrop<Form<'static, S>>,
+    /// The VM the form's script runs in. This is leaked for as long as the form is alive, and
+    /// freed when this is dropped.
+    lua_vm: NonNull<Lua>,
+}
+impl OwnedForm {
+    /// Creates a new form from the given Lua script in a VM of its own. See [`Form::new`].
+    pub fn new<P: Serialize>(script: &str, parameters: P) -> Result<Self, Error> {
+        Self::new_with_options(script, parameters, FormOptions::default())
+    }
+    /// Same as [`Self::new`], but this allows customising how the form treats its script.
+    pub fn new_with_options<P: Serialize>(
+        script: &str,
+        parameters: P,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
+        Self::build(|lua_vm| Form::new_with_options(script, parameters, lua_vm, options))
+    }
+    /// Resumes a form from a saved state in a VM of its own. See [`Form::resume`].
+    pub fn resume<P: Serialize>(script: &str, parameters: P, state: Value) -> Result<Self, Error> {
+        Self::resume_with_options(script, parameters, state, FormOptions::default())
+    }
+    /// Same as [`Self::resume`], but this allows customising how the form treats its script.
+    pub fn resume_with_options<P: Serialize>(
+        script: &str,
+        parameters: P,
+        state: Value,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
+        Self::build(|lua_vm| Form::resume_with_options(script, parameters, state, lua_vm, options))
+    }
+}
+impl<S: AnswerStore> OwnedForm<S> {
+    /// Same as [`Self::new_with_options`], but this keeps the form's answers and history in the
+    /// given store, rather than in memory. The store should be empty.
+    pub fn new_with_store<P: Serialize>(
+        script: &str,
+        parameters: P,
+        options: FormOptions,
+        store: S,
+    ) -> Result<Self, Error> {
+        Self::build(|lua_vm| Form::new_with_store(script, parameters, lua_vm, options, store))
+    }
+    /// Creates a new VM, and builds a form in it with the given function, freeing the VM again if
+    /// that fails.
+    fn build(
+        build: impl FnOnce(&'static Lua) -> Result<Form<'static, S>, Error>,
+    ) -> Result<Self, Error> {
+        let lua_vm = NonNull::from(Box::leak(Box::new(Lua::new())));
+        // SAFETY: the VM has just been leaked, and it's only freed below, or when this is
+        // dropped, both of which happen only once the form is gone
+        match build(unsafe { lua_vm.as_ref() }) {
+            Ok(form) => Ok(Self {
+                form: ManuallyDrop::new(form),
+                lua_vm,
+            }),
+            Err(err) => {
+                // SAFETY: there's no form, so nothing borrows the VM any more
+                drop(unsafe { Box::from_raw(lua_vm.as_ptr()) });
+                Err(err)
+            }
+        }
+    }
+
+    /// See [`Form::get_question`].
+    pub fn get_question(&mut self, idx: usize) -> Option<(&Question, Option<&Answer>)> {
+        self.form.get_question(idx)
+    }
+    /// See [`Form::progress_with_answer`].
+    pub fn progress_with_answer(
+        &mut self,
+        question_idx: usize,
+        answer: Answer,
+    ) -> Result<FormPoll<'_>, Error> {
+        self.form.progress_with_answer(question_idx, answer)
+    }
+    /// See [`Form::back`].
+    pub fn back(&mut self) -> Option<(&Question, Option<&Answer>)> {
+        self.form.back()
+    }
+    /// See [`Form::set_locale`].
+    pub fn set_locale(&mut self, locale: Option<String>) {
+        self.form.set_locale(locale);
+    }
+    /// See [`Form::prefetch`].
+    pub fn prefetch(&mut self, limit: usize) -> usize {
+        self.form.prefetch(limit)
+    }
+    /// If the form has been completed, returns the final object the driver script returned,
+    /// serialized for convenience as JSON. The VM is freed along with the form.
+    #[allow(clippy::result_large_err)]
+    pub fn into_done(mut self) -> Result<Value, Self> {
+        if let (ScriptState::Done(output), _) = &mut self.form.next_state {
+            return Ok(std::mem::take(output));
+        }
+        Err(self)
+    }
+}
+impl<S: AnswerStore> Deref for OwnedForm<S> {
+    type Target = Form<'static, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.form
+    }
+}
+impl<S: AnswerStore> std::fmt::Debug for OwnedForm<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.form.fmt(f)
+    }
+}
+impl<S: AnswerStore> Drop for OwnedForm<S> {
+    fn drop(&mut self) {
+        // SAFETY: the form is never used again, and it's dropped before the VM it borrows, which
+        // nothing else can borrow
+        unsafe {
+            ManuallyDrop::drop(&mut self.form);
+            drop(Box::from_raw(self.lua_vm.as_ptr()));
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use crate::Question;
+use serde::{Deserialize, Serialize};
+
+/// One of the questions on a [`Question::Page`], which scripts give just like a question on its
+/// own (with an ID, a type, and so on).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageQuestion {
+    /// The key of the answer to this question in an [`crate::Answer::Page`].
+    pub id: String,
+    /// The question itself, which can be any type other than another page.
+    pub question: Question,
+}
@@ -0,0 +1,242 @@
+/// The country calling codes of the regions phone questions can be given as hints, keyed by their
+/// ISO 3166-1 alpha-2 codes.
+const CALLING_CODES: &[(&str, &str)] = &[
+    // North America and the Caribbean
+    ("US", "1"),
+    ("CA", "1"),
+    ("PR", "1"),
+    ("BS", "1"),
+    ("BB", "1"),
+    ("DO", "1"),
+    ("JM", "1"),
+    ("TT", "1"),
+    // Europe
+    ("GB", "44"),
+    ("IE", "353"),
+    ("FR", "33"),
+    ("DE", "49"),
+    ("IT", "39"),
+    ("VA", "39"),
+    ("SM", "378"),
+    ("ES", "34"),
+    ("PT", "351"),
+    ("NL", "31"),
+    ("BE", "32"),
+    ("LU", "352"),
+    ("CH", "41"),
+    ("LI", "423"),
+    ("AT", "43"),
+    ("DK", "45"),
+    ("SE", "46"),
+    ("NO", "47"),
+    ("FI", "358"),
+    ("IS", "354"),
+    ("PL", "48"),
+    ("CZ", "420"),
+    ("SK", "421"),
+    ("HU", "36"),
+    ("RO", "40"),
+    ("MD", "373"),
+    ("BG", "359"),
+    ("GR", "30"),
+    ("CY", "357"),
+    ("MT", "356"),
+    ("MC", "377"),
+    ("AD", "376"),
+    ("HR", "385"),
+    ("SI", "386"),
+    ("RS", "381"),
+    ("BA", "387"),
+    ("ME", "382"),
+    ("MK", "389"),
+    ("AL", "355"),
+    ("EE", "372"),
+    ("LV", "371"),
+    ("LT", "370"),
+    ("UA", "380"),
+    ("BY", "375"),
+    ("RU", "7"),
+    ("TR", "90"),
+    ("GE", "995"),
+    ("AM", "374"),
+    ("AZ", "994"),
+    // Asia and the Pacific
+    ("AU", "61"),
+    ("NZ", "64"),
+    ("FJ", "679"),
+    ("PG", "675"),
+    ("JP", "81"),
+    ("KR", "82"),
+    ("CN", "86"),
+    ("HK", "852"),
+    ("MO", "853"),
+    ("TW", "886"),
+    ("SG", "65"),
+    ("MY", "60"),
+    ("ID", "62"),
+    ("TH", "66"),
+    ("VN", "84"),
+    ("PH", "63"),
+    ("IN", "91"),
+    ("PK", "92"),
+    ("BD", "880"),
+    ("LK", "94"),
+    ("NP", "977"),
+    ("KZ", "7"),
+    ("UZ", "998"),
+    // The Middle East
+    ("AE", "971"),
+    ("SA", "966"),
+    ("QA", "974"),
+    ("KW", "965"),
+    ("BH", "973"),
+    ("OM", "968"),
+    ("IL", "972"),
+    ("JO", "962"),
+    ("LB", "961"),
+    ("IQ", "964"),
+    ("IR", "98"),
+    // Africa
+    ("EG", "20"),
+    ("MA", "212"),
+    ("DZ", "213"),
+    ("TN", "216"),
+    ("NG", "234"),
+    ("GH", "233"),
+    ("SN", "221"),
+    ("CI", "225"),
+    ("CM", "237"),
+    ("KE", "254"),
+    ("ET", "251"),
+    ("TZ", "255"),
+    ("UG", "256"),
+    ("RW", "250"),
+    ("ZA", "27"),
+    ("ZW", "263"),
+    ("ZM", "260"),
+    ("MU", "230"),
+    // Latin America
+    ("MX", "52"),
+    ("GT", "502"),
+    ("CR", "506"),
+    ("PA", "507"),
+    ("CU", "53"),
+    ("BR", "55"),
+    ("AR", "54"),
+    ("CL", "56"),
+    ("CO", "57"),
+    ("PE", "51"),
+    ("VE", "58"),
+    ("EC", "593"),
+    ("BO", "591"),
+    ("PY", "595"),
+    ("UY", "598"),
+];
+
+/// The fewest digits a full international number can have (including the country code).
+const MIN_DIGITS: usize = 7;
+/// The most digits a full international number can have (including the country code), which is
+/// set by E.164.
+const MAX_DIGITS: usize = 15;
+
+/// Gets the country calling code for the given region (an ISO 3166-1 alpha-2 code, like `GB`), if
+/// it's one phone questions know about.
+pub(crate) fn calling_code(region: &str) -> Option<&'static str> {
+    CALLING_CODES
+        .iter()
+        .find(|(known, _)| *known == region)
+        .map(|(_, code)| *code)
+}
+
+/// Gets the prefix people in the given region dial before national numbers, which has to be
+/// removed to make an international number.
+fn trunk_prefix(region: &str) -> Option<&'static str> {
+    match region {
+        // The leading zero is part of the number here
+        "IT" | "VA" | "SM" | "MX" | "CI" => None,
+        "RU" | "KZ" | "BY" => Some("8"),
+        "HU" => Some("06"),
+        _ if calling_code(region) == Some("1") => Some("1"),
+        _ => Some("0"),
+    }
+}
+
+/// Splits the country calling code off the start of the given international number (without its
+/// `+`), if it's one phone questions know about, returning a region that uses it along with the
+/// rest of the number.
+fn split_calling_code(digits: &str) -> Option<(&'static str, &str)> {
+    // Calling codes are designed so none is the start of another, but we prefer the longest anyway
+    CALLING_CODES
+        .iter()
+        .filter(|(_, code)| digits.starts_with(code))
+        .max_by_key(|(_, code)| code.len())
+        .map(|(region, code)| (*region, &digits[code.len()..]))
+}
+
+/// Normalises the given phone number to E.164 format (e.g. `+442079460958`), ignoring spaces,
+/// dashes, dots, and brackets. Numbers without a country code (i.e. not starting with `+` or
+/// `00`) are taken to be national numbers in the given region, if there is one. International
+/// numbers that keep their region's trunk prefix of `0` after the country code (like
+/// `+44 (0)20 7946 0958`, which is a common way to write them) have it removed. If the number
+/// isn't valid, this returns a message for the user.
+pub(crate) fn normalize_phone(text: &str, region: Option<&str>) -> Result<String, String> {
+    let invalid = || "Please enter a phone number.".to_string();
+    let text = text.trim();
+    let (international, rest) = match text.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => match text.strip_prefix("00") {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        },
+    };
+    // The trunk prefix is often written in brackets after the country code, to show it's only
+    // dialled from inside the region
+    let rest = if international {
+        rest.replacen("(0)", "", 1)
+    } else {
+        rest.to_string()
+    };
+    let mut digits = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => return Err(invalid()),
+        }
+    }
+    if digits.is_empty() {
+        return Err(invalid());
+    }
+
+    let number = if international {
+        match split_calling_code(&digits) {
+            // Trunk prefixes other than zero can be the start of real numbers (e.g. Russian area
+            // codes starting with 8), so we only remove zeroes
+            Some((region, national)) if trunk_prefix(region) == Some("0") => {
+                let code = &digits[..digits.len() - national.len()];
+                format!("{code}{}", national.strip_prefix('0').unwrap_or(national))
+            }
+            _ => digits,
+        }
+    } else {
+        let Some(region) = region else {
+            return Err("Please include the country code, like +44.".to_string());
+        };
+        // This was checked when the question was parsed
+        let code = calling_code(region).unwrap_or_default();
+        let national = match trunk_prefix(region) {
+            // North American numbers only have the prefix if they'd be too long otherwise
+            Some("1") if digits.len() == 11 => &digits[1..],
+            Some("1") => &digits,
+            Some(prefix) => digits.strip_prefix(prefix).unwrap_or(&digits),
+            None => &digits,
+        };
+        format!("{code}{national}")
+    };
+    // Country codes never start with zero
+    if number.starts_with('0') || !(MIN_DIGITS..=MAX_DIGITS).contains(&number.len()) {
+        return Err("Please enter a full phone number, including the area code.".to_string());
+    }
+
+    Ok(format!("+{number}"))
+}
@@ -0,0 +1,42 @@
+use crate::{Answer, ScriptState, Warning};
+use mlua::Lua;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Marks the driver script call currently running in the given VM as impure. Host functions that
+/// scripts can call, and whose results could change between calls (e.g. ones that read the time
+/// or query a database), should call this, so that the form won't keep any next states it computes
+/// ahead of time using them (see [`crate::Form::prefetch`]), which might be stale by the time the
+/// user actually answers.
+///
+/// Outside of prefetching, this does nothing.
+pub fn mark_impure(lua: &Lua) {
+    if let Some(mut prefetching) = lua.app_data_mut::<Prefetching>() {
+        prefetching.impure = true;
+    }
+}
+
+/// A marker put into the Lua VM while the script is being called ahead of time, which records
+/// whether or not it called any impure host functions. While this is there, the call isn't
+/// recorded in the form's [`crate::Metrics`].
+pub(crate) struct Prefetching {
+    pub(crate) impure: bool,
+}
+
+/// The result of calling the script ahead of time with a possible answer to the next question.
+pub(crate) struct Prefetched {
+    /// The answer the script was called with.
+    pub(crate) answer: Answer,
+    /// The locale the answer was given in.
+    pub(crate) locale: Option<String>,
+    /// The next state the script gave, along with any warnings from getting it. If this is `None`,
+    /// the result couldn't be kept (e.g. because the script called an impure host function), and
+    /// the script will have to be called again when this answer is actually given.
+    pub(crate) outcome: Option<Outcome>,
+    /// The variants of experiments the script was given for the first time in this call, which
+    /// are only recorded as assigned if this result is used.
+    pub(crate) assigned: HashMap<String, String>,
+}
+
+/// The next state the script gave for an answer, along with any warnings from getting it.
+pub(crate) type Outcome = (Result<(ScriptState, Value), String>, Vec<Warning>);
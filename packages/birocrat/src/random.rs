@@ -0,0 +1,174 @@
+use crate::{error::Error, hash::encode};
+use mlua::{Lua, Table};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    rc::Rc,
+};
+
+/// The version of the encoding random numbers are derived from. Changing this would change every
+/// random number a script has ever been given, so replaying old sessions would give different
+/// results.
+const RANDOM_VERSION: &str = "birocrat-random-v1";
+
+/// Generates a new seed for a form that wasn't given one. This uses the randomness the standard
+/// library seeds hash maps with, which isn't available on `wasm32-unknown-unknown` (where every
+/// seed generated will be the same), so hosts in the browser should generate their own.
+pub(crate) fn new_seed() -> u64 {
+    RandomState::new().hash_one(RANDOM_VERSION)
+}
+
+/// Derives a number in `[0, 1)` from the given seed and key, for the given kind of use (so a
+/// random number and a variant with the same key are independent). The same seed, kind, and key
+/// will always give the same number, on every platform, which is what makes randomised forms
+/// replayable.
+fn random_fraction(seed: u64, kind: &str, key: &str) -> f64 {
+    let mut encoded = String::new();
+    // Seeds are encoded as strings, because JSON numbers can't hold every `u64` exactly
+    encode(
+        &json!([RANDOM_VERSION, seed.to_string(), kind, key]),
+        &mut encoded,
+    );
+    let digest = Sha256::digest(encoded.as_bytes());
+    let bits = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    // The top 53 bits fill an `f64`'s mantissa exactly
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+/// The `birocrat` table a form gives its script, and the environment of the script to put it in.
+/// Every form created from the same [`crate::Script`] shares its environment, so this has to be
+/// put in place before every call to the script, in case another form's table is there.
+#[derive(Debug)]
+pub(crate) struct ScriptApi<'l> {
+    /// The script's environment.
+    env: Table<'l>,
+    /// The `birocrat` table.
+    api: Table<'l>,
+    /// The variant of each experiment the script has asked for so far, keyed by experiment.
+    assigned: Rc<RefCell<HashMap<String, String>>>,
+}
+impl<'l> ScriptApi<'l> {
+    /// Creates the `birocrat` table for a form with the given seed and variants assigned by the
+    /// host (see [`crate::FormOptions::variants`]), whose script has the given environment.
+    pub(crate) fn new(
+        lua_vm: &'l Lua,
+        env: Table<'l>,
+        seed: u64,
+        variants: HashMap<String, String>,
+    ) -> Result<Self, Error> {
+        let assigned = Rc::new(RefCell::new(HashMap::new()));
+        Ok(Self {
+            env,
+            api: create_api(lua_vm, seed, variants, assigned.clone())?,
+            assigned,
+        })
+    }
+    /// Gets the variant of each experiment the script has asked for so far, keyed by experiment.
+    pub(crate) fn assigned(&self) -> HashMap<String, String> {
+        self.assigned.borrow().clone()
+    }
+    /// Records that the script has already been given the given variants, keyed by experiment
+    /// (e.g. when the form is being resumed).
+    pub(crate) fn restore_assigned(&self, assigned: HashMap<String, String>) {
+        self.assigned.borrow_mut().extend(assigned);
+    }
+    /// Forgets every variant given out since the given set of variants was assigned (see
+    /// [`Self::assigned`]), returning the ones that were newly given out. This is used to keep
+    /// calls made ahead of time from recording variants before the user has actually answered.
+    pub(crate) fn rewind_assigned(
+        &self,
+        assigned: HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let mut current = self.assigned.borrow_mut();
+        let new = current
+            .drain()
+            .filter(|(experiment, _)| !assigned.contains_key(experiment))
+            .collect();
+        *current = assigned;
+        new
+    }
+    /// Puts this form's `birocrat` table in the script's environment.
+    pub(crate) fn install(&self) -> Result<(), Error> {
+        self.env
+            .raw_set("birocrat", self.api.clone())
+            .map_err(|err| Error::CreateEnvironmentFailed { source: err })
+    }
+}
+
+/// Creates the `birocrat` table scripts are given for a form with the given seed. This has a
+/// `random(key, [m, [n]])` function, which works like Lua's `math.random` (giving a number in
+/// `[0, 1)`, an integer from 1 to `m`, or an integer from `m` to `n`), except that the result is
+/// derived from the form's seed and the given key, rather than from any hidden state. Calling it
+/// with the same key will always give the same result in the same session, so `Main` stays pure,
+/// and the whole session can be replayed from its seed.
+///
+/// It also has a `variant(experiment, variants)` function, which gives the variant of the given
+/// experiment (e.g. `"greeting"`) this session is in, out of the given array of variants (e.g.
+/// `{ "formal", "casual" }`). This is the variant the host assigned, if it assigned one, and is
+/// otherwise chosen evenly from the seed. Every variant given out is recorded in `assigned`.
+fn create_api(
+    lua_vm: &Lua,
+    seed: u64,
+    variants: HashMap<String, String>,
+    assigned: Rc<RefCell<HashMap<String, String>>>,
+) -> Result<Table<'_>, Error> {
+    let api = lua_vm
+        .create_table()
+        .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+    let random = lua_vm
+        .create_function(move |_, (key, m, n): (String, Option<i64>, Option<i64>)| {
+            let fraction = random_fraction(seed, "random", &key);
+            let (low, high) = match (m, n) {
+                (None, _) => return Ok(mlua::Value::Number(fraction)),
+                (Some(m), None) => (1, m),
+                (Some(m), Some(n)) => (m, n),
+            };
+            if low > high {
+                return Err(mlua::Error::runtime(format!(
+                    "bad arguments to `birocrat.random` (interval is empty: {low} to {high})"
+                )));
+            }
+            // Rounding could take a huge span right to its end, so we stop there
+            let span = (high as i128 - low as i128 + 1) as f64;
+            let offset = (fraction * span) as i128;
+            Ok(mlua::Value::Integer(
+                (low as i128 + offset).min(high as i128) as i64,
+            ))
+        })
+        .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+    api.set("random", random)
+        .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+    let variant = lua_vm
+        .create_function(move |_, (experiment, options): (String, Vec<String>)| {
+            if options.is_empty() {
+                return Err(mlua::Error::runtime(format!(
+                    "bad arguments to `birocrat.variant` (no variants given for experiment '{experiment}')"
+                )));
+            }
+            let variant = match variants.get(&experiment) {
+                Some(variant) if options.contains(variant) => variant.clone(),
+                Some(variant) => {
+                    return Err(mlua::Error::runtime(format!(
+                        "variant '{variant}' assigned to experiment '{experiment}' by the host is not one of its variants"
+                    )))
+                }
+                None => {
+                    let fraction = random_fraction(seed, "variant", &experiment);
+                    let idx = ((fraction * options.len() as f64) as usize).min(options.len() - 1);
+                    options[idx].clone()
+                }
+            };
+            assigned
+                .borrow_mut()
+                .insert(experiment, variant.clone());
+            Ok(variant)
+        })
+        .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+    api.set("variant", variant)
+        .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+
+    Ok(api)
+}
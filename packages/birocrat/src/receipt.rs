@@ -0,0 +1,86 @@
+use crate::{error::Error, hash::encode, AnswerStore, Form, ScriptState, Transcript};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The version of the encoding receipts are signed over. Like the version of hashes, this goes into
+/// every signature, so signatures over different encodings can never be confused.
+const RECEIPT_VERSION: &str = "birocrat-receipt-v1";
+
+/// A tamper-evident record of a completed form, which binds its output to the answers that
+/// produced it with an Ed25519 signature from the host. Anyone with the host's public key can then
+/// check that neither has been changed since the form was completed (e.g. before acting on an
+/// approval).
+///
+/// Receipts only contain the hash of the transcript (see [`Transcript::hash`]), so they can be
+/// passed around without exposing the user's answers, but the transcript itself is needed to
+/// check that it's the one the receipt is for. Receipts can be serialized to be stored or sent to
+/// whoever needs to check them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// The final object the script returned.
+    pub output: Value,
+    /// The hash of the transcript of the user's answers.
+    pub transcript_hash: String,
+    /// The host's signature over the output and transcript hash.
+    pub signature: Signature,
+}
+impl Receipt {
+    /// Creates a receipt for the given output and transcript, signed with the given key.
+    pub fn sign(output: Value, transcript: &Transcript, key: &SigningKey) -> Self {
+        let transcript_hash = transcript.hash();
+        let signature = key.sign(&Self::message(&output, &transcript_hash));
+
+        Self {
+            output,
+            transcript_hash,
+            signature,
+        }
+    }
+    /// Checks that this receipt was signed with the private half of the given key, and that its
+    /// output and transcript hash haven't been changed since.
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), Error> {
+        key.verify_strict(
+            &Self::message(&self.output, &self.transcript_hash),
+            &self.signature,
+        )
+        .map_err(|err| Error::InvalidReceiptSignature { source: err })
+    }
+    /// Checks that this receipt is valid (see [`Self::verify`]), and that it's for the given
+    /// transcript.
+    pub fn verify_transcript(
+        &self,
+        key: &VerifyingKey,
+        transcript: &Transcript,
+    ) -> Result<(), Error> {
+        self.verify(key)?;
+        if transcript.hash() != self.transcript_hash {
+            return Err(Error::ReceiptTranscriptMismatch);
+        }
+
+        Ok(())
+    }
+    /// Gets the bytes that are signed for the given output and transcript hash.
+    fn message(output: &Value, transcript_hash: &str) -> Vec<u8> {
+        let mut encoded = String::new();
+        encode(
+            &json!([RECEIPT_VERSION, output, transcript_hash]),
+            &mut encoded,
+        );
+        encoded.into_bytes()
+    }
+}
+
+impl<S: AnswerStore> Form<'_, S> {
+    /// If the form has been completed, creates a receipt for its output and transcript, signed with
+    /// the given key (see [`Receipt`]). Secret answers will affect the transcript hash as they
+    /// would for [`Transcript::hash`].
+    pub fn receipt(&self, key: &SigningKey) -> Option<Receipt> {
+        match &self.next_state {
+            (ScriptState::Done(output), _) => {
+                Some(Receipt::sign(output.clone(), &self.transcript(), key))
+            }
+            _ => None,
+        }
+    }
+}
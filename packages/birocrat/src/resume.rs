@@ -0,0 +1,206 @@
+use crate::{
+    error::Error, random::ScriptApi, Answer, AnswerStore, Form, FormOptions, HistoryEntry,
+    MemoryStore, Script, ScriptState, Warning,
+};
+use mlua::{Lua, LuaSerdeExt, Value as LuaValue};
+use serde::{de::Error as _, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The version of the format forms are saved in (see [`Form::serialize_state`]). This goes into
+/// every saved state, so that, if the format ever has to change, states saved in the old format
+/// will be refused, rather than misread.
+const STATE_VERSION: u64 = 1;
+
+/// Everything needed to resume a form where it left off, other than its script and parameters.
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    /// The version of the format this was saved in (see [`STATE_VERSION`]).
+    version: u64,
+    /// The questions the form had asked, in order.
+    history: Vec<HistoryEntry>,
+    /// The answers to the questions in the history, and to the next question if it has one.
+    answers: Vec<SavedAnswer>,
+    /// The state the script was in after the last question in the history was answered, and its
+    /// internal state.
+    next_state: (ScriptState, Value),
+    /// The form's seed, as text, because JSON numbers can't hold every `u64` exactly.
+    seed: String,
+    /// The variants of experiments the host assigned the form to.
+    host_variants: HashMap<String, String>,
+    /// The variants of experiments the script had been given.
+    assigned_variants: HashMap<String, String>,
+    /// The locale the user was answering in.
+    locale: Option<String>,
+    /// The mistakes in the script that had been worked around.
+    warnings: Vec<Warning>,
+}
+
+/// A cached answer in a [`SavedState`].
+#[derive(Serialize, Deserialize)]
+struct SavedAnswer {
+    /// The ID of the question this answers.
+    id: String,
+    /// The answer itself.
+    answer: Answer,
+    /// The locale the answer was given in, if there was one.
+    locale: Option<String>,
+}
+
+impl<'l> Form<'l> {
+    /// Resumes a form from a state saved with [`Self::serialize_state`], using the given Lua
+    /// script and parameters, which must be the same as those the form was created with (hosts
+    /// can check this by storing [`crate::form_hash`] along with the state). The form will be
+    /// exactly where it was when it was saved, without the script being called at all.
+    pub fn resume<P: Serialize>(
+        script: &str,
+        parameters: P,
+        state: Value,
+        lua_vm: &'l Lua,
+    ) -> Result<Self, Error> {
+        Self::resume_with_options(script, parameters, state, lua_vm, FormOptions::default())
+    }
+    /// Same as [`Self::resume`], but this allows customising how the form treats its script. The
+    /// seed and variants the form was saved with will be used, whatever the options say.
+    pub fn resume_with_options<P: Serialize>(
+        script: &str,
+        parameters: P,
+        state: Value,
+        lua_vm: &'l Lua,
+        options: FormOptions,
+    ) -> Result<Self, Error> {
+        let parameters = lua_vm
+            .to_value(&parameters)
+            .map_err(|err| Error::SerializeFormParamsFailed { source: err })?;
+        let script = Script::load(script, lua_vm)?;
+
+        Self::restore(&script, parameters, state, options, MemoryStore::default())
+    }
+}
+impl<'l, S: AnswerStore> Form<'l, S> {
+    /// Serializes the state of this form (the questions it has asked, their answers, and what the
+    /// script will do next) as JSON, so it can be saved (e.g. to a database) and resumed later
+    /// with [`Form::resume`]. Answers cached for questions that are no longer in the history
+    /// (because an earlier answer changed) aren't included.
+    ///
+    /// Secret answers are included as they are, so this should be stored as carefully as the
+    /// answers themselves.
+    pub fn serialize_state(&self) -> Result<Value, Error> {
+        let ids = (0..self.store.history_len())
+            .filter_map(|idx| self.store.history_entry(idx).map(|entry| entry.id.as_str()))
+            .chain(self.next_question_id());
+        let answers = ids
+            .filter_map(|id| {
+                Some(SavedAnswer {
+                    id: id.to_string(),
+                    answer: self.store.answer(id)?.clone(),
+                    locale: self.store.answer_locale(id).map(String::from),
+                })
+            })
+            .collect();
+        let state = SavedState {
+            version: STATE_VERSION,
+            history: (0..self.store.history_len())
+                .filter_map(|idx| self.store.history_entry(idx).cloned())
+                .collect(),
+            answers,
+            next_state: self.next_state.clone(),
+            seed: self.seed.to_string(),
+            host_variants: self.options.variants.clone(),
+            assigned_variants: self.variants(),
+            locale: self.locale.clone(),
+            warnings: self.warnings.clone(),
+        };
+
+        serde_json::to_value(state).map_err(|err| Error::SerializeSavedStateFailed { source: err })
+    }
+    /// Restores a form from the given saved state (see [`Self::serialize_state`]), keeping its
+    /// answers and history in the given store, which should be empty.
+    fn restore(
+        script: &Script<'l>,
+        parameters: LuaValue<'l>,
+        state: Value,
+        mut options: FormOptions,
+        mut store: S,
+    ) -> Result<Self, Error> {
+        // We check the version first, so states in other formats get a clear error
+        let version = state.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if version != STATE_VERSION {
+            return Err(Error::UnsupportedStateVersion {
+                version,
+                expected: STATE_VERSION,
+            });
+        }
+        let state: SavedState = serde_json::from_value(state)
+            .map_err(|err| Error::DeserializeSavedStateFailed { source: err })?;
+        let seed = state
+            .seed
+            .parse()
+            .map_err(|_| Error::DeserializeSavedStateFailed {
+                source: serde_json::Error::custom(format!("invalid seed '{}'", state.seed)),
+            })?;
+
+        for entry in state.history {
+            store.push_history(entry);
+        }
+        for SavedAnswer { id, answer, locale } in state.answers {
+            store.set_answer(id.clone(), answer);
+            store.set_answer_locale(id, locale);
+        }
+        options.seed = Some(seed);
+        options.variants = state.host_variants;
+        let script_api = ScriptApi::new(
+            script.lua_vm,
+            script.env.clone(),
+            seed,
+            options.variants.clone(),
+        )?;
+        script_api.restore_assigned(state.assigned_variants);
+
+        let position = store.history_len();
+        Ok(Self {
+            store,
+            lua_vm: script.lua_vm,
+            driver_function: script.driver_function.clone(),
+            review_function: script.review_function.clone(),
+            suggester: script.suggester.clone(),
+            next_state: state.next_state,
+            parameters,
+            options,
+            warnings: state.warnings,
+            locale: state.locale,
+            seed,
+            script_api,
+            error_streak: None,
+            position,
+            prefetched: Vec::new(),
+        })
+    }
+}
+
+/// Serializes optional UTC offsets (like the timezone of a [`crate::Question::DateTime`]) as text
+/// like `+10:00`, because `chrono` can't serialize them itself.
+pub(crate) mod optional_offset {
+    use chrono::FixedOffset;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        offset: &Option<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        offset
+            .map(|offset| offset.to_string())
+            .serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<FixedOffset>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|offset| {
+                offset
+                    .parse()
+                    .map_err(|_| D::Error::custom(format!("invalid offset '{offset}'")))
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{error::Error, suggest::Suggester, FormMeta};
+use mlua::{Function, Lua, Table, Value as LuaValue};
+
+/// A driver script loaded into a Lua VM, from which any number of forms can be created with
+/// [`crate::Form::from_script`]. Loading a script executes its top level, which only has to
+/// happen once here, rather than once for every form.
+///
+/// Scripts are loaded into an environment table of their own, so any globals they declare (like
+/// `Main`) won't clobber those of other scripts loaded into the same VM. Globals from the VM
+/// itself (like the standard library) are still available to them. Every form created from the
+/// same script shares its environment, which is why `Main` should be a pure function.
+#[derive(Debug, Clone)]
+pub struct Script<'l> {
+    /// The Lua VM the script is loaded into.
+    pub(crate) lua_vm: &'l Lua,
+    /// The table holding the script's globals.
+    pub(crate) env: Table<'l>,
+    /// The script's `Main` function.
+    pub(crate) driver_function: Function<'l>,
+    /// The script's `Review` function, if it has one.
+    pub(crate) review_function: Option<Function<'l>>,
+    /// Where the script's suggestions for answers come from, if it has any.
+    pub(crate) suggester: Option<Suggester<'l>>,
+}
+impl<'l> Script<'l> {
+    /// Loads the given script into the given Lua VM, in an environment of its own. This executes
+    /// the top level of the script.
+    pub fn load(source: &str, lua_vm: &'l Lua) -> Result<Self, Error> {
+        let env = lua_vm
+            .create_table()
+            .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+        // Anything the script doesn't declare itself should come from the VM's globals
+        let env_meta = lua_vm
+            .create_table()
+            .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+        env_meta
+            .set("__index", lua_vm.globals())
+            .map_err(|err| Error::CreateEnvironmentFailed { source: err })?;
+        env.set_metatable(Some(env_meta));
+
+        lua_vm
+            .load(source)
+            .set_environment(env.clone())
+            .exec()
+            .map_err(|err| Error::ScriptLoadFailed { source: err })?;
+
+        Self::from_env(lua_vm, env)
+    }
+    /// Gets the functions a script has declared in the given environment.
+    fn from_env(lua_vm: &'l Lua, env: Table<'l>) -> Result<Self, Error> {
+        let driver_function: Function = env
+            .get("Main")
+            .map_err(|err| Error::NoMainFunction { source: err })?;
+        let review_function: Option<Function> = env
+            .get("Review")
+            .map_err(|err| Error::InvalidReviewFunction { source: err })?;
+        let suggester = match env
+            .get("Suggest")
+            .map_err(|err| Error::InvalidSuggest { source: err })?
+        {
+            LuaValue::Nil => None,
+            LuaValue::Function(suggest_function) => Some(Suggester::Function(suggest_function)),
+            LuaValue::Table(table) => Some(Suggester::Table(table)),
+            other => {
+                return Err(Error::InvalidSuggest {
+                    source: mlua::Error::FromLuaConversionError {
+                        from: other.type_name(),
+                        to: "function or table",
+                        message: None,
+                    },
+                })
+            }
+        };
+
+        Ok(Self {
+            lua_vm,
+            env,
+            driver_function,
+            review_function,
+            suggester,
+        })
+    }
+
+    /// Gets the metadata this script declares about its forms, if it declares any.
+    pub fn meta(&self) -> Result<Option<FormMeta>, Error> {
+        let meta: Option<Table> = self
+            .env
+            .get("Meta")
+            .map_err(|err| Error::InvalidMeta { source: err })?;
+
+        meta.map(FormMeta::from_lua).transpose()
+    }
+}
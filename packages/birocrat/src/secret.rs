@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Text the user gave in answer to a [`crate::Question::Secret`], like a password or an API token.
+/// This works like a normal string, except that it redacts itself in `Debug` output, so secrets
+/// won't end up in logs by accident. The text itself can be read with [`SecretText::expose`].
+///
+/// Serializing this does *not* redact it, so that forms can be saved and resumed (see
+/// [`crate::Form::serialize_state`]).
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretText(String);
+impl SecretText {
+    /// Wraps the given text as a secret.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+    /// Gets the secret text. Be careful with what you do with this!
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+impl From<String> for SecretText {
+    fn from(text: String) -> Self {
+        Self(text)
+    }
+}
+impl fmt::Debug for SecretText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretText([redacted])")
+    }
+}
@@ -0,0 +1,64 @@
+use mlua::{FromLua, Lua, Value as LuaValue};
+use serde::{Deserialize, Serialize};
+
+/// One of the options in a [`crate::Question::Select`]. Scripts can give these as plain strings,
+/// which will just be used as values, or as tables like
+/// `{ value = "...", label = "...", description = "..." }`, for interfaces that need more to
+/// display than the value itself (including an `image`, for visual pickers). Options like "Other"
+/// can also set `specify = true` if the user must give some text along with them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectOption {
+    /// The value of the option, which is what will be given to the script if the user selects it,
+    /// and what answers should contain.
+    pub value: String,
+    /// A label to show the user instead of the value.
+    pub label: Option<String>,
+    /// A longer description of the option to show the user alongside its label.
+    pub description: Option<String>,
+    /// The URL or path of an image to show the user for this option (e.g. a preview of a
+    /// template), which is left to the interface to load. Answers still contain the option's
+    /// value.
+    pub image: Option<String>,
+    /// Whether or not the user must give some text along with this option if they choose it
+    /// (e.g. for "Allergies: please specify"), in which case they'll answer with
+    /// [`crate::Answer::OptionsWithText`].
+    pub specify: bool,
+}
+impl SelectOption {
+    /// Gets the text that should be shown to the user for this option, which is its label if it
+    /// has one, and otherwise its value.
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.value)
+    }
+}
+impl From<String> for SelectOption {
+    fn from(value: String) -> Self {
+        Self {
+            value,
+            label: None,
+            description: None,
+            image: None,
+            specify: false,
+        }
+    }
+}
+impl From<&str> for SelectOption {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+impl<'lua> FromLua<'lua> for SelectOption {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> mlua::Result<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                value: table.get("value")?,
+                label: table.get("label")?,
+                description: table.get("description")?,
+                image: table.get("image")?,
+                specify: table.get::<_, Option<bool>>("specify")?.unwrap_or(false),
+            }),
+            // This will also take numbers, as it always has
+            value => String::from_lua(value, lua).map(Self::from),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Answer, Question};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt};
+
+/// A question a form has asked, along with the state its script was in when it asked it. Going
+/// back to this state lets the form change the answer to this question.
+///
+/// The `Debug` output of this leaves out the script's state, which could contain earlier answers
+/// to secret questions.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The unique ID the script gave this question.
+    pub id: String,
+    /// The question that was asked.
+    pub question: Question,
+    /// The internal state of the script when it asked this question, serialized as JSON.
+    pub state: Value,
+}
+impl fmt::Debug for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HistoryEntry")
+            .field("id", &self.id)
+            .field("question", &self.question)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Storage for the answers a form has been given and the history of questions it has asked. The
+/// form manages both of these itself, but this lets hosts keep them somewhere other than in memory
+/// (e.g. to persist them, or to encrypt them at rest) by creating a form with
+/// [`crate::Form::new_with_store`]. [`MemoryStore`] is used by default.
+///
+/// Answers are cached by question ID, and may outlive the questions they were given for in the
+/// history (so they can be suggested if the same question is asked again after an earlier answer
+/// is changed). The history is indexed by the order in which questions were asked.
+pub trait AnswerStore {
+    /// Gets the answer cached for the question with the given ID, if there is one.
+    fn answer(&self, id: &str) -> Option<&Answer>;
+    /// Caches the given answer for the question with the given ID, replacing any previous one.
+    fn set_answer(&mut self, id: String, answer: Answer);
+    /// Gets the number of questions in the history.
+    fn history_len(&self) -> usize;
+    /// Gets the entry in the history at the given index, if there is one.
+    fn history_entry(&self, idx: usize) -> Option<&HistoryEntry>;
+    /// Adds the given entry to the end of the history.
+    fn push_history(&mut self, entry: HistoryEntry);
+    /// Removes every entry from the history after the first `len`.
+    fn truncate_history(&mut self, len: usize);
+    /// Gets the locale the answer cached for the question with the given ID was given in, if it
+    /// was given in one (see [`crate::Form::set_locale`]). Stores that don't keep locales can
+    /// leave this out, in which case answers will be treated as having no locale.
+    fn answer_locale(&self, _id: &str) -> Option<&str> {
+        None
+    }
+    /// Records the locale the answer cached for the question with the given ID was given in
+    /// (replacing any previous one), or that it wasn't given in one.
+    fn set_answer_locale(&mut self, _id: String, _locale: Option<String>) {}
+}
+
+/// An [`AnswerStore`] that keeps everything in memory. This is what forms use by default.
+///
+/// The `Debug` output of this only includes the IDs of the questions it has answers for, because
+/// cached answers may outlive the questions that would say whether they're secret.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    answers: HashMap<String, Answer>,
+    locales: HashMap<String, String>,
+    history: Vec<HistoryEntry>,
+}
+impl fmt::Debug for MemoryStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryStore")
+            .field("answered", &self.answers.keys().collect::<Vec<_>>())
+            .field("locales", &self.locales)
+            .field("history", &self.history)
+            .finish()
+    }
+}
+impl AnswerStore for MemoryStore {
+    fn answer(&self, id: &str) -> Option<&Answer> {
+        self.answers.get(id)
+    }
+    fn set_answer(&mut self, id: String, answer: Answer) {
+        self.answers.insert(id, answer);
+    }
+    fn history_len(&self) -> usize {
+        self.history.len()
+    }
+    fn history_entry(&self, idx: usize) -> Option<&HistoryEntry> {
+        self.history.get(idx)
+    }
+    fn push_history(&mut self, entry: HistoryEntry) {
+        self.history.push(entry);
+    }
+    fn truncate_history(&mut self, len: usize) {
+        self.history.truncate(len);
+    }
+    fn answer_locale(&self, id: &str) -> Option<&str> {
+        self.locales.get(id).map(String::as_str)
+    }
+    fn set_answer_locale(&mut self, id: String, locale: Option<String>) {
+        match locale {
+            Some(locale) => self.locales.insert(id, locale),
+            None => self.locales.remove(&id),
+        };
+    }
+}
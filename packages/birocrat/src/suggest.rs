@@ -0,0 +1,71 @@
+use crate::{error::Error, AnswerStore, Form};
+use mlua::{Function, Table};
+
+/// Where a script's suggestions for answers come from (see [`Form::suggest`]). Scripts declare
+/// these with a global `Suggest`, which can be either of these.
+#[derive(Debug, Clone)]
+pub(crate) enum Suggester<'l> {
+    /// A function, which is given the ID of a question, the text typed so far, and the form's
+    /// parameters, and returns the suggestions itself (e.g. by calling a host function).
+    Function(Function<'l>),
+    /// A table of arrays of possible answers, keyed by question ID, which are filtered by the text
+    /// typed so far.
+    Table(Table<'l>),
+}
+
+impl<'l, S: AnswerStore> Form<'l, S> {
+    /// Gets suggestions for an answer to the question with the given ID, based on the text the
+    /// user has typed so far, which interfaces can show as the user types (e.g. to autocomplete
+    /// a city). These come from the script's `Suggest` global, and, if it doesn't have one, there
+    /// won't be any.
+    ///
+    /// If `Suggest` is a table, the suggestions will be those listed under the question's ID that
+    /// contain the text (ignoring case), in the order they're listed. If it's a function, it will
+    /// be called with the question's ID, the text, and the form's parameters, and should return
+    /// an array of suggestions (or `nil` for none).
+    ///
+    /// This never changes the form, so it can be called as often as the interface likes, and the
+    /// user's answer should still be given with [`Self::progress_with_answer`] as usual.
+    ///
+    /// The question must be the next question, or one that's already been asked, otherwise this
+    /// will return [`Error::QuestionNotAsked`].
+    ///
+    /// This is synchronous, and will block until the script returns. Forms hold their Lua VM, so
+    /// they can't be sent to another thread, and the engine has no async runtime, so interfaces
+    /// should keep `Suggest` functions quick, and call this only once the user pauses typing.
+    pub fn suggest(&self, question_id: &str, text: &str) -> Result<Vec<String>, Error> {
+        let asked = self.next_question_id() == Some(question_id)
+            || (0..self.store.history_len()).any(|idx| {
+                self.store
+                    .history_entry(idx)
+                    .is_some_and(|entry| entry.id == question_id)
+            });
+        if !asked {
+            return Err(Error::QuestionNotAsked {
+                question_id: question_id.to_string(),
+            });
+        }
+
+        match &self.suggester {
+            None => Ok(Vec::new()),
+            Some(Suggester::Table(table)) => {
+                let candidates: Option<Vec<String>> = table
+                    .get(question_id)
+                    .map_err(|err| Error::InvalidSuggestions { source: err })?;
+                let text = text.to_lowercase();
+                Ok(candidates
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|candidate| candidate.to_lowercase().contains(&text))
+                    .collect())
+            }
+            Some(Suggester::Function(suggest_function)) => {
+                self.script_api.install()?;
+                let suggestions: Option<Vec<String>> = suggest_function
+                    .call((question_id, text, self.parameters.clone()))
+                    .map_err(|err| Error::RunSuggestFailed { source: err })?;
+                Ok(suggestions.unwrap_or_default())
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Answer, AnswerStore, Form, Question};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+
+/// The tag scripts can give questions whose answers are sensitive, but which aren't passwords or
+/// tokens (e.g. medical details), so they'll be redacted like answers to [`Question::Secret`].
+pub const SECRET_TAG: &str = "secret";
+/// What answers are replaced with in redacted transcripts (see [`Form::redact`]).
+pub const REDACTED: &str = "[redacted]";
+
+/// A record of every question a form has asked and how the user answered it, in the order they
+/// were asked. This is suitable for showing the user what they submitted, or keeping for audit
+/// purposes, and can be serialized to be kept alongside a receipt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// The questions and their answers, in the order they were asked.
+    pub entries: Vec<TranscriptEntry>,
+    /// The seed the form was given (see [`Form::seed`]), so the session can be replayed exactly.
+    pub seed: u64,
+    /// The variant of each experiment the script was given, keyed by experiment (see
+    /// [`Form::variants`]).
+    pub variants: HashMap<String, String>,
+}
+
+/// A single question in a [`Transcript`], along with its answer. The `Debug` output of this redacts
+/// the answer if the question is secret (see [`Question::is_secret`]).
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// The unique ID the script gave this question.
+    pub id: String,
+    /// The question that was asked.
+    pub question: Question,
+    /// The user's answer to the question.
+    pub answer: Answer,
+    /// The locale the user gave their answer in, if there was one.
+    pub locale: Option<String>,
+}
+impl fmt::Debug for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranscriptEntry")
+            .field("id", &self.id)
+            .field("question", &self.question)
+            .field("answer", &DebugAnswer::new(&self.question, &self.answer))
+            .field("locale", &self.locale)
+            .finish()
+    }
+}
+
+/// An answer to show in `Debug` output, which will be [`REDACTED`] if the question it answers is
+/// secret.
+pub(crate) struct DebugAnswer<'a> {
+    answer: Option<&'a Answer>,
+}
+impl<'a> DebugAnswer<'a> {
+    pub(crate) fn new(question: &Question, answer: &'a Answer) -> Self {
+        Self {
+            answer: (!question.is_secret()).then_some(answer),
+        }
+    }
+}
+impl fmt::Debug for DebugAnswer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.answer {
+            Some(answer) => answer.fmt(f),
+            None => f.write_str(REDACTED),
+        }
+    }
+}
+
+impl<'l, S: AnswerStore> Form<'l, S> {
+    /// Gets a transcript of every question answered so far and its answer. Like
+    /// [`Self::answers`], this won't include answers to questions that were later clobbered by
+    /// changing an earlier answer. If the form is done, this will be a full record of what the
+    /// user submitted.
+    pub fn transcript(&self) -> Transcript {
+        Transcript {
+            entries: self
+                .answers()
+                .map(|(id, question, answer)| TranscriptEntry {
+                    id: id.to_string(),
+                    question: question.clone(),
+                    answer: answer.clone(),
+                    locale: self.store.answer_locale(id).map(String::from),
+                })
+                .collect(),
+            seed: self.seed,
+            variants: self.variants(),
+        }
+    }
+    /// Gets a transcript like [`Self::transcript`], but with the answers to secret questions
+    /// (those of type [`Question::Secret`], or tagged with [`SECRET_TAG`]) replaced with
+    /// [`REDACTED`] text. This is safe to log.
+    pub fn redact(&self) -> Transcript {
+        let mut transcript = self.transcript();
+        for entry in &mut transcript.entries {
+            if entry.question.is_secret() {
+                entry.answer = Answer::Text(REDACTED.to_string());
+            }
+        }
+        transcript
+    }
+}
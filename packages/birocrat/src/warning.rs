@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A mistake in a driver script that the form worked around, rather than failing, because it was
+/// created in lenient mode (see [`crate::FormOptions::lenient`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Warning {
+    /// The default for a select-type question wasn't one of its options, so it was dropped.
+    DefaultNotInOptions {
+        /// The ID of the question.
+        question_id: String,
+        /// The default the script gave.
+        default: String,
+    },
+}
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DefaultNotInOptions {
+                question_id,
+                default,
+            } => write!(
+                f,
+                "dropped default '{default}' for select-type question '{question_id}', because it isn't one of the question's options"
+            ),
+        }
+    }
+}
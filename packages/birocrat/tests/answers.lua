@@ -0,0 +1,24 @@
+-- A form that asks about the user's pet, only asking for its name if it's a dog
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "pet", type = "select", text = "What kind of pet do you have?", options = { "dog", "cat" } },
+			{ step = "pet" },
+		}
+	elseif state.step == "pet" and answer.selected[1] == "dog" then
+		return {
+			"question",
+			{ id = "dog_name", type = "simple", text = "What's your dog's name?" },
+			{ step = "dog_name" },
+		}
+	elseif state.step == "pet" or state.step == "dog_name" then
+		return {
+			"question",
+			{ id = "owner", type = "simple", text = "What's your name?" },
+			{ step = "owner" },
+		}
+	else
+		return { "done", {}, nil }
+	end
+end
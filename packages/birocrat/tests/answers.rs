@@ -0,0 +1,48 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static ANSWERS_SCRIPT: &str = include_str!("answers.lua");
+
+#[test]
+fn should_list_answers_in_order() {
+    let vm = Lua::new();
+    let mut form = Form::new(ANSWERS_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(form.next_index(), 0);
+    assert_eq!(form.answers().count(), 0);
+
+    form.progress_with_answer(0, Answer::Options(vec!["dog".to_string()]))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("Rex".to_string()))
+        .unwrap();
+    assert_eq!(form.next_index(), 2);
+    assert_eq!(
+        form.answers()
+            .map(|(id, _, answer)| (id, answer.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("pet", Answer::Options(vec!["dog".to_string()])),
+            ("dog_name", Answer::Text("Rex".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn should_leave_out_clobbered_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(ANSWERS_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Options(vec!["dog".to_string()]))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("Rex".to_string()))
+        .unwrap();
+
+    // Cats don't get asked for their names, so that answer is gone
+    form.progress_with_answer(0, Answer::Options(vec!["cat".to_string()]))
+        .unwrap();
+    assert_eq!(form.next_index(), 1);
+    assert_eq!(form.next_question_id(), Some("owner"));
+    assert_eq!(
+        form.answers().map(|(id, _, _)| id).collect::<Vec<_>>(),
+        vec!["pet"]
+    );
+}
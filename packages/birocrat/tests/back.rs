@@ -0,0 +1,62 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static COMPUTED_SCRIPT: &str = include_str!("computed.lua");
+
+#[test]
+fn should_go_back_past_computed_steps() {
+    let vm = Lua::new();
+    let mut form = Form::new(COMPUTED_SCRIPT, json!({}), &vm).unwrap();
+    // There's nothing before the first question the user sees
+    assert_eq!(form.current_index(), 1);
+    assert!(form.back().is_none());
+    assert_eq!(form.current_index(), 1);
+
+    form.progress_with_answer(1, Answer::Text("30".to_string()))
+        .unwrap();
+    assert_eq!(form.current_index(), 3);
+    form.progress_with_answer(3, Answer::Text("Alex".to_string()))
+        .unwrap();
+    assert_eq!(form.current_index(), 4);
+
+    let (question, answer) = form.back().unwrap();
+    assert_eq!(question.prompt(), "What is your name?");
+    assert_eq!(answer, Some(&Answer::Text("Alex".to_string())));
+    // The age band was computed, so we should skip straight to the age
+    let (question, answer) = form.back().unwrap();
+    assert_eq!(question.prompt(), "How old are you?");
+    assert_eq!(answer, Some(&Answer::Text("30".to_string())));
+    assert_eq!(form.current_index(), 1);
+    assert!(form.back().is_none());
+
+    // Going back shouldn't change anything about the form
+    assert_eq!(form.next_index(), 4);
+    assert!(form.next_question().is_none());
+}
+
+#[test]
+fn should_answer_from_where_the_user_went_back_to() {
+    let vm = Lua::new();
+    let mut form = Form::new(COMPUTED_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(1, Answer::Text("30".to_string()))
+        .unwrap();
+    form.back().unwrap();
+    assert_eq!(form.current_index(), 1);
+
+    let FormPoll::Question { question, answer } = form
+        .progress_with_answer(form.current_index(), Answer::Text("10".to_string()))
+        .unwrap()
+    else {
+        panic!("expected question");
+    };
+    assert_eq!(question.prompt(), "What is your name?");
+    assert_eq!(answer, None);
+    assert_eq!(form.current_index(), 3);
+    form.progress_with_answer(form.current_index(), Answer::Text("Sam".to_string()))
+        .unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "source": "web", "band": "minor", "name": "Sam" })
+    );
+}
@@ -13,12 +13,18 @@ fn should_work() {
     let vm = Lua::new();
     let mut form = Form::new(BASIC_SCRIPT, params, &vm).unwrap();
 
-    let question = form.first_question();
+    let question = form.first_question().unwrap();
     assert_eq!(
         question,
         &Question::Simple {
             prompt: "What is your name, user 37?".to_string(),
             default: None,
+            pattern: None,
+            pattern_hint: None,
+            min_length: None,
+            max_length: None,
+            required: true,
+            common: QuestionCommon::default(),
         }
     );
     let poll = form
@@ -30,10 +36,21 @@ fn should_work() {
             question: &Question::Simple {
                 prompt: "How old are you, Alice?".to_string(),
                 default: Some("30".to_string()),
+                pattern: None,
+                pattern_hint: None,
+                min_length: None,
+                max_length: None,
+                required: true,
+                common: QuestionCommon::default(),
             },
             answer: None
         }
     );
+    // Now that we've answered a question, we can't get the first one like this
+    assert!(matches!(
+        form.first_question(),
+        Err(error::Error::FormAlreadyProgressed)
+    ));
     // Provide an incorrect answer
     let poll = form
         .progress_with_answer(1, Answer::Text("twenty-five".to_string()))
@@ -51,12 +68,19 @@ fn should_work() {
         FormPoll::Question {
             question: &Question::Select {
                 prompt: "What is your favourite type of cuisine?".to_string(),
-                default: None,
+                defaults: Vec::new(),
                 options: vec!["Indian", "Korean", "Japanese", "Chinese", "Italian"]
                     .into_iter()
-                    .map(|s| s.to_string())
+                    .map(SelectOption::from)
                     .collect(),
-                multiple: false
+                multiple: false,
+                allow_other: false,
+                searchable: false,
+                min_selected: None,
+                max_selected: None,
+                followup: None,
+                required: true,
+                common: QuestionCommon::default(),
             },
             answer: None
         }
@@ -68,6 +92,12 @@ fn should_work() {
             &Question::Simple {
                 prompt: "How old are you, Alice?".to_string(),
                 default: Some("30".to_string()),
+                pattern: None,
+                pattern_hint: None,
+                min_length: None,
+                max_length: None,
+                required: true,
+                common: QuestionCommon::default(),
             },
             Some(&Answer::Text("25".to_string()))
         ))
@@ -87,11 +117,19 @@ fn should_work() {
         .progress_with_answer(2, Answer::Options(vec!["American".to_string()]))
         .is_err());
 
-    // If we answer with `Italian`, we should be done
+    // If we answer with `Italian`, we should be done, and we can see the output without giving
+    // up the form
     let poll = form
         .progress_with_answer(2, Answer::Options(vec!["Italian".to_string()]))
         .unwrap();
-    assert_eq!(poll, FormPoll::Done);
+    assert_eq!(
+        poll,
+        FormPoll::Done(&json!({
+            "name": "Alice",
+            "age": 25,
+            "favourite_cuisine": "Italian"
+        }))
+    );
 
     // But we can go back and answer with something else to get another question
     let poll = form
@@ -102,12 +140,19 @@ fn should_work() {
         FormPoll::Question {
             question: &Question::Select {
                 prompt: "What levels of spice can you tolerate?".to_string(),
-                default: None,
+                defaults: Vec::new(),
                 options: vec!["Mild", "Medium", "Hot", "Very Hot", "Extreme Hot"]
                     .into_iter()
-                    .map(|s| s.to_string())
+                    .map(SelectOption::from)
                     .collect(),
                 multiple: true,
+                allow_other: false,
+                searchable: false,
+                min_selected: None,
+                max_selected: None,
+                followup: None,
+                required: true,
+                common: QuestionCommon::default(),
             },
             answer: None,
         }
@@ -125,19 +170,19 @@ fn should_work() {
             Answer::Options(vec!["Mild".to_string(), "Not that hot".to_string()])
         )
         .is_err());
-    assert_eq!(
+    assert!(matches!(
         form.progress_with_answer(3, Answer::Options(vec!["Mild".to_string()]))
             .unwrap(),
-        FormPoll::Done
-    );
-    assert_eq!(
+        FormPoll::Done(_)
+    ));
+    assert!(matches!(
         form.progress_with_answer(
             3,
             Answer::Options(vec!["Mild".to_string(), "Medium".to_string()])
         )
         .unwrap(),
-        FormPoll::Done
-    );
+        FormPoll::Done(_)
+    ));
 
     // And now we can get the form's final details
     let res = form.into_done().unwrap();
@@ -0,0 +1,12 @@
+-- A form that asks for a brand colour
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "brand", type = "color", text = "What's your brand colour?", default = "#F80" },
+			{},
+		}
+	else
+		return { "done", { color = answer.color, red = answer.red }, nil }
+	end
+end
@@ -0,0 +1,62 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static COLOR_SCRIPT: &str = include_str!("color.lua");
+
+#[test]
+fn should_parse_colors() {
+    let orange = Color {
+        red: 255,
+        green: 136,
+        blue: 0,
+    };
+    assert_eq!(Color::parse("#ff8800"), Some(orange));
+    assert_eq!(Color::parse("FF8800"), Some(orange));
+    assert_eq!(Color::parse(" #f80 "), Some(orange));
+    assert_eq!(Color::parse("rgb(255, 136, 0)"), Some(orange));
+    assert_eq!(Color::parse("RGB(255,136,0)"), Some(orange));
+    assert_eq!(Color::parse("rgb(256, 0, 0)"), None);
+    assert_eq!(Color::parse("rgb(1, 2)"), None);
+    assert_eq!(Color::parse("#ff880"), None);
+    assert_eq!(Color::parse("orange"), None);
+    assert_eq!(orange.to_string(), "#ff8800");
+}
+
+#[test]
+fn should_validate_colors() {
+    let vm = Lua::new();
+    let mut form = Form::new(COLOR_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Color { default: Some(default), .. } if default.to_string() == "#ff8800"
+    ));
+
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("orange".to_string()))
+            .unwrap(),
+        FormPoll::Error(
+            "Please enter a colour in hex (like #ff8800) or RGB (like rgb(255, 136, 0))."
+                .to_string()
+        )
+    );
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("rgb(18, 52, 86)".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "color": "#123456", "red": 18 })
+    );
+}
+
+#[test]
+fn should_reject_invalid_default_colors() {
+    let vm = Lua::new();
+    let script = COLOR_SCRIPT.replace(r##""#F80""##, r#""orange""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidColorDefault { value }) if value == "orange"
+    ));
+}
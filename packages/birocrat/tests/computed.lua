@@ -0,0 +1,34 @@
+-- A form that records where the user came from and their age band, without asking them
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "source", type = "computed", text = "Source", value = params.source or "web" },
+			{ stage = "source" },
+		}
+	elseif state.stage == "source" then
+		return {
+			"question",
+			{ id = "age", type = "simple", text = "How old are you?" },
+			{ stage = "age", source = answer.value },
+		}
+	elseif state.stage == "age" then
+		local band = "minor"
+		if tonumber(answer.text) >= 18 then
+			band = "adult"
+		end
+		return {
+			"question",
+			{ id = "band", type = "computed", text = "Age band", value = { band = band } },
+			{ stage = "band", source = state.source },
+		}
+	elseif state.stage == "band" then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What is your name?" },
+			{ stage = "name", source = state.source, band = answer.value.band },
+		}
+	else
+		return { "done", { source = state.source, band = state.band, name = answer.text }, nil }
+	end
+end
@@ -0,0 +1,95 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static COMPUTED_SCRIPT: &str = include_str!("computed.lua");
+
+fn answered_ids<'a>(form: &'a Form) -> Vec<&'a str> {
+    form.answers().map(|(id, _, _)| id).collect()
+}
+
+#[test]
+fn should_skip_leading_computed_steps() {
+    let vm = Lua::new();
+    let form = Form::new(COMPUTED_SCRIPT, json!({ "source": "email" }), &vm).unwrap();
+    assert_eq!(form.first_question().unwrap().prompt(), "How old are you?");
+    assert_eq!(form.next_index(), 1);
+    let (id, question, answer) = form.answers().next().unwrap();
+    assert_eq!(id, "source");
+    assert!(matches!(question, Question::Computed { .. }));
+    assert_eq!(answer, &Answer::Computed(json!("email")));
+}
+
+#[test]
+fn should_record_computed_values() {
+    let vm = Lua::new();
+    let mut form = Form::new(COMPUTED_SCRIPT, json!({}), &vm).unwrap();
+    let FormPoll::Question { question, .. } = form
+        .progress_with_answer(1, Answer::Text("30".to_string()))
+        .unwrap()
+    else {
+        panic!("expected question");
+    };
+    assert_eq!(question.prompt(), "What is your name?");
+    assert_eq!(answered_ids(&form), vec!["source", "age", "band"]);
+    assert_eq!(
+        form.store().answer("band"),
+        Some(&Answer::Computed(json!({ "band": "adult" })))
+    );
+
+    assert!(matches!(
+        form.progress_with_answer(3, Answer::Text("Alex".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "source": "web", "band": "adult", "name": "Alex" })
+    );
+}
+
+#[test]
+fn should_recompute_when_answers_change() {
+    let vm = Lua::new();
+    let mut form = Form::new(COMPUTED_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(1, Answer::Text("30".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("10".to_string()))
+        .unwrap();
+    assert_eq!(answered_ids(&form), vec!["source", "age", "band"]);
+    assert_eq!(
+        form.store().answer("band"),
+        Some(&Answer::Computed(json!({ "band": "minor" })))
+    );
+    assert_eq!(form.next_question_id(), Some("name"));
+}
+
+#[test]
+fn should_reject_other_answers_to_computed_steps() {
+    let vm = Lua::new();
+    let mut form = Form::new(COMPUTED_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Computed(json!("post"))),
+        Err(Error::InvalidAnswerType { .. })
+    ));
+    // The same value is fine, and changes nothing
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Computed(json!("web")))
+            .unwrap(),
+        FormPoll::Question { .. }
+    ));
+    assert_eq!(form.next_question_id(), Some("age"));
+}
+
+#[test]
+fn should_reject_computed_steps_on_pages() {
+    let vm = Lua::new();
+    let script = COMPUTED_SCRIPT.replace(
+        r#"{ id = "source", type = "computed", text = "Source", value = params.source or "web" }"#,
+        r#"{ id = "page", type = "page", text = "Page", questions = { { id = "source", type = "computed", text = "Source" } } }"#,
+    );
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::ComputedOnPage)
+    ));
+}
@@ -0,0 +1,19 @@
+-- A form that asks for a date within a range
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "deadline",
+				type = "date",
+				text = "When is it due?",
+				default = "2024-06-01",
+				min = "2024-01-01",
+				max = "2024-12-31",
+			},
+			{},
+		}
+	else
+		return { "done", { deadline = answer.date }, nil }
+	end
+end
@@ -0,0 +1,59 @@
+use birocrat::*;
+use chrono::NaiveDate;
+use mlua::Lua;
+use serde_json::json;
+
+static DATE_SCRIPT: &str = include_str!("date.lua");
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn should_validate_dates() {
+    let vm = Lua::new();
+    let mut form = Form::new(DATE_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Date {
+            prompt: "When is it due?".to_string(),
+            default: Some(date(2024, 6, 1)),
+            min: Some(date(2024, 1, 1)),
+            max: Some(date(2024, 12, 31)),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Invalid and out-of-range dates should be rejected before the script sees them
+    for text in ["next tuesday", "2024-02-30"] {
+        assert_eq!(
+            form.progress_with_answer(0, Answer::Text(text.to_string()))
+                .unwrap(),
+            FormPoll::Error("Please enter a date in the format YYYY-MM-DD.".to_string())
+        );
+    }
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Date(date(2023, 12, 31)))
+            .unwrap(),
+        FormPoll::Error("Please enter a date on or after 2024-01-01.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Text answers should be parsed
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text(" 2024-03-15 ".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::Date(date(2024, 3, 15))
+    );
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "deadline": "2024-03-15" })
+    );
+}
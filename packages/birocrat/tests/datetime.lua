@@ -0,0 +1,20 @@
+-- A form that books an appointment in a particular timezone
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "appointment",
+				type = "datetime",
+				text = "When would you like to come in?",
+				timezone = "+10:00",
+				default = "2024-06-03 09:00",
+				min = "2024-06-01T00:00:00+10:00",
+				max = "2024-06-30 17:00",
+			},
+			{},
+		}
+	else
+		return { "done", { appointment = answer.datetime }, nil }
+	end
+end
@@ -0,0 +1,68 @@
+use birocrat::*;
+use chrono::{DateTime, FixedOffset};
+use mlua::Lua;
+use serde_json::json;
+
+static DATETIME_SCRIPT: &str = include_str!("datetime.lua");
+
+fn datetime(rfc3339: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(rfc3339).unwrap()
+}
+
+#[test]
+fn should_validate_datetimes() {
+    let vm = Lua::new();
+    let mut form = Form::new(DATETIME_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::DateTime {
+            prompt: "When would you like to come in?".to_string(),
+            default: Some(datetime("2024-06-03T09:00:00+10:00")),
+            min: Some(datetime("2024-06-01T00:00:00+10:00")),
+            max: Some(datetime("2024-06-30T17:00:00+10:00")),
+            timezone: FixedOffset::east_opt(10 * 3600),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Invalid and out-of-range times should be rejected before the script sees them, comparing
+    // across timezones
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("tomorrow at noon".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter a date and time in the format YYYY-MM-DD HH:MM.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("2024-05-31T13:59:00Z".to_string()))
+            .unwrap(),
+        FormPoll::Error(
+            "Please enter a date and time no earlier than 2024-06-01 00:00 +10:00.".to_string()
+        )
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Answers in other timezones should be converted to the question's
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("2024-05-31T14:00:00Z".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::DateTime(datetime("2024-06-01T00:00:00+10:00"))
+    );
+
+    // And answers without an offset should be taken to be in the question's timezone
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("2024-06-12T14:30".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "appointment": "2024-06-12T14:30:00+10:00" })
+    );
+}
@@ -0,0 +1,20 @@
+-- A form that asks for an amount of money
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "amount",
+				type = "decimal",
+				text = "How much would you like to donate?",
+				currency = "aud",
+				min = "5",
+				max = "10000",
+				default = "20",
+			},
+			{},
+		}
+	else
+		return { "done", { amount = answer.decimal }, nil }
+	end
+end
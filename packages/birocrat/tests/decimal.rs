@@ -0,0 +1,109 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static DECIMAL_SCRIPT: &str = include_str!("decimal.lua");
+
+#[test]
+fn should_parse_and_display_decimals() {
+    assert_eq!(Decimal::parse("12.5"), Some(Decimal::new(125, 1)));
+    assert_eq!(Decimal::parse(" -0.05 "), Some(Decimal::new(-5, 2)));
+    assert_eq!(Decimal::parse("1,234.50"), Some(Decimal::new(123450, 2)));
+    assert_eq!(Decimal::parse(".5"), Some(Decimal::new(5, 1)));
+    assert_eq!(Decimal::parse("12."), Some(Decimal::new(12, 0)));
+    assert_eq!(Decimal::parse("."), None);
+    assert_eq!(Decimal::parse("1e3"), None);
+    assert_eq!(Decimal::parse("$5"), None);
+
+    assert_eq!(
+        Decimal::new(125, 1).with_scale(2),
+        Some(Decimal::new(1250, 2))
+    );
+    assert_eq!(
+        Decimal::new(1250, 2).with_scale(1),
+        Some(Decimal::new(125, 1))
+    );
+    assert_eq!(Decimal::new(1255, 3).with_scale(2), None);
+
+    assert_eq!(Decimal::new(1250, 2).to_string(), "12.50");
+    assert_eq!(Decimal::new(-5, 2).to_string(), "-0.05");
+    assert_eq!(Decimal::new(42, 0).to_string(), "42");
+}
+
+#[test]
+fn should_validate_decimals() {
+    let vm = Lua::new();
+    let mut form = Form::new(DECIMAL_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Decimal {
+            prompt: "How much would you like to donate?".to_string(),
+            default: Some(Decimal::new(2000, 2)),
+            precision: 2,
+            currency: Some("AUD".to_string()),
+            min: Some(Decimal::new(500, 2)),
+            max: Some(Decimal::new(1000000, 2)),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("lots".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter a number.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("12.345".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter a number with at most 2 decimal places.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("4.99".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter at least 5.00 AUD.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Decimal(Decimal::new(1000001, 2)))
+            .unwrap(),
+        FormPoll::Error("Please enter at most 10000.00 AUD.".to_string())
+    );
+}
+
+#[test]
+fn should_give_exact_decimals_to_scripts() {
+    let vm = Lua::new();
+    let mut form = Form::new(DECIMAL_SCRIPT, json!({}), &vm).unwrap();
+    // This would be 20.099999999999998 as a float sum
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("20.1".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "amount": "20.10" }));
+}
+
+#[test]
+fn should_reject_invalid_decimal_questions() {
+    let vm = Lua::new();
+    let script = DECIMAL_SCRIPT.replace(r#"currency = "aud""#, r#"currency = "dollars""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidCurrency { currency }) if currency == "dollars"
+    ));
+
+    let script = DECIMAL_SCRIPT.replace(r#"default = "20""#, r#"default = "20.001""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidDecimalValue {
+            property: "default",
+            ..
+        })
+    ));
+
+    let script = DECIMAL_SCRIPT.replace(r#"min = "5""#, r#"min = "50000""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidDecimalRange { .. })
+    ));
+}
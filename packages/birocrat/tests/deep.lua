@@ -0,0 +1,17 @@
+-- A form whose internal state is nested as deeply as the parameters say, or contains itself
+function Main(state, answer, params)
+	local nested = {}
+	if params.cyclic then
+		nested.self = nested
+	else
+		for _ = 2, params.depth do
+			nested = { nested }
+		end
+	end
+
+	if state == nil then
+		return { "question", { id = "name", type = "simple", text = "Name?" }, { nested = nested } }
+	else
+		return { "done", { name = answer.text, data = { nested = nested } }, nil }
+	end
+end
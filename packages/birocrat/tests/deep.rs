@@ -0,0 +1,37 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static DEEP_SCRIPT: &str = include_str!("deep.lua");
+
+#[test]
+fn should_reject_deeply_nested_values() {
+    // The state has a table around the nested one, so this is as deep as it can go
+    let vm = Lua::new();
+    let depth = MAX_VALUE_DEPTH - 1;
+    assert!(Form::new(DEEP_SCRIPT, json!({ "depth": depth }), &vm).is_ok());
+
+    for params in [
+        json!({ "depth": MAX_VALUE_DEPTH }),
+        // This would overflow the stack if we tried to serialize it
+        json!({ "depth": 1_000_000 }),
+        json!({ "cyclic": true }),
+    ] {
+        let vm = Lua::new();
+        assert!(matches!(
+            Form::new(DEEP_SCRIPT, params, &vm),
+            Err(Error::ValueTooDeep { .. })
+        ));
+    }
+}
+
+#[test]
+fn should_reject_deeply_nested_results() {
+    // The result's nested table is one level deeper than the state's
+    let vm = Lua::new();
+    let mut form = Form::new(DEEP_SCRIPT, json!({ "depth": MAX_VALUE_DEPTH - 1 }), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("Alice".to_string())),
+        Err(Error::ValueTooDeep { .. })
+    ));
+}
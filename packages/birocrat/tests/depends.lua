@@ -0,0 +1,26 @@
+-- A form where later questions declare which earlier answers they depend on
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "name", type = "simple", text = "What is your name?" }, {} }
+	elseif state.name == nil then
+		return {
+			"question",
+			{ id = "newsletter", type = "simple", text = "Would you like our newsletter?", depends_on = {} },
+			{ name = answer.text },
+		}
+	elseif state.greeting == nil and state.asked_greeting == nil then
+		-- We don't use the newsletter answer at all
+		return {
+			"question",
+			{
+				id = "greeting",
+				type = "simple",
+				text = "How should we greet you, " .. state.name .. "?",
+				depends_on = { "name" },
+			},
+			{ name = state.name, asked_greeting = true },
+		}
+	else
+		return { "done", { name = state.name, greeting = answer.text }, nil }
+	end
+end
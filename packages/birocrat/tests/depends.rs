@@ -0,0 +1,57 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static DEPENDS_SCRIPT: &str = include_str!("depends.lua");
+
+fn text(text: &str) -> Answer {
+    Answer::Text(text.to_string())
+}
+
+#[test]
+fn should_keep_answers_that_dont_depend_on_changes() {
+    let vm = Lua::new();
+    let mut form = Form::new(DEPENDS_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, text("Alice")).unwrap();
+    form.progress_with_answer(1, text("Yes")).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(2, text("Hi")).unwrap(),
+        FormPoll::Done(_)
+    ));
+
+    // The script ignores the newsletter answer, so changing it leaves its state the same, and
+    // nothing after it needs to be asked again
+    assert!(matches!(
+        form.progress_with_answer(1, text("No")).unwrap(),
+        FormPoll::Question { answer: Some(Answer::Text(greeting)), .. } if greeting == "Hi"
+    ));
+    assert_eq!(form.next_index(), 3);
+
+    // Changing the name should answer the newsletter question again automatically, but stop at
+    // the greeting, which depends on it
+    let poll = form.progress_with_answer(0, text("Bob")).unwrap();
+    assert_eq!(
+        poll,
+        FormPoll::Question {
+            question: &Question::Simple {
+                prompt: "How should we greet you, Bob?".to_string(),
+                default: None,
+                pattern: None,
+                pattern_hint: None,
+                min_length: None,
+                max_length: None,
+                required: true,
+                common: QuestionCommon::default(),
+            },
+            answer: Some(&text("Hi")),
+        }
+    );
+    assert_eq!(form.next_index(), 2);
+    assert_eq!(form.answers().nth(1).unwrap().2, &text("No"));
+
+    form.progress_with_answer(2, text("Hey")).unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "name": "Bob", "greeting": "Hey" })
+    );
+}
@@ -0,0 +1,16 @@
+-- A form that asks for an email address
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "email",
+				type = "email",
+				text = "What is your email address?",
+			},
+			{},
+		}
+	else
+		return { "done", { email = answer.text }, nil }
+	end
+end
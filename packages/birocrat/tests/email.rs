@@ -0,0 +1,55 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static EMAIL_SCRIPT: &str = include_str!("email.lua");
+
+#[test]
+fn should_validate_emails() {
+    let vm = Lua::new();
+    let mut form = Form::new(EMAIL_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Email {
+            prompt: "What is your email address?".to_string(),
+            default: None,
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    for address in [
+        "alice",
+        "alice@",
+        "@example.com",
+        "alice@localhost",
+        "alice@example..com",
+        "alice@-example.com",
+        "alice smith@example.com",
+        "alice..smith@example.com",
+    ] {
+        assert_eq!(
+            form.progress_with_answer(0, Answer::Text(address.to_string()))
+                .unwrap(),
+            FormPoll::Error("Please enter a valid email address.".to_string()),
+            "{address} should be rejected"
+        );
+    }
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Surrounding whitespace should be trimmed before the script sees the address
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            Answer::Text(" alice.smith+forms@example.co.uk ".to_string())
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "email": "alice.smith+forms@example.co.uk" })
+    );
+}
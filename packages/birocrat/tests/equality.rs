@@ -0,0 +1,59 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+/// Answers the first two questions of the basic form.
+fn start(form: &mut Form) {
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+}
+
+#[test]
+fn should_keep_history_for_equal_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    start(&mut form);
+
+    // Giving exactly the same answer shouldn't clobber anything, and should give us the next
+    // question with its old answer
+    let poll = form
+        .progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert!(matches!(
+        poll,
+        FormPoll::Question { answer: Some(Answer::Text(text)), .. } if text == "25"
+    ));
+    assert_eq!(form.next_index(), 2);
+
+    // By default, trivially different answers are still different
+    form.progress_with_answer(0, Answer::Text("alice".to_string()))
+        .unwrap();
+    assert_eq!(form.next_index(), 1);
+}
+
+#[test]
+fn should_use_custom_answer_equality() {
+    let vm = Lua::new();
+    let options = FormOptions {
+        answer_eq: Some(|a, b| a.eq_ignore_case(b)),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(BASIC_SCRIPT, json!({ "id": 37 }), &vm, options).unwrap();
+    start(&mut form);
+
+    form.progress_with_answer(0, Answer::Text("ALICE".to_string()))
+        .unwrap();
+    assert_eq!(form.next_index(), 2);
+    // The answer the script saw should be kept
+    assert_eq!(
+        form.get_question(0).unwrap().1,
+        Some(&Answer::Text("Alice".to_string()))
+    );
+
+    assert!(Answer::Text(" Bob  Smith ".to_string())
+        .eq_ignore_whitespace(&Answer::Text("Bob Smith".to_string())));
+}
@@ -0,0 +1,18 @@
+-- A form that asks for a CV and then any supporting documents
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "cv", type = "file", text = "Please upload your CV.", extensions = { ".PDF", "docx" } },
+			{},
+		}
+	elseif state.cv == nil then
+		return {
+			"question",
+			{ id = "supporting", type = "file", text = "Any supporting documents?", multiple = true },
+			{ cv = answer.files[1] },
+		}
+	else
+		return { "done", { cv = state.cv, supporting = answer.files }, nil }
+	end
+end
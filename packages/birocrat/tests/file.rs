@@ -0,0 +1,65 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::path::PathBuf;
+
+static FILE_SCRIPT: &str = include_str!("file.lua");
+
+#[test]
+fn should_validate_files() {
+    let vm = Lua::new();
+    let mut form = Form::new(FILE_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::File {
+            prompt: "Please upload your CV.".to_string(),
+            extensions: vec!["pdf".to_string(), "docx".to_string()],
+            multiple: false,
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Files should have one of the given extensions, and there should only be one
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Files(vec![PathBuf::from("cv.txt")]))
+            .unwrap(),
+        FormPoll::Error("Please choose files of these types: .pdf, .docx.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Files(Vec::new()))
+            .unwrap(),
+        FormPoll::Error("Please choose a file.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(
+            0,
+            Answer::Files(vec![PathBuf::from("a.pdf"), PathBuf::from("b.pdf")])
+        )
+        .is_err());
+
+    // Text should be taken as a path
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("docs/CV.Pdf".to_string()))
+            .unwrap(),
+        FormPoll::Question { .. }
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::Files(vec![PathBuf::from("docs/CV.Pdf")])
+    );
+
+    // And several files can be given to questions that allow it, with any extension
+    assert!(matches!(
+        form.progress_with_answer(
+            1,
+            Answer::Options(vec!["ref.txt".to_string(), "photo.jpg".to_string()])
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "cv": "docs/CV.Pdf", "supporting": ["ref.txt", "photo.jpg"] })
+    );
+}
@@ -0,0 +1,26 @@
+-- A form that asks when the user got their licence, but only if they drive
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "drives",
+				type = "select",
+				text = "Do you drive?",
+				options = { "yes", "no" },
+				followup = {
+					when = { "yes" },
+					type = "date",
+					text = "When did you get your licence?",
+				},
+			},
+			{},
+		}
+	else
+		local since = nil
+		if answer.followup then
+			since = answer.followup.date
+		end
+		return { "done", { drives = answer.selected[1], since = since }, nil }
+	end
+end
@@ -0,0 +1,100 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static FOLLOWUP_SCRIPT: &str = include_str!("followup.lua");
+
+fn with_followup(selected: &str, followup: Answer) -> Answer {
+    Answer::WithFollowUp {
+        answer: Box::new(Answer::Options(vec![selected.to_string()])),
+        followup: Box::new(followup),
+    }
+}
+
+#[test]
+fn should_parse_followups() {
+    let vm = Lua::new();
+    let form = Form::new(FOLLOWUP_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Select {
+        followup: Some(followup),
+        ..
+    } = form.first_question().unwrap()
+    else {
+        panic!("expected select question with follow-up");
+    };
+    assert_eq!(followup.when, vec!["yes"]);
+    assert!(matches!(*followup.question, Question::Date { .. }));
+    assert!(followup.is_asked(&["yes".to_string()]));
+    assert!(!followup.is_asked(&["no".to_string()]));
+}
+
+#[test]
+fn should_only_need_followup_when_asked() {
+    let vm = Lua::new();
+    let mut form = Form::new(FOLLOWUP_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["no".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "drives": "no" }));
+
+    // Answering a follow-up that wasn't asked means the interface is broken
+    let mut form = Form::new(FOLLOWUP_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            with_followup("no", Answer::Text("2020-01-02".to_string()))
+        ),
+        Err(Error::InvalidAnswerType { .. })
+    ));
+}
+
+#[test]
+fn should_check_followup_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(FOLLOWUP_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Options(vec!["yes".to_string()]))
+            .unwrap(),
+        FormPoll::Error("Please answer \"When did you get your licence?\".".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, with_followup("yes", Answer::Text("soon".to_string())))
+            .unwrap(),
+        FormPoll::Error(
+            "When did you get your licence?: Please enter a date in the format YYYY-MM-DD."
+                .to_string()
+        )
+    );
+
+    // The pair goes to the script together, with the follow-up parsed like any other answer
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            with_followup("yes", Answer::Text("2020-01-02".to_string()))
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "drives": "yes", "since": "2020-01-02" })
+    );
+}
+
+#[test]
+fn should_reject_invalid_followups() {
+    let vm = Lua::new();
+    let script = FOLLOWUP_SCRIPT.replace(r#"when = { "yes" }"#, r#"when = { "maybe" }"#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::FollowUpWhenNotInOptions { value }) if value == "maybe"
+    ));
+
+    let script = FOLLOWUP_SCRIPT.replace(r#"type = "date""#, r#"type = "info""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidFollowUpType)
+    ));
+}
@@ -0,0 +1,25 @@
+-- A form that asks for the details of each of the user's dependents
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "dependents",
+				type = "group",
+				text = "Add each of your dependents",
+				fields = {
+					{ id = "name", text = "Name" },
+					{ id = "age", text = "Age", required = false, pattern = "[0-9]+" },
+				},
+				max = 3,
+			},
+			{},
+		}
+	else
+		local names = {}
+		for _, entry in ipairs(answer.entries) do
+			table.insert(names, entry.name)
+		end
+		return { "done", { names = names, first_age = answer.entries[1] and answer.entries[1].age }, nil }
+	end
+end
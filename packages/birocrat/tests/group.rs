@@ -0,0 +1,114 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::collections::HashMap;
+
+static GROUP_SCRIPT: &str = include_str!("group.lua");
+
+fn entries(entries: &[&[(&str, &str)]]) -> Answer {
+    Answer::Group(
+        entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .iter()
+                    .map(|(id, value)| (id.to_string(), value.to_string()))
+                    .collect::<HashMap<_, _>>()
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn should_parse_fields() {
+    let vm = Lua::new();
+    let form = Form::new(GROUP_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Group {
+        fields, min, max, ..
+    } = form.first_question().unwrap()
+    else {
+        panic!("expected group question");
+    };
+    assert_eq!((*min, *max), (None, Some(3)));
+    assert_eq!(
+        fields,
+        &vec![
+            GroupField {
+                id: "name".to_string(),
+                prompt: "Name".to_string(),
+                required: true,
+                pattern: None,
+                pattern_hint: None,
+            },
+            GroupField {
+                id: "age".to_string(),
+                prompt: "Age".to_string(),
+                required: false,
+                pattern: Some("[0-9]+".to_string()),
+                pattern_hint: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_give_entries_to_script() {
+    let vm = Lua::new();
+    let mut form = Form::new(GROUP_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            entries(&[&[("name", "Alex"), ("age", "7")], &[("name", "Sam")]])
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "names": ["Alex", "Sam"], "first_age": "7" })
+    );
+}
+
+#[test]
+fn should_validate_entries() {
+    let vm = Lua::new();
+    let mut form = Form::new(GROUP_SCRIPT, json!({}), &vm).unwrap();
+    // Missing required fields, values that don't match patterns, and too many entries are the
+    // user's mistakes
+    for answer in [
+        entries(&[&[("age", "7")]]),
+        entries(&[&[("name", "")]]),
+        entries(&[&[("name", "Alex"), ("age", "seven")]]),
+        entries(&[
+            &[("name", "A")],
+            &[("name", "B")],
+            &[("name", "C")],
+            &[("name", "D")],
+        ]),
+    ] {
+        assert!(matches!(
+            form.progress_with_answer(0, answer).unwrap(),
+            FormPoll::Error(_)
+        ));
+    }
+    // Fields the script didn't declare are the interface's
+    assert!(matches!(
+        form.progress_with_answer(0, entries(&[&[("name", "Alex"), ("colour", "red")]])),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+    // No entries at all is fine without a minimum
+    assert!(matches!(
+        form.progress_with_answer(0, entries(&[])).unwrap(),
+        FormPoll::Done(_)
+    ));
+}
+
+#[test]
+fn should_reject_invalid_bounds() {
+    let vm = Lua::new();
+    let script = GROUP_SCRIPT.replace("max = 3,", "min = 4, max = 3,");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidGroupRange { min: 4, max: 3 })
+    ));
+}
@@ -0,0 +1,79 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::collections::HashMap;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+#[test]
+fn should_hash_form_definitions_by_content() {
+    let hash = form_hash(BASIC_SCRIPT, &json!({ "id": 37, "name": "Alice" }));
+    // This must never change, or hashes callers have stored will stop matching
+    assert_eq!(
+        hash,
+        "5acd086696e3125c9cf6b6b6c31dc359df35a606bb5ff806be210e8f6594fd0f"
+    );
+    // Key order doesn't matter
+    let reordered: serde_json::Value =
+        serde_json::from_str(r#"{ "name": "Alice", "id": 37 }"#).unwrap();
+    assert_eq!(form_hash(BASIC_SCRIPT, &reordered), hash);
+
+    assert_ne!(
+        form_hash(BASIC_SCRIPT, &json!({ "id": 38, "name": "Alice" })),
+        hash
+    );
+    let script = format!("{BASIC_SCRIPT}\n");
+    assert_ne!(
+        form_hash(&script, &json!({ "id": 37, "name": "Alice" })),
+        hash
+    );
+}
+
+#[test]
+fn should_hash_transcripts_by_answers() {
+    let answer = |form: &mut Form, name: &str| {
+        form.progress_with_answer(0, Answer::Text(name.to_string()))
+            .unwrap();
+        form.progress_with_answer(1, Answer::Text("25".to_string()))
+            .unwrap();
+        form.transcript().hash()
+    };
+    let vm = Lua::new();
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    let hash = answer(&mut form, "Alice");
+    let vm = Lua::new();
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    assert_eq!(answer(&mut form, "Alice"), hash);
+    let vm = Lua::new();
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    assert_ne!(answer(&mut form, "Bob"), hash);
+}
+
+#[test]
+fn should_hash_page_answers_regardless_of_order() {
+    let entry = |answers: Vec<(&str, &str)>| Transcript {
+        entries: vec![TranscriptEntry {
+            id: "address".to_string(),
+            question: Question::Info {
+                prompt: String::new(),
+                common: QuestionCommon::default(),
+            },
+            answer: Answer::Page(
+                answers
+                    .into_iter()
+                    .map(|(id, text)| (id.to_string(), Answer::Text(text.to_string())))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            locale: None,
+        }],
+        seed: 0,
+        variants: HashMap::new(),
+    };
+    let a = entry(vec![("street", "1 Main St"), ("city", "Springfield")]);
+    let b = entry(vec![("city", "Springfield"), ("street", "1 Main St")]);
+    assert_eq!(a.hash(), b.hash());
+    assert_ne!(
+        a.hash(),
+        entry(vec![("street", "2 Main St"), ("city", "Springfield")]).hash()
+    );
+}
@@ -0,0 +1,23 @@
+-- A form that explains where to find the answer to its question
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "tfn",
+				type = "simple",
+				text = "What's your tax file number?",
+				help = "You can find this on your last tax return.",
+			},
+			{ step = "tfn" },
+		}
+	elseif state.step == "tfn" then
+		return {
+			"question",
+			{ id = "confirm", type = "select", text = "Is that correct?", options = { "yes", "no" } },
+			{ step = "confirm" },
+		}
+	else
+		return { "done", {}, nil }
+	end
+end
@@ -0,0 +1,37 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static HELP_SCRIPT: &str = include_str!("help.lua");
+
+#[test]
+fn should_parse_help_text() {
+    let vm = Lua::new();
+    let mut form = Form::new(HELP_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap().help(),
+        Some("You can find this on your last tax return.")
+    );
+
+    // It's optional
+    let poll = form
+        .progress_with_answer(0, Answer::Text("123456789".to_string()))
+        .unwrap();
+    let FormPoll::Question { question, .. } = poll else {
+        panic!("expected question");
+    };
+    assert_eq!(question.help(), None);
+}
+
+#[test]
+fn should_reject_invalid_help_text() {
+    let vm = Lua::new();
+    let script = HELP_SCRIPT.replace(
+        r#""You can find this on your last tax return.""#,
+        r#"{ "not", "text" }"#,
+    );
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidHelpProperty { .. })
+    ));
+}
@@ -0,0 +1,22 @@
+-- A form that explains what's about to happen before asking anything
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "intro",
+				type = "info",
+				text = "This form takes about a minute.\nYour answers are kept private.",
+			},
+			{ step = "intro" },
+		}
+	elseif state.step == "intro" then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What's your name?" },
+			{ step = "name", acknowledged = answer.type },
+		}
+	else
+		return { "done", { acknowledged = state.acknowledged, name = answer.text }, nil }
+	end
+end
@@ -0,0 +1,39 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static INFO_SCRIPT: &str = include_str!("info.lua");
+
+#[test]
+fn should_accept_acknowledgement() {
+    let vm = Lua::new();
+    let mut form = Form::new(INFO_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Info { prompt, .. } = form.first_question().unwrap() else {
+        panic!("expected info step");
+    };
+    assert!(prompt.contains("about a minute"));
+
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Acknowledged).unwrap(),
+        FormPoll::Question { .. }
+    ));
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("Sam".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "acknowledged": "acknowledged", "name": "Sam" })
+    );
+}
+
+#[test]
+fn should_reject_other_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(INFO_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("ok".to_string())),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+}
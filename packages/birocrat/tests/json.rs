@@ -0,0 +1,58 @@
+use birocrat::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn should_round_trip_answers_through_json() {
+    let answers = vec![
+        Answer::Text("Alice".to_string()),
+        Answer::Options(vec!["Indian".to_string(), "Korean".to_string()]),
+        Answer::OptionsWithText(vec![
+            ("Indian".to_string(), None),
+            ("Other".to_string(), Some("Thai".to_string())),
+        ]),
+        Answer::Group(vec![HashMap::from([(
+            "name".to_string(),
+            "Sam".to_string(),
+        )])]),
+        Answer::Acknowledged,
+        Answer::Skipped,
+        Answer::WithFollowUp {
+            answer: Box::new(Answer::Options(vec!["Yes".to_string()])),
+            followup: Box::new(Answer::Text("Because".to_string())),
+        },
+        Answer::Page(HashMap::from([
+            ("street".to_string(), Answer::Text("1 High St".to_string())),
+            ("unit".to_string(), Answer::Skipped),
+        ])),
+    ];
+    for answer in answers {
+        assert_eq!(Answer::from_json(answer.to_json()), Some(answer));
+    }
+}
+
+#[test]
+fn should_read_typed_answers_back_as_text() {
+    // The form parses these according to the question they answer
+    assert_eq!(
+        Answer::from_json(Answer::Rating(4).to_json()),
+        Some(Answer::Text("4".to_string()))
+    );
+    assert_eq!(
+        Answer::from_json(Answer::Decimal(Decimal::parse("12.50").unwrap()).to_json()),
+        Some(Answer::Text("12.50".to_string()))
+    );
+    assert_eq!(
+        Answer::from_json(Answer::Color(Color::parse("#ff8800").unwrap()).to_json()),
+        Some(Answer::Text("#ff8800".to_string()))
+    );
+}
+
+#[test]
+fn should_reject_invalid_json_answers() {
+    assert_eq!(Answer::from_json(json!(false)), None);
+    assert_eq!(Answer::from_json(json!([1, 2])), None);
+    assert_eq!(Answer::from_json(json!([["Other"]])), None);
+    assert_eq!(Answer::from_json(json!([{ "name": 1 }])), None);
+    assert_eq!(Answer::from_json(json!({ "street": false })), None);
+}
@@ -0,0 +1,18 @@
+-- A form that asks for a username and a short bio, both of which have length limits
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "username", type = "simple", text = "Choose a username", min_length = 3, max_length = 8 },
+			{ stage = "username" },
+		}
+	elseif state.stage == "username" then
+		return {
+			"question",
+			{ id = "bio", type = "multiline", text = "Tell us about yourself", max_length = 20 },
+			{ stage = "bio", username = answer.text },
+		}
+	else
+		return { "done", { username = state.username, bio = answer.text }, nil }
+	end
+end
@@ -0,0 +1,66 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static LENGTH_SCRIPT: &str = include_str!("length.lua");
+
+fn text(text: &str) -> Answer {
+    Answer::Text(text.to_string())
+}
+
+#[test]
+fn should_enforce_lengths() {
+    let vm = Lua::new();
+    let mut form = Form::new(LENGTH_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Simple {
+            min_length: Some(3),
+            max_length: Some(8),
+            ..
+        }
+    ));
+
+    assert_eq!(
+        form.progress_with_answer(0, text("al")).unwrap(),
+        FormPoll::Error("Please enter at least 3 characters (you've entered 2).".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, text("alexander")).unwrap(),
+        FormPoll::Error("Please enter at most 8 characters (you've entered 9).".to_string())
+    );
+    // Lengths are counted in characters, not bytes
+    assert!(matches!(
+        form.progress_with_answer(0, text("zoë")).unwrap(),
+        FormPoll::Question { .. }
+    ));
+
+    assert!(matches!(
+        form.progress_with_answer(1, text("I like long walks on the beach"))
+            .unwrap(),
+        FormPoll::Error(_)
+    ));
+    assert!(matches!(
+        form.progress_with_answer(1, text("I like Lua")).unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "username": "zoë", "bio": "I like Lua" })
+    );
+}
+
+#[test]
+fn should_reject_invalid_lengths() {
+    let vm = Lua::new();
+    let script = LENGTH_SCRIPT.replace("min_length = 3", "min_length = 10");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidLengthRange { min: 10, max: 8 })
+    ));
+    let script = LENGTH_SCRIPT.replace("min_length = 3", "min_length = \"three\"");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidLengthProperty { .. })
+    ));
+}
@@ -0,0 +1,18 @@
+-- A form with a typo in the default for one of its questions
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "colour",
+				type = "select",
+				text = "What is your favourite colour?",
+				options = { "Red", "Green", "Blue" },
+				default = "Gren",
+			},
+			{},
+		}
+	else
+		return { "done", { colour = answer.selected[1] }, nil }
+	end
+end
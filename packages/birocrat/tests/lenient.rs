@@ -0,0 +1,51 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static LENIENT_SCRIPT: &str = include_str!("lenient.lua");
+
+#[test]
+fn should_drop_invalid_defaults_in_lenient_mode() {
+    // Strict mode is the default
+    let vm = Lua::new();
+    assert!(matches!(
+        Form::new(LENIENT_SCRIPT, json!({}), &vm),
+        Err(Error::DefaultNotInOptions { default }) if default == "Gren"
+    ));
+
+    let vm = Lua::new();
+    let options = FormOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(LENIENT_SCRIPT, json!({}), &vm, options).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Select {
+            prompt: "What is your favourite colour?".to_string(),
+            defaults: Vec::new(),
+            options: vec!["Red".into(), "Green".into(), "Blue".into()],
+            multiple: false,
+            allow_other: false,
+            searchable: false,
+            min_selected: None,
+            max_selected: None,
+            followup: None,
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+    assert_eq!(
+        form.warnings(),
+        &[Warning::DefaultNotInOptions {
+            question_id: "colour".to_string(),
+            default: "Gren".to_string(),
+        }]
+    );
+
+    let poll = form
+        .progress_with_answer(0, Answer::Options(vec!["Green".to_string()]))
+        .unwrap();
+    assert!(matches!(poll, FormPoll::Done(_)));
+    assert_eq!(form.into_done().unwrap(), json!({ "colour": "Green" }));
+}
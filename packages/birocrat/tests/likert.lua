@@ -0,0 +1,28 @@
+-- A form that asks how much the user agrees with a statement, then how satisfied they are
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "easy",
+				type = "likert",
+				text = "The form was easy to fill in.",
+				default = "Agree",
+			},
+			{},
+		}
+	elseif state.easy == nil then
+		return {
+			"question",
+			{
+				id = "satisfied",
+				type = "likert",
+				text = "How satisfied are you?",
+				labels = { "Unsatisfied", "Neutral", "Satisfied" },
+			},
+			{ easy = answer.rating },
+		}
+	else
+		return { "done", { easy = state.easy, satisfied = answer.rating }, nil }
+	end
+end
@@ -0,0 +1,76 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static LIKERT_SCRIPT: &str = include_str!("likert.lua");
+
+#[test]
+fn should_default_to_agreement_scale() {
+    let vm = Lua::new();
+    let form = Form::new(LIKERT_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Likert {
+            prompt: "The form was easy to fill in.".to_string(),
+            default: Some(4),
+            labels: vec![
+                "Strongly disagree".to_string(),
+                "Disagree".to_string(),
+                "Neither agree nor disagree".to_string(),
+                "Agree".to_string(),
+                "Strongly agree".to_string(),
+            ],
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+}
+
+#[test]
+fn should_take_points_or_labels() {
+    let vm = Lua::new();
+    let mut form = Form::new(LIKERT_SCRIPT, json!({}), &vm).unwrap();
+    // Points off the scale, and text that isn't a label or a point, should be rejected
+    for answer in [
+        Answer::Rating(0),
+        Answer::Rating(6),
+        Answer::Text("Somewhat agree".to_string()),
+    ] {
+        assert!(matches!(
+            form.progress_with_answer(0, answer).unwrap(),
+            FormPoll::Error(_)
+        ));
+    }
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Labels are matched without case
+    form.progress_with_answer(0, Answer::Text(" strongly agree ".to_string()))
+        .unwrap();
+    assert_eq!(form.answers().next().unwrap().2, &Answer::Rating(5));
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("2".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "easy": 5, "satisfied": 2 })
+    );
+}
+
+#[test]
+fn should_reject_invalid_scales() {
+    let vm = Lua::new();
+    let script = LIKERT_SCRIPT.replace(r#"default = "Agree","#, r#"labels = { "Yes" },"#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::TooFewLikertLabels { count: 1 })
+    ));
+    let script = LIKERT_SCRIPT.replace(r#"default = "Agree","#, r#"default = "Maybe","#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidLikertDefault { .. })
+    ));
+}
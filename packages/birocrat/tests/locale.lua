@@ -0,0 +1,21 @@
+-- A form that interprets answers according to the locale they were given in
+local yes = { en = "yes", de = "ja" }
+
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "agree", type = "simple", text = "Do you agree?" }, {} }
+	elseif state.agreed == nil then
+		local agreed = answer.text == yes[answer.locale or "en"]
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What is your name?" },
+			{ agreed = agreed, agree_locale = answer.locale },
+		}
+	else
+		return {
+			"done",
+			{ agreed = state.agreed, agree_locale = state.agree_locale, name_locale = answer.locale },
+			nil,
+		}
+	end
+end
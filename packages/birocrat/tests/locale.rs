@@ -0,0 +1,36 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static LOCALE_SCRIPT: &str = include_str!("locale.lua");
+
+#[test]
+fn should_keep_answers_with_their_locales() {
+    let vm = Lua::new();
+    let mut form = Form::new(LOCALE_SCRIPT, json!({}), &vm).unwrap();
+    form.set_locale(Some("de".to_string()));
+    form.progress_with_answer(0, Answer::Text("ja".to_string()))
+        .unwrap();
+
+    // Switching locales part-way through shouldn't change how earlier answers are understood
+    form.set_locale(Some("en".to_string()));
+    assert_eq!(form.locale(), Some("en"));
+    form.progress_with_answer(1, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert_eq!(form.answer_locale("agree"), Some("de"));
+    assert_eq!(form.answer_locale("name"), Some("en"));
+    assert_eq!(form.transcript().entries[0].locale, Some("de".to_string()));
+
+    // The same text in a different locale is a different answer, so it shouldn't be taken from
+    // the cache
+    form.progress_with_answer(0, Answer::Text("ja".to_string()))
+        .unwrap();
+    assert_eq!(form.next_index(), 1);
+    assert_eq!(form.answer_locale("agree"), Some("en"));
+    form.progress_with_answer(1, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "agreed": false, "agree_locale": "en", "name_locale": "en" })
+    );
+}
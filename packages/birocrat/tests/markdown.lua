@@ -0,0 +1,23 @@
+-- A form that explains itself with some formatting before asking anything
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "intro",
+				type = "info",
+				text = "This form takes **five minutes**. You'll need:\n\n- your passport\n- your [tax file number](https://example.com/tfn)",
+				format = "markdown",
+			},
+			{ step = "intro" },
+		}
+	elseif state.step == "intro" then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What's your *full* name?" },
+			{ step = "name" },
+		}
+	else
+		return { "done", { name = answer.text }, nil }
+	end
+end
@@ -0,0 +1,40 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static MARKDOWN_SCRIPT: &str = include_str!("markdown.lua");
+
+#[test]
+fn should_parse_prompt_format() {
+    let vm = Lua::new();
+    let mut form = Form::new(MARKDOWN_SCRIPT, json!({}), &vm).unwrap();
+    let question = form.first_question().unwrap();
+    assert_eq!(question.format(), PromptFormat::Markdown);
+    // The prompt is left as the script gave it, for interfaces to render
+    assert!(question
+        .prompt()
+        .starts_with("This form takes **five minutes**."));
+
+    // Prompts are plain text unless the script says otherwise
+    let poll = form.progress_with_answer(0, Answer::Acknowledged).unwrap();
+    let FormPoll::Question { question, .. } = poll else {
+        panic!("expected question");
+    };
+    assert_eq!(question.format(), PromptFormat::Plain);
+}
+
+#[test]
+fn should_reject_unknown_prompt_formats() {
+    let vm = Lua::new();
+    let script = MARKDOWN_SCRIPT.replace(r#""markdown""#, r#""html""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::UnknownPromptFormat { format }) if format == "html"
+    ));
+
+    let script = MARKDOWN_SCRIPT.replace(r#""markdown""#, "true");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidFormatProperty { .. })
+    ));
+}
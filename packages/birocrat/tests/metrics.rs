@@ -0,0 +1,36 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+#[derive(Default)]
+struct Recorder {
+    steps: Mutex<Vec<StepMetrics>>,
+}
+impl Metrics for Recorder {
+    fn record_step(&self, step: StepMetrics) {
+        self.steps.lock().unwrap().push(step);
+    }
+}
+
+#[test]
+fn should_record_metrics_for_each_script_call() {
+    let recorder = Arc::new(Recorder::default());
+    let options = FormOptions {
+        metrics: Some(recorder.clone()),
+        ..Default::default()
+    };
+    let vm = Lua::new();
+    let mut form = Form::new_with_options(BASIC_SCRIPT, json!({ "id": 37 }), &vm, options).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+
+    let steps = recorder.steps.lock().unwrap();
+    assert_eq!(steps.len(), 2);
+    // The first state is `{ question = 1 }`, and the second adds the user's name
+    assert_eq!(steps[0].state_size, r#"{"question":1}"#.len());
+    assert!(steps[1].state_size > steps[0].state_size);
+    assert!(steps.iter().all(|step| step.vm_memory > 0));
+}
@@ -0,0 +1,19 @@
+-- A form that asks which notifications the user wants, with some ticked already
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "notifications",
+				type = "select",
+				text = "Which notifications would you like?",
+				options = { "email", "sms", "push" },
+				multiple = true,
+				default = { "email", "push" },
+			},
+			{},
+		}
+	else
+		return { "done", { notifications = answer.selected }, nil }
+	end
+end
@@ -0,0 +1,62 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static MULTIPLE_DEFAULTS_SCRIPT: &str = include_str!("multiple_defaults.lua");
+
+#[test]
+fn should_parse_list_of_defaults() {
+    let vm = Lua::new();
+    let form = Form::new(MULTIPLE_DEFAULTS_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select { defaults, .. } if defaults == &["email", "push"]
+    ));
+
+    // A single string still works
+    let script = MULTIPLE_DEFAULTS_SCRIPT.replace(r#"{ "email", "push" }"#, r#""sms""#);
+    let form = Form::new(&script, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select { defaults, .. } if defaults == &["sms"]
+    ));
+}
+
+#[test]
+fn should_reject_list_of_defaults_for_single_select() {
+    let vm = Lua::new();
+    let script = MULTIPLE_DEFAULTS_SCRIPT.replace("multiple = true", "multiple = false");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::MultipleDefaultsForSingleSelect)
+    ));
+}
+
+#[test]
+fn should_check_every_default_is_an_option() {
+    let vm = Lua::new();
+    let script =
+        MULTIPLE_DEFAULTS_SCRIPT.replace(r#"{ "email", "push" }"#, r#"{ "email", "pigeon" }"#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::DefaultNotInOptions { default }) if default == "pigeon"
+    ));
+
+    // In lenient mode, only the invalid one is dropped
+    let options = FormOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let form = Form::new_with_options(&script, json!({}), &vm, options).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select { defaults, .. } if defaults == &["email"]
+    ));
+    assert_eq!(
+        form.warnings(),
+        &[Warning::DefaultNotInOptions {
+            question_id: "notifications".to_string(),
+            default: "pigeon".to_string(),
+        }]
+    );
+}
@@ -0,0 +1,62 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+/// Answers the first two questions of the basic form.
+fn start(form: &mut Form) {
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+}
+
+#[test]
+fn should_require_exact_options_by_default() {
+    let vm = Lua::new();
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    start(&mut form);
+
+    assert!(form
+        .progress_with_answer(2, Answer::Options(vec!["italian".to_string()]))
+        .is_err());
+}
+
+#[test]
+fn should_match_normalized_options() {
+    let vm = Lua::new();
+    let options = FormOptions {
+        option_normalize: Some(fold_case_and_whitespace),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(BASIC_SCRIPT, json!({ "id": 37 }), &vm, options).unwrap();
+    start(&mut form);
+
+    // Selections that don't match any option are still invalid
+    assert!(form
+        .progress_with_answer(2, Answer::Options(vec!["Mexican".to_string()]))
+        .is_err());
+
+    form.progress_with_answer(2, Answer::Options(vec!["  INDIAN ".to_string()]))
+        .unwrap();
+    form.progress_with_answer(
+        3,
+        Answer::Options(vec!["very  hot".to_string(), "Mild".to_string()]),
+    )
+    .unwrap();
+    // Both the script and the host should only see the canonical options
+    assert_eq!(
+        form.answers().nth(3).unwrap().2,
+        &Answer::Options(vec!["Very Hot".to_string(), "Mild".to_string()])
+    );
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({
+            "name": "Alice",
+            "age": 25,
+            "favourite_cuisine": "Indian",
+            "spice_levels": ["Very Hot", "Mild"]
+        })
+    );
+}
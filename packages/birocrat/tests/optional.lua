@@ -0,0 +1,18 @@
+-- A form with an optional question, which reports whether it was skipped
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What's your name?" },
+			{ step = "name" },
+		}
+	elseif state.step == "name" then
+		return {
+			"question",
+			{ id = "nickname", type = "simple", text = "Do you have a nickname?", required = false },
+			{ step = "nickname", name = answer.text },
+		}
+	else
+		return { "done", { name = state.name, skipped = answer.type == "skipped", nickname = answer.text }, nil }
+	end
+end
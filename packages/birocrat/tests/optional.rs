@@ -0,0 +1,50 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static OPTIONAL_SCRIPT: &str = include_str!("optional.lua");
+
+#[test]
+fn should_allow_skipping_optional_questions() {
+    let vm = Lua::new();
+    let mut form = Form::new(OPTIONAL_SCRIPT, json!({}), &vm).unwrap();
+    assert!(form.first_question().unwrap().is_required());
+    let poll = form
+        .progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    let FormPoll::Question { question, .. } = poll else {
+        panic!("expected question");
+    };
+    assert!(!question.is_required());
+
+    let poll = form.progress_with_answer(1, Answer::Skipped).unwrap();
+    assert!(matches!(poll, FormPoll::Done(_)));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "name": "Alice", "skipped": true })
+    );
+}
+
+#[test]
+fn should_still_allow_answering_optional_questions() {
+    let vm = Lua::new();
+    let mut form = Form::new(OPTIONAL_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("Al".to_string()))
+        .unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "name": "Alice", "skipped": false, "nickname": "Al" })
+    );
+}
+
+#[test]
+fn should_not_allow_skipping_required_questions() {
+    let vm = Lua::new();
+    let mut form = Form::new(OPTIONAL_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Skipped),
+        Err(Error::InvalidAnswerType { .. })
+    ));
+}
@@ -0,0 +1,19 @@
+-- A form that asks for the user's country, from a list the host provides
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "country",
+				type = "select",
+				text = "Where do you live?",
+				options_from = "param.catalog.countries",
+				default = "Australia",
+				searchable = true,
+			},
+			{},
+		}
+	else
+		return { "done", { country = answer.selected[1] }, nil }
+	end
+end
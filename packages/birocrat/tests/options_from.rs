@@ -0,0 +1,71 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static OPTIONS_FROM_SCRIPT: &str = include_str!("options_from.lua");
+
+#[test]
+fn should_take_options_from_params() {
+    let vm = Lua::new();
+    let params = json!({ "catalog": { "countries": ["Australia", "Canada", "Japan"] } });
+    let mut form = Form::new(OPTIONS_FROM_SCRIPT, params, &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select { options, defaults, .. }
+            if options.iter().map(|option| option.value.as_str()).eq(["Australia", "Canada", "Japan"])
+                && defaults == &["Australia"]
+    ));
+
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["Japan".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "country": "Japan" }));
+}
+
+#[test]
+fn should_reject_missing_options_from() {
+    let vm = Lua::new();
+    assert!(matches!(
+        Form::new(OPTIONS_FROM_SCRIPT, json!({ "catalog": {} }), &vm),
+        Err(Error::OptionsFromNotFound { path }) if path == "param.catalog.countries"
+    ));
+    assert!(matches!(
+        Form::new(OPTIONS_FROM_SCRIPT, json!({ "catalog": "none" }), &vm),
+        Err(Error::OptionsFromNotFound { .. })
+    ));
+    let script = OPTIONS_FROM_SCRIPT.replace("param.catalog", "catalog");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidOptionsFromPath { .. })
+    ));
+}
+
+#[test]
+fn should_reject_options_from_that_arent_strings() {
+    let vm = Lua::new();
+    for countries in [
+        json!("Australia"),
+        json!(["Australia", 2]),
+        json!({ "a": "Australia" }),
+    ] {
+        let params = json!({ "catalog": { "countries": countries } });
+        assert!(matches!(
+            Form::new(OPTIONS_FROM_SCRIPT, params, &vm),
+            Err(Error::OptionsFromNotStrings { .. })
+        ));
+    }
+}
+
+#[test]
+fn should_reject_options_and_options_from() {
+    let vm = Lua::new();
+    let script =
+        OPTIONS_FROM_SCRIPT.replace("searchable", r#"options = { "Australia" }, searchable"#);
+    let params = json!({ "catalog": { "countries": ["Australia"] } });
+    assert!(matches!(
+        Form::new(&script, params, &vm),
+        Err(Error::OptionsAndOptionsFrom)
+    ));
+}
@@ -0,0 +1,21 @@
+-- A form that asks the user for their favourite colour, letting them give their own if it isn't
+-- one of the options
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "colour",
+				type = "select",
+				text = "What's your favourite colour?",
+				options = { "red", "green", "blue" },
+				multiple = params.multiple,
+				allow_other = true,
+				default = "teal",
+			},
+			{},
+		}
+	else
+		return { "done", { colours = answer.selected }, nil }
+	end
+end
@@ -0,0 +1,77 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static OTHER_SCRIPT: &str = include_str!("other.lua");
+
+fn select(selected: &[&str]) -> Answer {
+    Answer::Options(selected.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn should_accept_other_value() {
+    let vm = Lua::new();
+    let mut form = Form::new(OTHER_SCRIPT, json!({}), &vm).unwrap();
+    // The default doesn't have to be an option
+    let question = form.first_question().unwrap();
+    assert!(matches!(
+        question,
+        Question::Select {
+            allow_other: true,
+            defaults,
+            ..
+        } if defaults == &["teal"]
+    ));
+    assert!(question.is_other("teal"));
+    assert!(!question.is_other("red"));
+
+    assert!(matches!(
+        form.progress_with_answer(0, select(&["  teal "])).unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "colours": ["teal"] }));
+}
+
+#[test]
+fn should_reject_empty_other_value() {
+    let vm = Lua::new();
+    let mut form = Form::new(OTHER_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, select(&[" "])).unwrap(),
+        FormPoll::Error(_)
+    ));
+}
+
+#[test]
+fn should_allow_only_one_other_value() {
+    let vm = Lua::new();
+    let mut form = Form::new(OTHER_SCRIPT, json!({ "multiple": true }), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, select(&["teal", "mauve"])),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+    assert!(matches!(
+        form.progress_with_answer(0, select(&["red", "teal"]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "colours": ["red", "teal"] })
+    );
+}
+
+#[test]
+fn should_prefer_normalized_options() {
+    let vm = Lua::new();
+    let options = FormOptions {
+        option_normalize: Some(fold_case_and_whitespace),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(OTHER_SCRIPT, json!({}), &vm, options).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, select(&["Red"])).unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "colours": ["red"] }));
+}
@@ -0,0 +1,65 @@
+use birocrat::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+static COMPUTED_SCRIPT: &str = include_str!("computed.lua");
+
+/// Starts a form for a new session. This couldn't return a [`Form`], which would borrow a VM
+/// local to this function.
+fn start_session() -> OwnedForm {
+    OwnedForm::new(COMPUTED_SCRIPT, json!({ "source": "email" })).unwrap()
+}
+
+#[test]
+fn should_store_forms_in_sessions() {
+    let mut sessions = HashMap::new();
+    sessions.insert("first", start_session());
+    sessions.insert("second", start_session());
+
+    let form = sessions.get_mut("first").unwrap();
+    assert_eq!(form.first_question().unwrap().prompt(), "How old are you?");
+    form.progress_with_answer(1, Answer::Text("30".to_string()))
+        .unwrap();
+    form.progress_with_answer(3, Answer::Text("Alex".to_string()))
+        .unwrap();
+
+    let form = sessions.remove("first").unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "source": "email", "band": "adult", "name": "Alex" })
+    );
+    // The other session should be unaffected
+    let form = sessions.remove("second").unwrap();
+    assert_eq!(form.next_index(), 1);
+    assert!(form.into_done().is_err());
+}
+
+#[test]
+fn should_resume_owned_forms() {
+    let mut form = start_session();
+    form.progress_with_answer(1, Answer::Text("10".to_string()))
+        .unwrap();
+    let state = form.serialize_state().unwrap();
+    drop(form);
+
+    let mut form = OwnedForm::resume(COMPUTED_SCRIPT, json!({ "source": "email" }), state).unwrap();
+    assert_eq!(
+        form.next_question().unwrap().0.prompt(),
+        "What is your name?"
+    );
+    form.progress_with_answer(3, Answer::Text("Sam".to_string()))
+        .unwrap();
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "source": "email", "band": "minor", "name": "Sam" })
+    );
+}
+
+#[test]
+fn should_report_errors_creating_owned_forms() {
+    let err = OwnedForm::new("this isn't lua", json!({})).unwrap_err();
+    assert!(
+        matches!(err, error::Error::ScriptLoadFailed { .. }),
+        "{err:?}"
+    );
+}
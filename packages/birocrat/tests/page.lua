@@ -0,0 +1,30 @@
+-- A form that asks for an address all at once
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "address",
+				type = "page",
+				text = "Where do you live?",
+				questions = {
+					{ id = "street", type = "simple", text = "Street" },
+					{ id = "postcode", type = "simple", text = "Postcode", pattern = "[0-9]{4}" },
+					{ id = "country", type = "select", text = "Country", options = { "AU", "NZ" } },
+				},
+			},
+			{},
+		}
+	else
+		local answers = answer.answers
+		return {
+			"done",
+			{
+				street = answers.street.text,
+				postcode = answers.postcode.text,
+				country = answers.country.selected[1],
+			},
+			nil,
+		}
+	end
+end
@@ -0,0 +1,82 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::collections::HashMap;
+
+static PAGE_SCRIPT: &str = include_str!("page.lua");
+
+fn address(postcode: &str, country: &str) -> Answer {
+    Answer::Page(HashMap::from([
+        ("street".to_string(), Answer::Text("1 Main St".to_string())),
+        ("postcode".to_string(), Answer::Text(postcode.to_string())),
+        (
+            "country".to_string(),
+            Answer::Options(vec![country.to_string()]),
+        ),
+    ]))
+}
+
+#[test]
+fn should_accept_page_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(PAGE_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Page { questions, .. } = form.first_question().unwrap() else {
+        panic!("expected page");
+    };
+    let ids = questions
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["street", "postcode", "country"]);
+
+    assert!(matches!(
+        form.progress_with_answer(0, address("2000", "AU")).unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "street": "1 Main St", "postcode": "2000", "country": "AU" })
+    );
+}
+
+#[test]
+fn should_check_every_answer_on_page() {
+    let vm = Lua::new();
+    let mut form = Form::new(PAGE_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, address("20", "AU")).unwrap(),
+        FormPoll::Error(message) if message.starts_with("Postcode:")
+    ));
+    assert!(matches!(
+        form.progress_with_answer(0, address("2000", "UK")),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+
+    // Every question on the page has to be answered
+    let Answer::Page(mut answers) = address("2000", "AU") else {
+        unreachable!()
+    };
+    answers.remove("street");
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Page(answers)),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+}
+
+#[test]
+fn should_reject_invalid_pages() {
+    let vm = Lua::new();
+    let nested = PAGE_SCRIPT.replace(
+        r#"{ id = "street", type = "simple", text = "Street" },"#,
+        r#"{ id = "street", type = "page", text = "Street", questions = { { id = "a", type = "simple", text = "A" } } },"#,
+    );
+    assert!(matches!(
+        Form::new(&nested, json!({}), &vm),
+        Err(error::Error::NestedPage)
+    ));
+    let duplicate = PAGE_SCRIPT.replace(r#"id = "postcode""#, r#"id = "street""#);
+    assert!(matches!(
+        Form::new(&duplicate, json!({}), &vm),
+        Err(error::Error::DuplicatePageQuestionId { .. })
+    ));
+}
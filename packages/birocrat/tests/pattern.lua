@@ -0,0 +1,29 @@
+-- A form that asks for a UK postcode and an invoice number, both of which must match patterns
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "postcode",
+				type = "simple",
+				text = "What is your postcode?",
+				pattern = "[A-Z]{1,2}[0-9][A-Z0-9]? [0-9][A-Z]{2}",
+				pattern_hint = "Please enter a postcode like SW1A 1AA.",
+			},
+			{ stage = "postcode" },
+		}
+	elseif state.stage == "postcode" then
+		return {
+			"question",
+			{
+				id = "invoice",
+				type = "simple",
+				text = "What is your invoice number?",
+				pattern = "INV-\\d+",
+			},
+			{ stage = "invoice", postcode = answer.text },
+		}
+	else
+		return { "done", { postcode = state.postcode, invoice = answer.text }, nil }
+	end
+end
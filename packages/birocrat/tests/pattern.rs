@@ -0,0 +1,49 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static PATTERN_SCRIPT: &str = include_str!("pattern.lua");
+
+#[test]
+fn should_enforce_patterns() {
+    let vm = Lua::new();
+    let mut form = Form::new(PATTERN_SCRIPT, json!({}), &vm).unwrap();
+
+    // Patterns must match the whole answer
+    for postcode in ["sw1a 1aa", "SW1A 1AA!", "Postcode: SW1A 1AA"] {
+        assert_eq!(
+            form.progress_with_answer(0, Answer::Text(postcode.to_string()))
+                .unwrap(),
+            FormPoll::Error("Please enter a postcode like SW1A 1AA.".to_string()),
+            "{postcode} should be rejected"
+        );
+    }
+    form.progress_with_answer(0, Answer::Text("SW1A 1AA".to_string()))
+        .unwrap();
+
+    // Without a hint, there should still be a message
+    assert_eq!(
+        form.progress_with_answer(1, Answer::Text("12345".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter an answer in the expected format.".to_string())
+    );
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("INV-12345".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "postcode": "SW1A 1AA", "invoice": "INV-12345" })
+    );
+}
+
+#[test]
+fn should_reject_invalid_patterns() {
+    let vm = Lua::new();
+    let script = PATTERN_SCRIPT.replace("[0-9][A-Z]{2}", "[0-9][A-Z]{2");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidPattern { .. })
+    ));
+}
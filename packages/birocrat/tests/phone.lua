@@ -0,0 +1,18 @@
+-- A form that asks for a phone number
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "phone",
+				type = "phone",
+				text = "What is your phone number?",
+				region = "gb",
+				default = "020 7946 0958",
+			},
+			{},
+		}
+	else
+		return { "done", { phone = answer.text }, nil }
+	end
+end
@@ -0,0 +1,129 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static PHONE_SCRIPT: &str = include_str!("phone.lua");
+
+/// Answers the phone question in a new form from the given script, returning what the script saw
+/// or the error the user was given.
+fn answer(script: &str, number: &str) -> Result<String, String> {
+    let vm = Lua::new();
+    let mut form = Form::new(script, json!({}), &vm).unwrap();
+    match form
+        .progress_with_answer(0, Answer::Text(number.to_string()))
+        .unwrap()
+    {
+        FormPoll::Done(_) => Ok(form.into_done().unwrap()["phone"]
+            .as_str()
+            .unwrap()
+            .to_string()),
+        FormPoll::Error(msg) => Err(msg),
+        poll => panic!("unexpected poll {poll:?}"),
+    }
+}
+
+#[test]
+fn should_parse_phone_questions() {
+    let vm = Lua::new();
+    let form = Form::new(PHONE_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Phone {
+            prompt: "What is your phone number?".to_string(),
+            default: Some("+442079460958".to_string()),
+            region: Some("GB".to_string()),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+}
+
+#[test]
+fn should_normalize_to_e164() {
+    assert_eq!(
+        answer(PHONE_SCRIPT, "020 7946 0958").unwrap(),
+        "+442079460958"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "(020) 7946-0958").unwrap(),
+        "+442079460958"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+44 20 7946 0958").unwrap(),
+        "+442079460958"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "0033 1 23 45 67 89").unwrap(),
+        "+33123456789"
+    );
+    // Trunk prefixes kept after the country code aren't part of the number
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+44 (0)20 7946 0958").unwrap(),
+        "+442079460958"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+44 020 7946 0958").unwrap(),
+        "+442079460958"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+39 06 1234 5678").unwrap(),
+        "+390612345678"
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+7 812 123 45 67").unwrap(),
+        "+78121234567"
+    );
+
+    // Regions have their own prefixes
+    let us = PHONE_SCRIPT
+        .replace(r#""gb""#, r#""US""#)
+        .replace(r#"default = "020 7946 0958","#, "");
+    assert_eq!(answer(&us, "(415) 555-0132").unwrap(), "+14155550132");
+    assert_eq!(answer(&us, "1 415 555 0132").unwrap(), "+14155550132");
+    let it = us.replace(r#""US""#, r#""IT""#);
+    assert_eq!(answer(&it, "06 1234 5678").unwrap(), "+390612345678");
+}
+
+#[test]
+fn should_reject_invalid_numbers() {
+    assert_eq!(
+        answer(PHONE_SCRIPT, "call me").unwrap_err(),
+        "Please enter a phone number."
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "0123").unwrap_err(),
+        "Please enter a full phone number, including the area code."
+    );
+    assert_eq!(
+        answer(PHONE_SCRIPT, "+44 20 7946 0958 1234 5678").unwrap_err(),
+        "Please enter a full phone number, including the area code."
+    );
+
+    // Without a region, there has to be a country code
+    let script = PHONE_SCRIPT
+        .replace(r#"region = "gb","#, "")
+        .replace(r#""020 7946 0958""#, r#""+44 20 7946 0958""#);
+    assert_eq!(
+        answer(&script, "020 7946 0958").unwrap_err(),
+        "Please include the country code, like +44."
+    );
+    assert_eq!(
+        answer(&script, "+44 20 7946 0958").unwrap(),
+        "+442079460958"
+    );
+}
+
+#[test]
+fn should_reject_invalid_phone_questions() {
+    let vm = Lua::new();
+    let script = PHONE_SCRIPT.replace(r#""gb""#, r#""XX""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::UnknownPhoneRegion { region }) if region == "XX"
+    ));
+    let script = PHONE_SCRIPT.replace(r#"region = "gb","#, "");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidPhoneDefault { .. })
+    ));
+}
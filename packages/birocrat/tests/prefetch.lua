@@ -0,0 +1,22 @@
+-- A form that looks up the price of the plan the user chooses through a host function, which
+-- might be slow, and tries out ways of upselling to those who choose the pro plan
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "plan",
+				type = "select",
+				text = "Which plan would you like?",
+				options = { "Basic", "Pro" },
+			},
+			{},
+		}
+	else
+		local plan = answer.selected[1]
+		if plan == "Pro" then
+			birocrat.variant("upsell", { "banner", "email" })
+		end
+		return { "done", { plan = plan, price = Price(plan) }, nil }
+	end
+end
@@ -0,0 +1,112 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+static PREFETCH_SCRIPT: &str = include_str!("prefetch.lua");
+
+/// Creates a VM with a `Price` host function, which counts how many times it's called. If
+/// `impure` is set, it will mark itself as impure.
+fn vm_with_price(impure: bool) -> (Lua, Rc<Cell<usize>>) {
+    let vm = Lua::new();
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    let price = vm
+        .create_function(move |lua, plan: String| {
+            counter.set(counter.get() + 1);
+            if impure {
+                mark_impure(lua);
+            }
+            Ok(if plan == "Pro" { 20 } else { 5 })
+        })
+        .unwrap();
+    vm.globals().set("Price", price).unwrap();
+    (vm, calls)
+}
+
+#[test]
+fn should_use_prefetched_states() {
+    let (vm, calls) = vm_with_price(false);
+    let mut form = Form::new(PREFETCH_SCRIPT, json!({}), &vm).unwrap();
+
+    // This should be bounded, and stop once every option has been tried
+    assert_eq!(form.prefetch(1), 1);
+    assert_eq!(form.prefetch(5), 1);
+    assert_eq!(form.prefetch(5), 0);
+    assert_eq!(calls.get(), 2);
+
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["Pro".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(calls.get(), 2);
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "plan": "Pro", "price": 20 })
+    );
+}
+
+#[test]
+fn should_discard_impure_prefetched_states() {
+    let (vm, calls) = vm_with_price(true);
+    let mut form = Form::new(PREFETCH_SCRIPT, json!({}), &vm).unwrap();
+
+    assert_eq!(form.prefetch(5), 2);
+    assert_eq!(calls.get(), 2);
+
+    // The script has to be called again, because the price might have changed
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["Basic".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(calls.get(), 3);
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "plan": "Basic", "price": 5 })
+    );
+}
+
+#[test]
+fn should_only_assign_variants_from_prefetched_states_once_used() {
+    let (vm, _) = vm_with_price(false);
+    let mut form = Form::new(PREFETCH_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(form.prefetch(5), 2);
+    // The script was given a variant for the pro plan, but the user hasn't chosen it yet
+    assert!(form.variants().is_empty());
+
+    form.progress_with_answer(0, Answer::Options(vec!["Basic".to_string()]))
+        .unwrap();
+    assert!(form.variants().is_empty());
+    form.progress_with_answer(0, Answer::Options(vec!["Pro".to_string()]))
+        .unwrap();
+    assert!(form.variants().contains_key("upsell"));
+}
+
+#[derive(Default)]
+struct Recorder {
+    steps: Mutex<Vec<StepMetrics>>,
+}
+impl Metrics for Recorder {
+    fn record_step(&self, step: StepMetrics) {
+        self.steps.lock().unwrap().push(step);
+    }
+}
+
+#[test]
+fn should_not_record_metrics_for_prefetched_states() {
+    let recorder = Arc::new(Recorder::default());
+    let options = FormOptions {
+        metrics: Some(recorder.clone()),
+        ..Default::default()
+    };
+    let (vm, _) = vm_with_price(false);
+    let mut form = Form::new_with_options(PREFETCH_SCRIPT, json!({}), &vm, options).unwrap();
+    assert_eq!(form.prefetch(5), 2);
+    assert_eq!(recorder.steps.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,23 @@
+-- A form that asks one of two variants of a question, at random
+function Main(state, answer, params)
+	if state == nil then
+		local variant = birocrat.random("greeting", 2)
+		local prompt = variant == 1 and "What's your name?" or "What should we call you?"
+		return {
+			"question",
+			{ id = "name", type = "simple", text = prompt },
+			{ variant = variant },
+		}
+	else
+		return {
+			"done",
+			{
+				name = answer.text,
+				variant = state.variant,
+				fraction = birocrat.random("fraction"),
+				roll = birocrat.random("roll", -3, 3),
+			},
+			nil,
+		}
+	end
+end
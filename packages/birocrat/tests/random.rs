@@ -0,0 +1,85 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::{json, Value};
+
+static RANDOM_SCRIPT: &str = include_str!("random.lua");
+
+/// Runs the form with the given seed, returning its output.
+fn run(seed: u64, vm: &Lua) -> Value {
+    let options = FormOptions {
+        seed: Some(seed),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(RANDOM_SCRIPT, json!({}), vm, options).unwrap();
+    assert_eq!(form.seed(), seed);
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert_eq!(form.transcript().seed, seed);
+    form.into_done().unwrap()
+}
+
+#[test]
+fn should_replay_with_same_seed() {
+    let vm = Lua::new();
+    let output = run(42, &vm);
+    assert_eq!(run(42, &vm), output);
+
+    let fraction = output["fraction"].as_f64().unwrap();
+    assert!((0.0..1.0).contains(&fraction));
+    let roll = output["roll"].as_i64().unwrap();
+    assert!((-3..=3).contains(&roll));
+}
+
+#[test]
+fn should_vary_with_seed() {
+    let vm = Lua::new();
+    let outputs = (0..20).map(|seed| run(seed, &vm)).collect::<Vec<_>>();
+    // Both variants should come up across enough seeds
+    for variant in [1, 2] {
+        assert!(outputs.iter().any(|output| output["variant"] == variant));
+    }
+    assert!(outputs
+        .iter()
+        .any(|output| output["fraction"] != outputs[0]["fraction"]));
+}
+
+#[test]
+fn should_generate_seed() {
+    let vm = Lua::new();
+    let a = Form::new(RANDOM_SCRIPT, json!({}), &vm).unwrap();
+    let b = Form::new(RANDOM_SCRIPT, json!({}), &vm).unwrap();
+    assert_ne!(a.seed(), b.seed());
+}
+
+#[test]
+fn should_keep_seeds_apart_in_shared_script() {
+    let vm = Lua::new();
+    let expected = run(1, &vm);
+    assert_ne!(run(2, &vm), expected);
+
+    // Forms from the same script share its environment, so starting another form mustn't change
+    // what the first one sees
+    let script = Script::load(RANDOM_SCRIPT, &vm).unwrap();
+    let start = |seed| {
+        let options = FormOptions {
+            seed: Some(seed),
+            ..Default::default()
+        };
+        Form::from_script(&script, json!({}), options).unwrap()
+    };
+    let mut a = start(1);
+    let _b = start(2);
+    a.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert_eq!(a.into_done().unwrap(), expected);
+}
+
+#[test]
+fn should_reject_empty_intervals() {
+    let vm = Lua::new();
+    let script = RANDOM_SCRIPT.replace(r#""greeting", 2"#, r#""greeting", 2, 1"#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::RunDriverFailed { .. })
+    ));
+}
@@ -0,0 +1,20 @@
+-- A form that asks for a number within a range, in steps
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "volume",
+				type = "range",
+				text = "How loud should notifications be?",
+				min = 0,
+				max = 1,
+				step = 0.1,
+				default = 0.5,
+			},
+			{},
+		}
+	else
+		return { "done", { volume = answer.value }, nil }
+	end
+end
@@ -0,0 +1,88 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static RANGE_SCRIPT: &str = include_str!("range.lua");
+
+#[test]
+fn should_validate_ranges() {
+    let vm = Lua::new();
+    let mut form = Form::new(RANGE_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Range {
+            prompt: "How loud should notifications be?".to_string(),
+            default: Some(0.5),
+            min: 0.0,
+            max: 1.0,
+            step: Some(0.1),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Numbers outside the range, or between steps, should be rejected
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Range(1.5)).unwrap(),
+        FormPoll::Error("Please choose a number from 0 to 1.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("0.25".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please choose a number in steps of 0.1, starting from 0.".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("loud".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter a number.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Steps shouldn't be thrown off by floating point error
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text(" 0.3 ".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.answers().next().unwrap().2, &Answer::Range(0.3));
+    assert_eq!(form.into_done().unwrap(), json!({ "volume": 0.3 }));
+}
+
+#[test]
+fn should_reject_invalid_ranges() {
+    let vm = Lua::new();
+    let script = RANGE_SCRIPT.replace("step = 0.1", "step = 0");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidRangeBounds { .. })
+    ));
+    let script = RANGE_SCRIPT.replace("max = 1,", "");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::MissingRangeBounds)
+    ));
+    let script = RANGE_SCRIPT.replace("default = 0.5", "default = 2");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidRangeDefault { .. })
+    ));
+}
+
+#[test]
+fn should_reject_non_finite_ranges() {
+    let vm = Lua::new();
+    for script in [
+        RANGE_SCRIPT.replace("min = 0,", "min = 0 / 0,"),
+        RANGE_SCRIPT.replace("max = 1,", "max = 0 / 0,"),
+        RANGE_SCRIPT.replace("max = 1,", "max = math.huge,"),
+        RANGE_SCRIPT.replace("step = 0.1", "step = 0 / 0"),
+        RANGE_SCRIPT.replace("step = 0.1", "step = math.huge"),
+    ] {
+        assert!(matches!(
+            Form::new(&script, json!({}), &vm),
+            Err(error::Error::InvalidRangeBounds { .. })
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+-- A form that asks the user to prioritise some features
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "features",
+				type = "rank",
+				text = "Which features matter most to you?",
+				options = {
+					{ value = "speed", label = "Speed" },
+					{ value = "price", label = "Price" },
+					"support",
+				},
+			},
+			{},
+		}
+	else
+		return { "done", { priorities = answer.selected }, nil }
+	end
+end
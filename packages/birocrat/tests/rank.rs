@@ -0,0 +1,70 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static RANK_SCRIPT: &str = include_str!("rank.lua");
+
+fn ranking(order: &[&str]) -> Answer {
+    Answer::Options(order.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn should_accept_permutation() {
+    let vm = Lua::new();
+    let mut form = Form::new(RANK_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Rank { options, .. } = form.first_question().unwrap() else {
+        panic!("expected rank question");
+    };
+    assert_eq!(options.len(), 3);
+    assert_eq!(options[0].label(), "Speed");
+
+    assert!(matches!(
+        form.progress_with_answer(0, ranking(&["support", "speed", "price"]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "priorities": ["support", "speed", "price"] })
+    );
+}
+
+#[test]
+fn should_reject_non_permutations() {
+    let vm = Lua::new();
+    let mut form = Form::new(RANK_SCRIPT, json!({}), &vm).unwrap();
+    for order in [
+        &["speed", "price"][..],
+        &["speed", "price", "price"],
+        &["speed", "price", "support", "colour"],
+        &["speed", "price", "Support"],
+    ] {
+        assert!(matches!(
+            form.progress_with_answer(0, ranking(order)),
+            Err(error::Error::InvalidAnswerType { .. })
+        ));
+    }
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("speed".to_string())),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+}
+
+#[test]
+fn should_match_normalized_options() {
+    let vm = Lua::new();
+    let options = FormOptions {
+        option_normalize: Some(fold_case_and_whitespace),
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(RANK_SCRIPT, json!({}), &vm, options).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, ranking(&[" Price", "SPEED", "support"]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "priorities": ["price", "speed", "support"] })
+    );
+}
@@ -0,0 +1,21 @@
+-- A form that asks for a rating on a labelled scale
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "recommend",
+				type = "rating",
+				text = "How likely are you to recommend us?",
+				min = 0,
+				max = 10,
+				min_label = "Not at all likely",
+				max_label = "Extremely likely",
+				default = 5,
+			},
+			{},
+		}
+	else
+		return { "done", { recommend = answer.rating }, nil }
+	end
+end
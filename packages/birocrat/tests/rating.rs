@@ -0,0 +1,57 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static RATING_SCRIPT: &str = include_str!("rating.lua");
+
+#[test]
+fn should_validate_ratings() {
+    let vm = Lua::new();
+    let mut form = Form::new(RATING_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Rating {
+            prompt: "How likely are you to recommend us?".to_string(),
+            default: Some(5),
+            min: 0,
+            max: 10,
+            min_label: Some("Not at all likely".to_string()),
+            max_label: Some("Extremely likely".to_string()),
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Ratings outside the scale, or that aren't numbers, should be rejected
+    for answer in [
+        Answer::Rating(11),
+        Answer::Text("7.5".to_string()),
+        Answer::Text("great".to_string()),
+    ] {
+        assert_eq!(
+            form.progress_with_answer(0, answer).unwrap(),
+            FormPoll::Error("Please give a rating from 0 to 10.".to_string())
+        );
+    }
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text(" 9 ".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.answers().next().unwrap().2, &Answer::Rating(9));
+    assert_eq!(form.into_done().unwrap(), json!({ "recommend": 9 }));
+}
+
+#[test]
+fn should_reject_invalid_scales() {
+    let vm = Lua::new();
+    let script = RATING_SCRIPT.replace("min = 0", "min = 11");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidRatingRange { min: 11, max: 10 })
+    ));
+}
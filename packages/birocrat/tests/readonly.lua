@@ -0,0 +1,23 @@
+-- A form with a consent that can't be withdrawn once given
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "consent",
+				type = "select",
+				text = "Do you agree to the terms?",
+				options = { "Yes", "No" },
+				readonly = true,
+			},
+			{},
+		}
+	elseif state.consent == nil then
+		if answer.selected[1] ~= "Yes" then
+			return { "error", "You must agree to the terms to continue.", nil }
+		end
+		return { "question", { id = "name", type = "simple", text = "What is your name?" }, { consent = true } }
+	else
+		return { "done", { name = answer.text }, nil }
+	end
+end
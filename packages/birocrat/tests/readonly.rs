@@ -0,0 +1,43 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static READONLY_SCRIPT: &str = include_str!("readonly.lua");
+
+#[test]
+fn should_lock_readonly_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(READONLY_SCRIPT, json!({}), &vm).unwrap();
+    assert!(form.first_question().unwrap().is_readonly());
+
+    // Answers the script rejects aren't given, so they don't lock the question
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Options(vec!["No".to_string()]))
+            .unwrap(),
+        FormPoll::Error("You must agree to the terms to continue.".to_string())
+    );
+    let poll = form
+        .progress_with_answer(0, Answer::Options(vec!["Yes".to_string()]))
+        .unwrap();
+    assert!(matches!(poll, FormPoll::Question { question, .. } if !question.is_readonly()));
+    form.progress_with_answer(1, Answer::Text("Alice".to_string()))
+        .unwrap();
+
+    // Now the consent can't be changed, but giving the same answer again is fine
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["No".to_string()])),
+        Err(Error::AnswerLocked { question_id }) if question_id == "consent"
+    ));
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["Yes".to_string()]))
+            .unwrap(),
+        FormPoll::Question { answer: Some(Answer::Text(name)), .. } if name == "Alice"
+    ));
+    // Other answers can still be changed as usual
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("Bob".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "name": "Bob" }));
+}
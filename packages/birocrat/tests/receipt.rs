@@ -0,0 +1,89 @@
+#![cfg(feature = "receipts")]
+
+use birocrat::{error::Error, *};
+use ed25519_dalek::SigningKey;
+use mlua::Lua;
+use serde_json::json;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+fn complete(vm: &Lua) -> Form<'_> {
+    let mut form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+    assert!(matches!(
+        form.progress_with_answer(2, Answer::Options(vec!["Italian".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    form
+}
+
+#[test]
+fn should_sign_and_verify_receipts() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let vm = Lua::new();
+    let form = complete(&vm);
+    let receipt = form.receipt(&key).unwrap();
+    assert_eq!(receipt.transcript_hash, form.transcript().hash());
+    receipt.verify(&key.verifying_key()).unwrap();
+    receipt
+        .verify_transcript(&key.verifying_key(), &form.transcript())
+        .unwrap();
+
+    // A different key won't do
+    let other_key = SigningKey::from_bytes(&[8; 32]);
+    assert!(matches!(
+        receipt.verify(&other_key.verifying_key()),
+        Err(Error::InvalidReceiptSignature { .. })
+    ));
+}
+
+#[test]
+fn should_detect_tampering() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let vm = Lua::new();
+    let form = complete(&vm);
+
+    let mut receipt = form.receipt(&key).unwrap();
+    receipt.output["age"] = json!(99);
+    assert!(matches!(
+        receipt.verify(&key.verifying_key()),
+        Err(Error::InvalidReceiptSignature { .. })
+    ));
+
+    let receipt = form.receipt(&key).unwrap();
+    let mut transcript = form.transcript();
+    transcript.entries[0].answer = Answer::Text("Bob".to_string());
+    assert!(matches!(
+        receipt.verify_transcript(&key.verifying_key(), &transcript),
+        Err(Error::ReceiptTranscriptMismatch)
+    ));
+}
+
+#[test]
+fn should_only_give_receipts_for_completed_forms() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let vm = Lua::new();
+    let form = Form::new(BASIC_SCRIPT, json!({ "id": 37 }), &vm).unwrap();
+    assert!(form.receipt(&key).is_none());
+}
+
+#[test]
+fn should_verify_receipts_after_serializing() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let vm = Lua::new();
+    let form = complete(&vm);
+    let receipt = form.receipt(&key).unwrap();
+    let transcript = form.transcript();
+
+    let receipt: Receipt = serde_json::from_str(&serde_json::to_string(&receipt).unwrap()).unwrap();
+    let transcript: Transcript =
+        serde_json::from_str(&serde_json::to_string(&transcript).unwrap()).unwrap();
+    assert_eq!(transcript, form.transcript());
+    receipt
+        .verify_transcript(&key.verifying_key(), &transcript)
+        .unwrap();
+}
@@ -0,0 +1,20 @@
+-- A form with answers of varying sensitivity
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "name", type = "simple", text = "What is your name?" }, {} }
+	elseif state.name == nil then
+		return {
+			"question",
+			{ id = "condition", type = "simple", text = "What is your condition?", tags = { "secret" } },
+			{ name = answer.text },
+		}
+	elseif state.condition == nil then
+		return {
+			"question",
+			{ id = "token", type = "secret", text = "What is your insurance token?" },
+			{ name = state.name, condition = answer.text },
+		}
+	else
+		return { "done", { name = state.name, condition = state.condition, token = answer.text }, nil }
+	end
+end
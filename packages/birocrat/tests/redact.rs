@@ -0,0 +1,77 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static REDACT_SCRIPT: &str = include_str!("redact.lua");
+
+#[test]
+fn should_redact_secret_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(REDACT_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("Gout".to_string()))
+        .unwrap();
+    form.progress_with_answer(2, Answer::Text("tok-1234".to_string()))
+        .unwrap();
+
+    let redacted = form
+        .redact()
+        .entries
+        .into_iter()
+        .map(|entry| entry.answer)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        redacted,
+        vec![
+            Answer::Text("Alice".to_string()),
+            Answer::Text(REDACTED.to_string()),
+            Answer::Text(REDACTED.to_string()),
+        ]
+    );
+    // The full transcript should still have everything
+    assert_eq!(
+        form.transcript().entries[1].answer,
+        Answer::Text("Gout".to_string())
+    );
+
+    // Logging the form shouldn't leak anything, even from the script's state or output
+    let debug = format!("{form:?}");
+    assert!(debug.contains("Alice"));
+    assert!(!debug.contains("Gout"));
+    assert!(!debug.contains("tok-1234"));
+}
+
+#[test]
+fn should_redact_secret_answers_in_debug_output() {
+    let vm = Lua::new();
+    let mut form = Form::new(REDACT_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("Gout".to_string()))
+        .unwrap();
+    form.progress_with_answer(2, Answer::Text("tok-1234".to_string()))
+        .unwrap();
+
+    let debug = format!("{:?}", form.transcript());
+    assert!(debug.contains("Alice"));
+    assert!(!debug.contains("Gout"));
+    assert!(!debug.contains("tok-1234"));
+    // The store and its history can hold answers too
+    let debug = format!("{:?}", form.store());
+    assert!(!debug.contains("Gout"));
+    assert!(!debug.contains("tok-1234"));
+
+    // Changing the name brings the condition back up with its previous answer
+    let poll = form
+        .progress_with_answer(0, Answer::Text("Alex".to_string()))
+        .unwrap();
+    assert!(matches!(
+        poll,
+        FormPoll::Question {
+            answer: Some(Answer::Text(_)),
+            ..
+        }
+    ));
+    assert!(!format!("{poll:?}").contains("Gout"));
+}
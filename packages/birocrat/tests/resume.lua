@@ -0,0 +1,28 @@
+-- A form that books an appointment for an API user, and gives them a random desk
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "token", type = "secret", text = "What is your API token?" },
+			{ desk = birocrat.random("desk", 1, 100) },
+		}
+	elseif state.token == nil then
+		return {
+			"question",
+			{ id = "when", type = "datetime", text = "When would you like to come in?", timezone = "+10:00" },
+			{ desk = state.desk, token = answer.text },
+		}
+	elseif state.when == nil then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What is your name?" },
+			{ desk = state.desk, token = state.token, when = answer.datetime },
+		}
+	else
+		return {
+			"done",
+			{ desk = state.desk, token = state.token, when = state.when, name = answer.text, team = params.team },
+			nil,
+		}
+	end
+end
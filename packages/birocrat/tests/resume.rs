@@ -0,0 +1,119 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::{json, Value};
+
+static RESUME_SCRIPT: &str = include_str!("resume.lua");
+
+/// Starts the form and answers its first two questions, returning its saved state, as it would be
+/// stored.
+fn saved_state(vm: &Lua) -> String {
+    let mut form = Form::new(RESUME_SCRIPT, json!({ "team": "ops" }), vm).unwrap();
+    form.set_locale(Some("en-AU".to_string()));
+    form.progress_with_answer(0, Answer::Text("correct-horse".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("2024-06-01 09:30".to_string()))
+        .unwrap();
+    serde_json::to_string(&form.serialize_state().unwrap()).unwrap()
+}
+
+#[test]
+fn should_resume_where_form_left_off() {
+    let vm = Lua::new();
+    let state = saved_state(&vm);
+
+    let resume_vm = Lua::new();
+    let state = serde_json::from_str::<Value>(&state).unwrap();
+    let mut form =
+        Form::resume(RESUME_SCRIPT, json!({ "team": "ops" }), state, &resume_vm).unwrap();
+    assert_eq!(form.next_index(), 2);
+    assert_eq!(
+        form.next_question().unwrap().0.prompt(),
+        "What is your name?"
+    );
+    assert_eq!(form.locale(), Some("en-AU"));
+    assert_eq!(form.answer_locale("token"), Some("en-AU"));
+    assert_eq!(
+        form.store().answer("token"),
+        Some(&Answer::Secret(SecretText::new("correct-horse")))
+    );
+    let Some(Answer::DateTime(when)) = form.store().answer("when") else {
+        panic!("expected datetime answer");
+    };
+    assert_eq!(when.to_rfc3339(), "2024-06-01T09:30:00+10:00");
+
+    assert!(matches!(
+        form.progress_with_answer(2, Answer::Text("Alex".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    let output = form.into_done().unwrap();
+    assert_eq!(output["name"], "Alex");
+    assert_eq!(output["team"], "ops");
+    assert_eq!(output["token"], "correct-horse");
+    assert_eq!(output["when"], "2024-06-01T09:30:00+10:00");
+}
+
+#[test]
+fn should_keep_seed_when_resuming() {
+    let vm = Lua::new();
+    let mut form = Form::new(RESUME_SCRIPT, json!({}), &vm).unwrap();
+    let state = form.serialize_state().unwrap();
+    let mut resumed = Form::resume(RESUME_SCRIPT, json!({}), state, &vm).unwrap();
+    assert_eq!(resumed.seed(), form.seed());
+
+    // Changing the first answer reruns the script from the start, which should roll the same desk
+    for form in [&mut form, &mut resumed] {
+        form.progress_with_answer(0, Answer::Text("battery-staple".to_string()))
+            .unwrap();
+        form.progress_with_answer(1, Answer::Text("2024-06-01 09:30".to_string()))
+            .unwrap();
+        form.progress_with_answer(2, Answer::Text("Alex".to_string()))
+            .unwrap();
+    }
+    assert_eq!(resumed.into_done().unwrap(), form.into_done().unwrap());
+}
+
+#[test]
+fn should_reject_unknown_state_versions() {
+    let vm = Lua::new();
+    let mut state = serde_json::from_str::<Value>(&saved_state(&vm)).unwrap();
+    state["version"] = json!(99);
+    let err = Form::resume(RESUME_SCRIPT, json!({}), state, &vm).unwrap_err();
+    assert!(
+        matches!(err, Error::UnsupportedStateVersion { version: 99, .. }),
+        "{err:?}"
+    );
+
+    let err = Form::resume(RESUME_SCRIPT, json!({}), json!({ "version": 1 }), &vm).unwrap_err();
+    assert!(
+        matches!(err, Error::DeserializeSavedStateFailed { .. }),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn should_open_read_only() {
+    let vm = Lua::new();
+    let state = serde_json::from_str::<Value>(&saved_state(&vm)).unwrap();
+    let options = FormOptions {
+        read_only: true,
+        ..Default::default()
+    };
+    let mut form =
+        Form::resume_with_options(RESUME_SCRIPT, json!({ "team": "ops" }), state, &vm, options)
+            .unwrap();
+    assert_eq!(form.transcript().entries.len(), 2);
+
+    // Neither a new answer nor a changed one should be accepted
+    for idx in [2, 0] {
+        let err = form
+            .progress_with_answer(idx, Answer::Text("Alex".to_string()))
+            .unwrap_err();
+        assert!(matches!(err, Error::FormReadOnly), "{err:?}");
+    }
+    assert_eq!(form.next_index(), 2);
+    assert!(matches!(
+        form.get_question(0).unwrap().1,
+        Some(Answer::Secret(_))
+    ));
+}
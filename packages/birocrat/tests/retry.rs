@@ -0,0 +1,64 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+fn form_with_limit(vm: &Lua, max_errors: Option<usize>) -> Form<'_> {
+    let options = FormOptions {
+        max_errors,
+        ..Default::default()
+    };
+    let mut form = Form::new_with_options(BASIC_SCRIPT, json!({ "id": 37 }), vm, options).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form
+}
+
+#[test]
+fn should_give_up_after_too_many_errors() {
+    let vm = Lua::new();
+    let mut form = form_with_limit(&vm, Some(2));
+
+    for _ in 0..2 {
+        assert!(matches!(
+            form.progress_with_answer(1, Answer::Text("old".to_string()))
+                .unwrap(),
+            FormPoll::Error(_)
+        ));
+    }
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("very old".to_string())),
+        Err(error::Error::TooManyErrors { question_id, errors: 3 }) if question_id == "2"
+    ));
+
+    // The count should start again after that, and be reset by a good answer
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("old".to_string()))
+            .unwrap(),
+        FormPoll::Error(_)
+    ));
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+    for _ in 0..2 {
+        assert!(matches!(
+            form.progress_with_answer(1, Answer::Text("old".to_string()))
+                .unwrap(),
+            FormPoll::Error(_)
+        ));
+    }
+}
+
+#[test]
+fn should_allow_unlimited_errors_by_default() {
+    let vm = Lua::new();
+    let mut form = form_with_limit(&vm, None);
+
+    for _ in 0..10 {
+        assert!(matches!(
+            form.progress_with_answer(1, Answer::Text("old".to_string()))
+                .unwrap(),
+            FormPoll::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+-- A form that asks for a range of years, and checks they're in order once both are known
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "start", type = "simple", text = "Start year?" }, {} }
+	elseif state.start == nil then
+		return { "question", { id = "end", type = "simple", text = "End year?" }, { start = answer.text } }
+	else
+		return { "done", { start = tonumber(state.start), ["end"] = tonumber(answer.text) }, nil }
+	end
+end
+
+function Review(answers, params)
+	if tonumber(answers["end"].text) < tonumber(answers.start.text) then
+		return {
+			start = "The start year must be before the end year.",
+			["end"] = "The end year must be after the start year.",
+		}
+	end
+end
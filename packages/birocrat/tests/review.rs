@@ -0,0 +1,46 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::collections::HashMap;
+
+static REVIEW_SCRIPT: &str = include_str!("review.lua");
+
+#[test]
+fn should_reject_answers_in_review() {
+    let vm = Lua::new();
+    let mut form = Form::new(REVIEW_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("2020".to_string()))
+        .unwrap();
+
+    // The review should reject these, without accepting the last answer
+    let poll = form
+        .progress_with_answer(1, Answer::Text("2010".to_string()))
+        .unwrap();
+    assert_eq!(
+        poll,
+        FormPoll::Rejected(HashMap::from([
+            (
+                "start".to_string(),
+                "The start year must be before the end year.".to_string()
+            ),
+            (
+                "end".to_string(),
+                "The end year must be after the start year.".to_string()
+            ),
+        ]))
+    );
+    assert_eq!(form.next_question_id(), Some("end"));
+    assert_eq!(form.answers().count(), 1);
+
+    // Fixing the first answer should let the second through
+    form.progress_with_answer(0, Answer::Text("2000".to_string()))
+        .unwrap();
+    let poll = form
+        .progress_with_answer(1, Answer::Text("2010".to_string()))
+        .unwrap();
+    assert!(matches!(poll, FormPoll::Done(_)));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "start": 2000, "end": 2010 })
+    );
+}
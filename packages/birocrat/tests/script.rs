@@ -0,0 +1,73 @@
+use birocrat::*;
+use mlua::{Function, Lua};
+use serde_json::json;
+
+static REVIEW_SCRIPT: &str = include_str!("review.lua");
+
+#[test]
+fn should_create_many_forms_from_one_script() {
+    let vm = Lua::new();
+    let script = Script::load(REVIEW_SCRIPT, &vm).unwrap();
+    // The script's globals shouldn't have leaked into the VM
+    assert!(vm
+        .globals()
+        .get::<_, Option<Function>>("Main")
+        .unwrap()
+        .is_none());
+
+    let mut first = Form::from_script(&script, json!({}), FormOptions::default()).unwrap();
+    let mut second = Form::from_script(&script, json!({}), FormOptions::default()).unwrap();
+    first
+        .progress_with_answer(0, Answer::Text("2000".to_string()))
+        .unwrap();
+    second
+        .progress_with_answer(0, Answer::Text("2020".to_string()))
+        .unwrap();
+    first
+        .progress_with_answer(1, Answer::Text("2010".to_string()))
+        .unwrap();
+
+    // The forms should be completely independent
+    assert_eq!(second.next_question_id(), Some("end"));
+    assert_eq!(
+        first.into_done().unwrap(),
+        json!({ "start": 2000, "end": 2010 })
+    );
+    // And the script's `Review` should still be used
+    assert!(matches!(
+        second.progress_with_answer(1, Answer::Text("2010".to_string())),
+        Ok(FormPoll::Rejected(_))
+    ));
+}
+
+/// A script that asks a question using a global it declares.
+static GLOBAL_SCRIPT: &str = r#"
+Title = "{title}"
+function Main(state, answer, params)
+    if state == nil then
+        return { "question", { id = "name", type = "simple", text = Title }, {} }
+    else
+        return { "done", { title = Title }, nil }
+    end
+end
+"#;
+
+#[test]
+fn should_isolate_forms_sharing_a_vm() {
+    let vm = Lua::new();
+    let mut first = Form::new(&GLOBAL_SCRIPT.replace("{title}", "First"), json!({}), &vm).unwrap();
+    let second = Form::new(&GLOBAL_SCRIPT.replace("{title}", "Second"), json!({}), &vm).unwrap();
+
+    // Loading the second script shouldn't have changed anything for the first
+    assert_eq!(first.first_question().unwrap().prompt(), "First");
+    assert_eq!(second.first_question().unwrap().prompt(), "Second");
+    first
+        .progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    assert_eq!(first.into_done().unwrap(), json!({ "title": "First" }));
+    assert!(vm
+        .globals()
+        .get::<_, Option<String>>("Title")
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,10 @@
+-- A form that asks for an API token
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "token", type = "secret", text = "What is your API token?" }, {} }
+	elseif #answer.text < 8 then
+		return { "error", "That token is too short.", nil }
+	else
+		return { "done", { token_length = #answer.text }, nil }
+	end
+end
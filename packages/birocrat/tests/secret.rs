@@ -0,0 +1,34 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static SECRET_SCRIPT: &str = include_str!("secret.lua");
+
+#[test]
+fn should_redact_secret_answers() {
+    let vm = Lua::new();
+    let mut form = Form::new(SECRET_SCRIPT, json!({}), &vm).unwrap();
+    // Text answers to secret questions should be checked by the script as usual
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("hunter2".to_string()))
+            .unwrap(),
+        FormPoll::Error("That token is too short.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("correct-horse".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+
+    // But they should be stored as secrets, which don't show up in debug output
+    let (_, _, answer) = form.answers().next().unwrap();
+    assert_eq!(answer, &Answer::Secret(SecretText::new("correct-horse")));
+    let debug = format!("{answer:?}");
+    assert!(!debug.contains("correct-horse"));
+    assert!(debug.contains("redacted"));
+
+    assert_eq!(form.into_done().unwrap(), json!({ "token_length": 13 }));
+}
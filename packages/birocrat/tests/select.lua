@@ -0,0 +1,27 @@
+-- A form that asks the user to choose a plan, with labels, descriptions, and images for the options
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "plan",
+				type = "select",
+				text = "Which plan would you like?",
+				options = {
+					{
+						value = "basic",
+						label = "Basic",
+						description = "For individuals",
+						image = "https://example.com/basic.png",
+					},
+					{ value = "team", label = "Team" },
+					"enterprise",
+				},
+				default = "team",
+			},
+			{},
+		}
+	else
+		return { "done", { plan = answer.selected[1] }, nil }
+	end
+end
@@ -0,0 +1,103 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static SELECT_SCRIPT: &str = include_str!("select.lua");
+
+#[test]
+fn should_parse_option_tables() {
+    let vm = Lua::new();
+    let mut form = Form::new(SELECT_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Select {
+        options, defaults, ..
+    } = form.first_question().unwrap()
+    else {
+        panic!("expected select question");
+    };
+    assert_eq!(defaults, &["team"]);
+    assert_eq!(
+        options,
+        &vec![
+            SelectOption {
+                value: "basic".to_string(),
+                label: Some("Basic".to_string()),
+                description: Some("For individuals".to_string()),
+                image: Some("https://example.com/basic.png".to_string()),
+                specify: false,
+            },
+            SelectOption {
+                value: "team".to_string(),
+                label: Some("Team".to_string()),
+                description: None,
+                image: None,
+                specify: false,
+            },
+            SelectOption::from("enterprise"),
+        ]
+    );
+    assert_eq!(options[0].label(), "Basic");
+    assert_eq!(options[2].label(), "enterprise");
+
+    // Answers are made of values, not labels
+    assert!(form
+        .progress_with_answer(0, Answer::Options(vec!["Basic".to_string()]))
+        .is_err());
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Options(vec!["basic".to_string()]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(form.into_done().unwrap(), json!({ "plan": "basic" }));
+}
+
+#[test]
+fn should_require_option_values() {
+    let vm = Lua::new();
+    let script = SELECT_SCRIPT.replace(
+        r#"{ value = "team", label = "Team" }"#,
+        r#"{ label = "Team" }"#,
+    );
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::NoOptionsInQuestionData { .. })
+    ));
+}
+
+#[test]
+fn should_reject_invalid_option_images() {
+    let vm = Lua::new();
+    let script = SELECT_SCRIPT.replace(
+        r#"image = "https://example.com/basic.png""#,
+        r#"image = { "not", "a", "url" }"#,
+    );
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::NoOptionsInQuestionData { .. })
+    ));
+}
+
+#[test]
+fn should_parse_searchable_hint() {
+    let vm = Lua::new();
+    let form = Form::new(SELECT_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select {
+            searchable: false,
+            ..
+        }
+    ));
+
+    let script = SELECT_SCRIPT.replace(
+        r#"default = "team","#,
+        r#"default = "team", searchable = true,"#,
+    );
+    let form = Form::new(&script, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select {
+            searchable: true,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,21 @@
+-- A form that asks the user to choose up to two toppings, but at least one
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "toppings",
+				type = "select",
+				text = "Which toppings would you like?",
+				options = { "cheese", "olives", "mushrooms" },
+				multiple = true,
+				allow_other = true,
+				min_selected = 1,
+				max_selected = 2,
+			},
+			{},
+		}
+	else
+		return { "done", { toppings = answer.selected }, nil }
+	end
+end
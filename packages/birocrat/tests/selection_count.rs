@@ -0,0 +1,80 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static SELECTION_COUNT_SCRIPT: &str = include_str!("selection_count.lua");
+
+fn toppings(selected: &[&str]) -> Answer {
+    Answer::Options(selected.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn should_enforce_selection_counts() {
+    let vm = Lua::new();
+    let mut form = Form::new(SELECTION_COUNT_SCRIPT, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.first_question().unwrap(),
+        Question::Select {
+            min_selected: Some(1),
+            max_selected: Some(2),
+            ..
+        }
+    ));
+
+    assert_eq!(
+        form.progress_with_answer(0, toppings(&[])).unwrap(),
+        FormPoll::Error("Please choose at least 1 option.".to_string())
+    );
+    // The user's own value counts too
+    assert_eq!(
+        form.progress_with_answer(0, toppings(&["cheese", "olives", "pineapple"]))
+            .unwrap(),
+        FormPoll::Error("Please choose at most 2 options.".to_string())
+    );
+    assert!(matches!(
+        form.progress_with_answer(0, toppings(&["cheese", "pineapple"]))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "toppings": ["cheese", "pineapple"] })
+    );
+}
+
+#[test]
+fn should_reject_invalid_selection_counts() {
+    let vm = Lua::new();
+    let script = SELECTION_COUNT_SCRIPT.replace("min_selected = 1", "min_selected = 3");
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(error::Error::InvalidSelectionCountRange { min: 3, max: 2 })
+    ));
+}
+
+#[test]
+fn should_not_count_the_same_option_twice() {
+    let vm = Lua::new();
+    let options = FormOptions {
+        option_normalize: Some(fold_case),
+        ..Default::default()
+    };
+    let script = SELECTION_COUNT_SCRIPT.replace("min_selected = 1", "min_selected = 2");
+    let mut form = Form::new_with_options(&script, json!({}), &vm, options).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, toppings(&["cheese", "cheese"])),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+    assert!(matches!(
+        form.progress_with_answer(0, toppings(&["Cheese", "cheese"])),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+    // Invalid options are a problem with the interface, not something the user can fix
+    let vm = Lua::new();
+    let script = SELECTION_COUNT_SCRIPT.replace("allow_other = true", "allow_other = false");
+    let mut form = Form::new(&script, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, toppings(&["cheese", "olives", "ham"])),
+        Err(error::Error::InvalidAnswerType { .. })
+    ));
+}
@@ -0,0 +1,12 @@
+-- A form that accidentally puts functions into its state and output
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What is your name?" },
+			{ helpers = { format = string.format } },
+		}
+	else
+		return { "done", { names = { answer.text, string.upper } }, nil }
+	end
+end
@@ -0,0 +1,29 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static SERIALIZE_SCRIPT: &str = include_str!("serialize.lua");
+
+#[test]
+fn should_report_path_to_unserializable_state() {
+    let vm = Lua::new();
+    let err = Form::new(SERIALIZE_SCRIPT, json!({}), &vm).unwrap_err();
+    assert!(
+        matches!(&err, error::Error::SerializeStateFailed { path, .. } if path == "helpers.format"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn should_report_path_to_unserializable_output() {
+    let vm = Lua::new();
+    let script = SERIALIZE_SCRIPT.replace("{ helpers = { format = string.format } }", "{}");
+    let mut form = Form::new(&script, json!({}), &vm).unwrap();
+    let err = form
+        .progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap_err();
+    assert!(
+        matches!(&err, error::Error::SerializeAnswersFailed { path, .. } if path == "names[1]"),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,23 @@
+-- A form that asks about dietary requirements, some of which need details
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "diet",
+				type = "select",
+				text = "Do you have any dietary requirements?",
+				options = {
+					"vegetarian",
+					"vegan",
+					{ value = "allergies", label = "Allergies (please specify)", specify = true },
+				},
+				multiple = true,
+				allow_other = true,
+			},
+			{},
+		}
+	else
+		return { "done", { diet = answer.selected, details = answer.details or {} }, nil }
+	end
+end
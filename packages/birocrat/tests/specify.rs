@@ -0,0 +1,89 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static SPECIFY_SCRIPT: &str = include_str!("specify.lua");
+
+#[test]
+fn should_require_details_for_specify_options() {
+    let vm = Lua::new();
+    let mut form = Form::new(SPECIFY_SCRIPT, json!({}), &vm).unwrap();
+    let Question::Select { options, .. } = form.first_question().unwrap() else {
+        panic!("expected select question");
+    };
+    assert!(!options[0].specify);
+    assert!(options[2].specify);
+
+    // Leaving out the details is the user's mistake
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Options(vec!["allergies".to_string()]))
+            .unwrap(),
+        FormPoll::Error("Please give some details for \"Allergies (please specify)\".".to_string())
+    );
+    assert_eq!(
+        form.progress_with_answer(
+            0,
+            Answer::OptionsWithText(vec![("allergies".to_string(), Some("  ".to_string()))])
+        )
+        .unwrap(),
+        FormPoll::Error("Please give some details for \"Allergies (please specify)\".".to_string())
+    );
+    // But details for options that don't take them mean the interface is broken
+    assert!(form
+        .progress_with_answer(
+            0,
+            Answer::OptionsWithText(vec![("vegan".to_string(), Some("Strict".to_string()))])
+        )
+        .is_err());
+    assert!(form
+        .progress_with_answer(
+            0,
+            Answer::OptionsWithText(vec![("Halal".to_string(), Some("Strict".to_string()))])
+        )
+        .is_err());
+
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            Answer::OptionsWithText(vec![
+                ("vegan".to_string(), None),
+                ("allergies".to_string(), Some(" Peanuts ".to_string())),
+            ])
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::OptionsWithText(vec![
+            ("vegan".to_string(), None),
+            ("allergies".to_string(), Some("Peanuts".to_string())),
+        ])
+    );
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "diet": ["vegan", "allergies"], "details": { "allergies": "Peanuts" } })
+    );
+}
+
+#[test]
+fn should_accept_plain_options_without_details() {
+    let vm = Lua::new();
+    let mut form = Form::new(SPECIFY_SCRIPT, json!({}), &vm).unwrap();
+    // Selections without any options that need details are always given as plain options
+    assert!(matches!(
+        form.progress_with_answer(
+            0,
+            Answer::OptionsWithText(vec![
+                ("vegetarian".to_string(), None),
+                ("Halal".to_string(), None),
+            ])
+        )
+        .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::Options(vec!["vegetarian".to_string(), "Halal".to_string()])
+    );
+}
@@ -0,0 +1,72 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static BASIC_SCRIPT: &str = include_str!("basic.lua");
+
+/// A store that keeps everything in memory, but records which answers it was given.
+#[derive(Debug, Default)]
+struct RecordingStore {
+    inner: MemoryStore,
+    saved: Vec<String>,
+}
+impl AnswerStore for RecordingStore {
+    fn answer(&self, id: &str) -> Option<&Answer> {
+        self.inner.answer(id)
+    }
+    fn set_answer(&mut self, id: String, answer: Answer) {
+        self.saved.push(id.clone());
+        self.inner.set_answer(id, answer);
+    }
+    fn history_len(&self) -> usize {
+        self.inner.history_len()
+    }
+    fn history_entry(&self, idx: usize) -> Option<&HistoryEntry> {
+        self.inner.history_entry(idx)
+    }
+    fn push_history(&mut self, entry: HistoryEntry) {
+        self.inner.push_history(entry);
+    }
+    fn truncate_history(&mut self, len: usize) {
+        self.inner.truncate_history(len);
+    }
+}
+
+#[test]
+fn should_use_custom_store() {
+    let vm = Lua::new();
+    let mut form = Form::new_with_store(
+        BASIC_SCRIPT,
+        json!({ "id": 37 }),
+        &vm,
+        FormOptions::default(),
+        RecordingStore::default(),
+    )
+    .unwrap();
+
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    // Rejected answers shouldn't be stored
+    form.progress_with_answer(1, Answer::Text("twenty-five".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+    // Changing an earlier answer should clobber the history after it
+    form.progress_with_answer(0, Answer::Text("Bob".to_string()))
+        .unwrap();
+    assert_eq!(form.next_index(), 1);
+    // The old answer to the next question should still be suggested
+    assert_eq!(
+        form.next_question().unwrap().1,
+        Some(&Answer::Text("25".to_string()))
+    );
+
+    assert_eq!(form.store().saved, vec!["1", "2", "1"]);
+
+    let transcript = form.transcript();
+    assert_eq!(transcript.entries.len(), 1);
+    assert_eq!(
+        transcript.entries[0].answer,
+        Answer::Text("Bob".to_string())
+    );
+}
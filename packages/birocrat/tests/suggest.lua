@@ -0,0 +1,18 @@
+-- A form that asks where the user lives, suggesting cities and streets as they type
+function Main(state, answer, params)
+	if state == nil then
+		return { "question", { id = "city", type = "simple", text = "Which city do you live in?" }, {} }
+	elseif state.city == nil then
+		return { "question", { id = "street", type = "simple", text = "Which street?" }, { city = answer.text } }
+	else
+		return { "done", { city = state.city, street = answer.text }, nil }
+	end
+end
+
+function Suggest(id, text, params)
+	if id == "city" then
+		return nil
+	end
+	-- Provided by the host
+	return find_streets(params.country, text)
+end
@@ -0,0 +1,70 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static SUGGEST_SCRIPT: &str = include_str!("suggest.lua");
+
+#[test]
+fn should_suggest_from_tables() {
+    let vm = Lua::new();
+    let script = r#"
+        Suggest = { city = { "Sydney", "Melbourne", "Sunshine Coast" } }
+        function Main(state, answer, params)
+            if state == nil then
+                return { "question", { id = "city", type = "simple", text = "City?" }, {} }
+            end
+            return { "done", { city = answer.text }, nil }
+        end
+    "#;
+    let form = Form::new(script, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.suggest("city", "s").unwrap(),
+        vec!["Sydney", "Sunshine Coast"]
+    );
+    assert_eq!(form.suggest("city", "MEL").unwrap(), vec!["Melbourne"]);
+    assert_eq!(form.suggest("city", "").unwrap().len(), 3);
+    // Questions that haven't been asked can't be suggested for
+    let err = form.suggest("street", "s").unwrap_err();
+    assert!(
+        matches!(err, Error::QuestionNotAsked { ref question_id } if question_id == "street"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn should_suggest_from_functions() {
+    let vm = Lua::new();
+    let find_streets = vm
+        .create_function(|_, (country, text): (String, String)| {
+            Ok(vec![format!("{text} Street, {country}")])
+        })
+        .unwrap();
+    vm.globals().set("find_streets", find_streets).unwrap();
+    let mut form = Form::new(SUGGEST_SCRIPT, json!({ "country": "AU" }), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Sydney".to_string()))
+        .unwrap();
+
+    assert_eq!(
+        form.suggest("street", "George").unwrap(),
+        vec!["George Street, AU"]
+    );
+    assert!(form.suggest("city", "Syd").unwrap().is_empty());
+    // Getting suggestions shouldn't change the form
+    assert_eq!(form.next_index(), 1);
+    assert_eq!(form.next_question_id(), Some("street"));
+}
+
+#[test]
+fn should_not_suggest_without_suggest() {
+    let vm = Lua::new();
+    let form = Form::new(include_str!("basic.lua"), json!({ "id": 1 }), &vm).unwrap();
+    assert!(form.suggest("1", "A").unwrap().is_empty());
+}
+
+#[test]
+fn should_reject_invalid_suggest() {
+    let vm = Lua::new();
+    let script = format!("{SUGGEST_SCRIPT}\nSuggest = 5");
+    let err = Form::new(&script, json!({}), &vm).unwrap_err();
+    assert!(matches!(err, Error::InvalidSuggest { .. }), "{err:?}");
+}
@@ -0,0 +1,24 @@
+-- A form that tags its questions about the user, so interfaces can treat them differently
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What's your name?", tags = { "pii" } },
+			{ step = "name" },
+		}
+	elseif state.step == "name" then
+		return {
+			"question",
+			{ id = "age", type = "simple", text = "How old are you?", tags = { "pii", "demographics" } },
+			{ step = "age" },
+		}
+	elseif state.step == "age" then
+		return {
+			"question",
+			{ id = "feedback", type = "multiline", text = "How was your stay?" },
+			{ step = "feedback" },
+		}
+	else
+		return { "done", {}, nil }
+	end
+end
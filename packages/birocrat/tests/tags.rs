@@ -0,0 +1,71 @@
+use birocrat::{error::Error, *};
+use mlua::Lua;
+use serde_json::json;
+
+static TAGS_SCRIPT: &str = include_str!("tags.lua");
+
+#[test]
+fn should_parse_tags() {
+    let vm = Lua::new();
+    let mut form = Form::new(TAGS_SCRIPT, json!({}), &vm).unwrap();
+    let question = form.first_question().unwrap();
+    assert_eq!(question.tags(), ["pii".to_string()]);
+    assert!(question.has_tag("pii"));
+    assert!(!question.has_tag("demographics"));
+
+    let poll = form
+        .progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    let FormPoll::Question { question, .. } = poll else {
+        panic!("expected question");
+    };
+    assert_eq!(
+        question.tags(),
+        ["pii".to_string(), "demographics".to_string()]
+    );
+
+    // They're optional
+    let poll = form
+        .progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+    let FormPoll::Question { question, .. } = poll else {
+        panic!("expected question");
+    };
+    assert!(question.tags().is_empty());
+}
+
+#[test]
+fn should_filter_history_by_tag() {
+    let vm = Lua::new();
+    let mut form = Form::new(TAGS_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, Answer::Text("25".to_string()))
+        .unwrap();
+    form.progress_with_answer(2, Answer::Text("Lovely".to_string()))
+        .unwrap();
+
+    assert_eq!(
+        form.history_filtered("pii")
+            .map(|(id, _, _)| id)
+            .collect::<Vec<_>>(),
+        vec!["name", "age"]
+    );
+    assert_eq!(
+        form.history_filtered("demographics")
+            .map(|(id, _, answer)| (id, answer.clone()))
+            .collect::<Vec<_>>(),
+        vec![("age", Answer::Text("25".to_string()))]
+    );
+    assert_eq!(form.history_filtered("nonexistent").count(), 0);
+}
+
+#[test]
+fn should_reject_invalid_tags() {
+    let vm = Lua::new();
+    let script = TAGS_SCRIPT.replace(r#"tags = { "pii" }"#, r#"tags = "pii""#);
+    assert!(matches!(
+        Form::new(&script, json!({}), &vm),
+        Err(Error::InvalidTagsProperty { .. })
+    ));
+}
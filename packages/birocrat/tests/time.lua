@@ -0,0 +1,29 @@
+-- A form that asks for a time within working hours, and then for one with seconds
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "meeting",
+				type = "time",
+				text = "When should the meeting start?",
+				default = "09:30",
+				min = "09:00",
+				max = "17:00",
+			},
+			{},
+		}
+	elseif state.meeting == nil then
+		return {
+			"question",
+			{ id = "alarm", type = "time", text = "When should the alarm go off?", seconds = true },
+			{ meeting = answer.time },
+		}
+	else
+		return {
+			"done",
+			{ meeting = state.meeting, alarm = { answer.hour, answer.minute, answer.second } },
+			nil,
+		}
+	end
+end
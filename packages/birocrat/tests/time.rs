@@ -0,0 +1,73 @@
+use birocrat::*;
+use chrono::NaiveTime;
+use mlua::Lua;
+use serde_json::json;
+
+static TIME_SCRIPT: &str = include_str!("time.lua");
+
+fn time(h: u32, m: u32, s: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, s).unwrap()
+}
+
+#[test]
+fn should_validate_times() {
+    let vm = Lua::new();
+    let mut form = Form::new(TIME_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Time {
+            prompt: "When should the meeting start?".to_string(),
+            default: Some(time(9, 30, 0)),
+            min: Some(time(9, 0, 0)),
+            max: Some(time(17, 0, 0)),
+            seconds: false,
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Invalid and out-of-range times should be rejected before the script sees them, as should
+    // seconds when the question doesn't allow them
+    for text in ["half nine", "25:00", "10:15:30"] {
+        assert_eq!(
+            form.progress_with_answer(0, Answer::Text(text.to_string()))
+                .unwrap(),
+            FormPoll::Error("Please enter a time in the format HH:MM.".to_string())
+        );
+    }
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Time(time(17, 30, 0)))
+            .unwrap(),
+        FormPoll::Error("Please enter a time no later than 17:00:00.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    // Text answers should be parsed, including with whole-minute seconds
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text(" 10:15:00 ".to_string()))
+            .unwrap(),
+        FormPoll::Question { .. }
+    ));
+    assert_eq!(
+        form.answers().next().unwrap().2,
+        &Answer::Time(time(10, 15, 0))
+    );
+
+    // The second question allows seconds
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("7:05".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert!(matches!(
+        form.progress_with_answer(1, Answer::Text("07:05:42".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "meeting": "10:15:00", "alarm": [7, 5, 42] })
+    );
+}
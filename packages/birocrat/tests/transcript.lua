@@ -0,0 +1,24 @@
+-- A form that asks for the user's name and where they'd like to travel, finishing with a summary
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{ id = "name", type = "simple", text = "What's your name?" },
+			{ step = "name" },
+		}
+	elseif state.step == "name" then
+		return {
+			"question",
+			{
+				id = "destinations",
+				type = "select",
+				text = "Where would you like to go?",
+				options = { "Japan", "Peru", "Norway" },
+				multiple = true,
+			},
+			{ step = "destinations", name = answer.text },
+		}
+	else
+		return { "done", { name = state.name, destinations = answer.selected }, nil }
+	end
+end
@@ -0,0 +1,51 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static TRANSCRIPT_SCRIPT: &str = include_str!("transcript.lua");
+
+#[test]
+fn should_record_questions_and_answers_in_order() {
+    let vm = Lua::new();
+    let mut form = Form::new(TRANSCRIPT_SCRIPT, json!({}), &vm).unwrap();
+    assert!(form.transcript().entries.is_empty());
+
+    let destinations = Answer::Options(vec!["Japan".to_string(), "Peru".to_string()]);
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+    form.progress_with_answer(1, destinations.clone()).unwrap();
+
+    let transcript = form.transcript();
+    assert_eq!(
+        transcript
+            .entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry.question.prompt()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("name", "What's your name?"),
+            ("destinations", "Where would you like to go?")
+        ]
+    );
+    assert_eq!(
+        transcript.entries[0].answer,
+        Answer::Text("Alice".to_string())
+    );
+    assert_eq!(transcript.entries[1].answer, destinations);
+    assert_eq!(transcript.seed, form.seed());
+}
+
+#[test]
+fn should_round_trip_transcripts_through_json() {
+    let vm = Lua::new();
+    let mut form = Form::new(TRANSCRIPT_SCRIPT, json!({}), &vm).unwrap();
+    form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+        .unwrap();
+
+    let transcript = form.transcript();
+    let json = serde_json::to_string(&transcript).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Transcript>(&json).unwrap(),
+        transcript
+    );
+}
@@ -0,0 +1,17 @@
+-- A form that asks for a website, which must be served over HTTP(S)
+function Main(state, answer, params)
+	if state == nil then
+		return {
+			"question",
+			{
+				id = "website",
+				type = "url",
+				text = "What is your website?",
+				schemes = { "HTTPS", "http:" },
+			},
+			{},
+		}
+	else
+		return { "done", { website = answer.text }, nil }
+	end
+end
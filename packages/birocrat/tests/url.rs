@@ -0,0 +1,61 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+
+static URL_SCRIPT: &str = include_str!("url.lua");
+
+#[test]
+fn should_validate_urls() {
+    let vm = Lua::new();
+    let mut form = Form::new(URL_SCRIPT, json!({}), &vm).unwrap();
+    assert_eq!(
+        form.first_question().unwrap(),
+        &Question::Url {
+            prompt: "What is your website?".to_string(),
+            default: None,
+            schemes: vec!["https".to_string(), "http".to_string()],
+            required: true,
+            common: QuestionCommon::default(),
+        }
+    );
+
+    // Relative URLs have nothing to be resolved against
+    for url in ["example.com", "/about", "https://"] {
+        assert_eq!(
+            form.progress_with_answer(0, Answer::Text(url.to_string()))
+                .unwrap(),
+            FormPoll::Error("Please enter a full URL, like https://example.com.".to_string()),
+            "{url} should be rejected"
+        );
+    }
+    assert_eq!(
+        form.progress_with_answer(0, Answer::Text("ftp://example.com".to_string()))
+            .unwrap(),
+        FormPoll::Error("Please enter a URL using one of these schemes: https, http.".to_string())
+    );
+    assert!(form
+        .progress_with_answer(0, Answer::Options(Vec::new()))
+        .is_err());
+
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text(" HTTPS://example.com/about ".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+    assert_eq!(
+        form.into_done().unwrap(),
+        json!({ "website": "HTTPS://example.com/about" })
+    );
+}
+
+#[test]
+fn should_allow_any_scheme_by_default() {
+    let vm = Lua::new();
+    let script = URL_SCRIPT.replace(r#"schemes = { "HTTPS", "http:" },"#, "");
+    let mut form = Form::new(&script, json!({}), &vm).unwrap();
+    assert!(matches!(
+        form.progress_with_answer(0, Answer::Text("mailto:alice@example.com".to_string()))
+            .unwrap(),
+        FormPoll::Done(_)
+    ));
+}
@@ -0,0 +1,18 @@
+-- A form that tries out two ways of asking for the user's name
+function Main(state, answer, params)
+	if state == nil then
+		local variant = birocrat.variant("greeting", { "formal", "casual" })
+		local prompt = variant == "formal" and "What is your name?" or "What should we call you?"
+		return {
+			"question",
+			{ id = "name", type = "simple", text = prompt },
+			{},
+		}
+	else
+		return {
+			"done",
+			{ name = answer.text, greeting = birocrat.variant("greeting", { "formal", "casual" }) },
+			nil,
+		}
+	end
+end
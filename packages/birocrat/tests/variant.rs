@@ -0,0 +1,71 @@
+use birocrat::*;
+use mlua::Lua;
+use serde_json::json;
+use std::collections::HashMap;
+
+static VARIANT_SCRIPT: &str = include_str!("variant.lua");
+
+/// Starts the form with the given seed and variants assigned by the host.
+fn start<'l>(seed: u64, variants: &[(&str, &str)], vm: &'l Lua) -> Result<Form<'l>, error::Error> {
+    let options = FormOptions {
+        seed: Some(seed),
+        variants: variants
+            .iter()
+            .map(|(experiment, variant)| (experiment.to_string(), variant.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    Form::new_with_options(VARIANT_SCRIPT, json!({}), vm, options)
+}
+
+#[test]
+fn should_assign_variants_from_seed() {
+    let vm = Lua::new();
+    let variants = (0..20)
+        .map(|seed| {
+            let form = start(seed, &[], &vm).unwrap();
+            let variant = form.variants()["greeting"].clone();
+            // The same seed should always give the same variant
+            assert_eq!(
+                start(seed, &[], &vm).unwrap().variants()["greeting"],
+                variant
+            );
+            variant
+        })
+        .collect::<Vec<_>>();
+    for variant in ["formal", "casual"] {
+        assert!(variants.iter().any(|assigned| assigned == variant));
+    }
+}
+
+#[test]
+fn should_use_variants_from_host() {
+    let vm = Lua::new();
+    for seed in 0..5 {
+        let mut form = start(seed, &[("greeting", "casual")], &vm).unwrap();
+        assert_eq!(
+            form.first_question().unwrap().prompt(),
+            "What should we call you?"
+        );
+
+        form.progress_with_answer(0, Answer::Text("Alice".to_string()))
+            .unwrap();
+        assert_eq!(
+            form.transcript().variants,
+            HashMap::from([("greeting".to_string(), "casual".to_string())])
+        );
+        assert_eq!(
+            form.into_done().unwrap(),
+            json!({ "name": "Alice", "greeting": "casual" })
+        );
+    }
+}
+
+#[test]
+fn should_reject_unknown_variants_from_host() {
+    let vm = Lua::new();
+    assert!(matches!(
+        start(0, &[("greeting", "shouty")], &vm),
+        Err(error::Error::RunDriverFailed { .. })
+    ));
+}